@@ -0,0 +1,39 @@
+/// Where an API key is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+}
+
+/// Authentication applied to a request at send time.
+/// Declared in `.http` files with a `# @auth` directive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Auth {
+    Basic { username: String, password: String },
+    Bearer { token: String },
+    /// Sent after the server's challenge (RFC 7616).
+    Digest { username: String, password: String },
+    ApiKey { location: ApiKeyLocation, name: String, value: String },
+}
+
+impl Auth {
+    /// Returns a copy with `f` applied to every user supplied value.
+    pub fn map_values(&self, f: impl Fn(&str) -> String) -> Auth {
+        match self {
+            Auth::Basic { username, password } => Auth::Basic {
+                username: f(username),
+                password: f(password),
+            },
+            Auth::Bearer { token } => Auth::Bearer { token: f(token) },
+            Auth::Digest { username, password } => Auth::Digest {
+                username: f(username),
+                password: f(password),
+            },
+            Auth::ApiKey { location, name, value } => Auth::ApiKey {
+                location: location.clone(),
+                name: f(name),
+                value: f(value),
+            },
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+/// The body of a request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Body {
+    Text(String),
+    /// Contents of a file (`< ./path`), streamed when sent.
+    File(PathBuf),
+    /// `multipart/form-data` parts. The boundary is the one written in the file.
+    Multipart { boundary: String, parts: Vec<Part> },
+    /// `application/x-www-form-urlencoded` fields, written as `key=value` lines or joined by `&`.
+    /// Values are held decoded; the file may percent-encode them.
+    Form(Vec<(String, String)>),
+    /// A GraphQL query with its optional JSON variables, sent as a JSON POST.
+    GraphQL { query: String, variables: Option<String> },
+    /// Predefined messages of a WebSocket request, separated by `===` lines.
+    Messages(Vec<String>),
+}
+
+/// A single part of a multipart body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub content: PartContent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartContent {
+    Text(String),
+    File(PathBuf),
+}
+
+impl Body {
+    /// Returns the text of a plain text body.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Body::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy with `f` applied to all user written text, including file paths.
+    pub fn map_text(&self, f: impl Fn(&str) -> String) -> Body {
+        let map_path = |path: &PathBuf| PathBuf::from(f(&path.to_string_lossy()));
+        match self {
+            Body::Text(text) => Body::Text(f(text)),
+            Body::File(path) => Body::File(map_path(path)),
+            Body::Multipart { boundary, parts } => Body::Multipart {
+                boundary: boundary.clone(),
+                parts: parts
+                    .iter()
+                    .map(|part| Part {
+                        name: f(&part.name),
+                        filename: part.filename.as_deref().map(&f),
+                        content_type: part.content_type.clone(),
+                        content: match &part.content {
+                            PartContent::Text(text) => PartContent::Text(f(text)),
+                            PartContent::File(path) => PartContent::File(map_path(path)),
+                        },
+                    })
+                    .collect(),
+            },
+            Body::Form(fields) => Body::Form(fields.iter().map(|(k, v)| (f(k), f(v))).collect()),
+            Body::GraphQL { query, variables } => Body::GraphQL {
+                query: f(query),
+                variables: variables.as_deref().map(&f),
+            },
+            Body::Messages(messages) => Body::Messages(messages.iter().map(|m| f(m)).collect()),
+        }
+    }
+
+    /// Returns a copy with `f` applied to every file path, e.g. to resolve relative paths.
+    pub fn map_paths(&self, f: impl Fn(&PathBuf) -> PathBuf) -> Body {
+        match self {
+            Body::File(path) => Body::File(f(path)),
+            Body::Multipart { boundary, parts } => Body::Multipart {
+                boundary: boundary.clone(),
+                parts: parts
+                    .iter()
+                    .map(|part| Part {
+                        content: match &part.content {
+                            PartContent::File(path) => PartContent::File(f(path)),
+                            content => content.clone(),
+                        },
+                        ..part.clone()
+                    })
+                    .collect(),
+            },
+            body => body.clone(),
+        }
+    }
+}
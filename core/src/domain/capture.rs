@@ -0,0 +1,221 @@
+use super::environment::Environment;
+use super::mock::url_path;
+use super::request::Request;
+use super::response::Response;
+use std::time::SystemTime;
+
+/// Headers of a proxied call that only describe its connection and are not worth recording.
+pub const UNRECORDED_HEADERS: [&str; 9] = [
+    "host",
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "content-length",
+];
+
+/// Request headers carrying credentials, recorded as secret variables rather than verbatim.
+pub const CREDENTIAL_HEADERS: [&str; 2] = ["authorization", "cookie"];
+/// Response headers carrying credentials, which are not recorded.
+pub const UNRECORDED_RESPONSE_HEADERS: [&str; 1] = ["set-cookie"];
+
+/// A call passed through the recording proxy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedCall {
+    /// The request as sent upstream, with an absolute URL.
+    pub request: Request,
+    pub response: Response,
+}
+
+/// A call read from, or written to, an HTTP archive (HAR).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedCall {
+    pub started: SystemTime,
+    pub call: CapturedCall,
+}
+
+/// Which calls are recorded or imported. Empty lists match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptureFilter {
+    /// Hosts, e.g. `api.example.com`. `*.example.com` also matches subdomains.
+    pub hosts: Vec<String>,
+    /// Path prefixes, e.g. `/v1/users`.
+    pub paths: Vec<String>,
+    pub methods: Vec<String>,
+    /// Parts of the response content type, e.g. `json`.
+    pub content_types: Vec<String>,
+}
+
+impl CaptureFilter {
+    pub fn matches(&self, call: &CapturedCall) -> bool {
+        let Some(host) = url_host(&call.request.url) else {
+            return false;
+        };
+        let path = url_path(&call.request.url);
+        let host_matches = self.hosts.is_empty()
+            || self.hosts.iter().any(|pattern| {
+                let pattern = pattern.to_lowercase();
+                match pattern.strip_prefix("*.") {
+                    Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+                    None => host == pattern,
+                }
+            });
+        let path_matches = self.paths.is_empty() || self.paths.iter().any(|prefix| path.starts_with(prefix.as_str()));
+        let method_matches = self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(&call.request.method));
+        let content_type = call
+            .response
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.to_lowercase())
+            .unwrap_or_default();
+        let content_type_matches =
+            self.content_types.is_empty() || self.content_types.iter().any(|t| content_type.contains(&t.to_lowercase()));
+        host_matches && path_matches && method_matches && content_type_matches
+    }
+}
+
+/// Names the request file of a captured call after its method and path, e.g. `get_users_42`.
+/// A numeric suffix keeps it apart from `existing` requests.
+pub fn request_id(method: &str, url: &str, existing: &[String]) -> String {
+    let path = without_escapes(&url_path(url));
+    // Methods come from recordings and archives of third parties, so only their letters count
+    let method: String = method.chars().filter(char::is_ascii_alphanumeric).collect();
+    let mut id = if method.is_empty() { "request".to_string() } else { method.to_lowercase() };
+    for segment in path.split(|c: char| !c.is_ascii_alphanumeric()).filter(|s| !s.is_empty()) {
+        id.push('_');
+        id.push_str(&segment.to_lowercase());
+    }
+    if !existing.contains(&id) {
+        return id;
+    }
+    (2..).map(|n| format!("{}_{}", id, n)).find(|candidate| !existing.contains(candidate)).unwrap_or(id)
+}
+
+/// The lowercase host of an absolute URL, without credentials or port; None without a scheme.
+fn url_host(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    Some(host.to_lowercase())
+}
+
+/// Replaces percent escapes such as `%7B` by `_`: in request ids they separate words, like any other punctuation.
+fn without_escapes(path: &str) -> String {
+    let mut text = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(i) = rest.find('%') {
+        text.push_str(&rest[..i]);
+        let escape = rest.as_bytes().get(i + 1..i + 3).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        text.push(if escape { '_' } else { '%' });
+        rest = &rest[i + if escape { 3 } else { 1 }..];
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Replaces the credential headers of a recorded request with placeholders such as `{{authorization}}`,
+/// returning the variables to store as secrets. A value differing from the one `environment`
+/// already holds gets a numbered name, e.g. `{{cookie_2}}`.
+pub fn extract_credentials(request: &mut Request, environment: &Environment) -> Vec<(String, String)> {
+    let mut names: Vec<String> =
+        request.headers.keys().filter(|k| CREDENTIAL_HEADERS.contains(&k.to_lowercase().as_str())).cloned().collect();
+    names.sort();
+    let mut secrets: Vec<(String, String)> = Vec::new();
+    for name in names {
+        let value = request.headers[&name].clone();
+        let stored = |variable: &str| {
+            secrets.iter().find(|(n, _)| n == variable).map(|(_, v)| v.clone()).or_else(|| environment.get(variable))
+        };
+        let base = name.to_lowercase();
+        let variable = std::iter::once(base.clone())
+            .chain((2..).map(|n| format!("{}_{}", base, n)))
+            .find(|candidate| stored(candidate).is_none_or(|v| v == value))
+            .unwrap_or(base);
+        if stored(&variable).is_none() {
+            secrets.push((variable.clone(), value));
+        }
+        request.headers.insert(name, format!("{{{{{}}}}}", variable));
+    }
+    secrets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn call(method: &str, url: &str, content_type: &str) -> CapturedCall {
+        let mut response = Response::new(200, "OK".to_string(), String::new(), std::time::Duration::ZERO);
+        response.headers.insert("Content-Type".to_string(), content_type.to_string());
+        CapturedCall { request: Request::new(method, url), response }
+    }
+
+    #[test]
+    fn test_filter() {
+        let all = CaptureFilter::default();
+        assert!(all.matches(&call("GET", "https://api.example.com/v1/users", "text/html")));
+
+        let filter = CaptureFilter {
+            hosts: vec!["*.example.com".to_string()],
+            paths: vec!["/v1/".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches(&call("GET", "https://api.example.com:8443/v1/users?page=2", "")));
+        assert!(filter.matches(&call("GET", "http://example.com/v1/", "")));
+        assert!(!filter.matches(&call("GET", "https://api.example.com/v2/users", "")));
+        assert!(!filter.matches(&call("GET", "https://example.org/v1/users", "")));
+        assert!(!filter.matches(&call("GET", "not a url", "")));
+        assert!(filter.matches(&call("GET", "https://user:pw@API.example.com/v1/users", "")));
+        assert_eq!(url_host("http://[::1]:8080/v1").as_deref(), Some("::1"));
+
+        let filter = CaptureFilter { methods: vec!["post".to_string()], content_types: vec!["JSON".to_string()], ..Default::default() };
+        assert!(filter.matches(&call("POST", "https://example.com/users", "application/json; charset=utf-8")));
+        assert!(!filter.matches(&call("GET", "https://example.com/users", "application/json")));
+        assert!(!filter.matches(&call("POST", "https://example.com/logo.png", "image/png")));
+    }
+
+    #[test]
+    fn test_extract_credentials() {
+        let environment = Environment::new(HashMap::new(), HashMap::from([("cookie".to_string(), "sid=old".to_string())]));
+        let mut request = Request::new("GET", "https://api.example.com/me");
+        request.headers.insert("Authorization".to_string(), "Bearer abc".to_string());
+        request.headers.insert("cookie".to_string(), "sid=new".to_string());
+        request.headers.insert("accept".to_string(), "*/*".to_string());
+
+        let secrets = extract_credentials(&mut request, &environment);
+        assert_eq!(
+            secrets,
+            vec![("authorization".to_string(), "Bearer abc".to_string()), ("cookie_2".to_string(), "sid=new".to_string())]
+        );
+        assert_eq!(request.headers["Authorization"], "{{authorization}}");
+        assert_eq!(request.headers["cookie"], "{{cookie_2}}");
+        assert_eq!(request.headers["accept"], "*/*");
+
+        // A value already stored is reused
+        let mut again = Request::new("GET", "https://api.example.com/me");
+        again.headers.insert("Cookie".to_string(), "sid=old".to_string());
+        assert!(extract_credentials(&mut again, &environment).is_empty());
+        assert_eq!(again.headers["Cookie"], "{{cookie}}");
+    }
+
+    #[test]
+    fn test_request_id() {
+        assert_eq!(request_id("GET", "https://api.example.com/users/42?x=1", &[]), "get_users_42");
+        assert_eq!(request_id("POST", "http://localhost:8080/", &[]), "post");
+        assert_eq!(request_id("GET", "https://api.example.com/users/{{id}}", &[]), "get_users_id");
+        assert_eq!(request_id("../../../x", "https://api.example.com/users", &[]), "x_users");
+        assert_eq!(request_id("GET", "https://api.example.com/files/a%20b%2Fc/%zz", &[]), "get_files_a_b_c_zz");
+        assert_eq!(request_id("GET", "https://api.example.com/wiki/café", &[]), "get_wiki_caf");
+        assert_eq!(request_id("/..", "https://api.example.com/", &[]), "request");
+        let existing = vec!["get_users".to_string(), "get_users_2".to_string()];
+        assert_eq!(request_id("GET", "https://api.example.com/users", &existing), "get_users_3");
+    }
+}
@@ -0,0 +1,50 @@
+use super::request::Request;
+
+/// Requests and variables imported from another API client (Postman, Insomnia).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Collection {
+    pub name: String,
+    pub items: Vec<CollectionItem>,
+    /// Collection and environment variables, in the order they were declared.
+    pub variables: Vec<(String, String)>,
+    /// Variables marked secret (or private) in the source.
+    pub secrets: Vec<(String, String)>,
+    /// Parts of the source that could not be imported, e.g. pre-request scripts.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectionItem {
+    Folder { name: String, items: Vec<CollectionItem> },
+    Request { name: String, request: Box<Request> },
+}
+
+/// Turns a display name into a file name: `Get user (v2)` becomes `get_user_v2`.
+pub fn file_name(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("Get user (v2)"), "get_user_v2");
+        assert_eq!(file_name("  Ünïcode -- Folder "), "ünïcode_folder");
+        assert_eq!(file_name("!!!"), "untitled");
+    }
+}
@@ -0,0 +1,354 @@
+use super::response::Response;
+use serde_json::{Map, Value};
+
+/// One line of a line-based diff.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// A change found by the structural JSON diff, at a path like `$.items[0].name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+}
+
+/// Differences between two responses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseDiff {
+    /// Old and new status code.
+    pub status: (u16, u16),
+    /// Line diff of the headers as `name: value`, sorted by name.
+    pub headers: Vec<DiffLine>,
+    /// Structural changes; empty when a body is not JSON.
+    pub changes: Vec<JsonChange>,
+    /// Line diff of the bodies, pretty-printed with sorted keys when JSON.
+    pub lines: Vec<DiffLine>,
+}
+
+impl ResponseDiff {
+    pub fn is_empty(&self) -> bool {
+        let same = |lines: &[DiffLine]| lines.iter().all(|l| matches!(l, DiffLine::Same(_)));
+        self.status.0 == self.status.1 && same(&self.headers) && same(&self.lines)
+    }
+}
+
+/// Headers that change on every response and are never compared.
+const VOLATILE_HEADERS: [&str; 1] = ["date"];
+
+/// Compares two responses. `ignore` lists volatile fields to leave out, e.g. `$.meta.requestId`
+/// (anchored at the root), `items[*].updatedAt` or `timestamp` (matching at any depth).
+/// Headers named in `ignore` are left out too.
+pub fn diff_responses(old: &Response, new: &Response, ignore: &[String]) -> ResponseDiff {
+    let patterns: Vec<Pattern> = ignore.iter().map(|p| Pattern::parse(p)).collect();
+    let json = serde_json::from_str::<Value>(&old.body).ok().zip(serde_json::from_str::<Value>(&new.body).ok());
+
+    let (changes, lines) = match json {
+        Some((old_json, new_json)) => {
+            let old_json = canonical(&old_json, &mut Vec::new(), &patterns, None);
+            let new_json = canonical(&new_json, &mut Vec::new(), &patterns, None);
+            let mut changes = Vec::new();
+            diff_values(&mut Vec::new(), &old_json, &new_json, &mut changes);
+            let pretty = |v: &Value| serde_json::to_string_pretty(v).unwrap_or_default();
+            (changes, diff_lines(&pretty(&old_json), &pretty(&new_json)))
+        }
+        None => (Vec::new(), diff_lines(&old.body, &new.body)),
+    };
+    let headers = diff_lines(&header_lines(old, ignore), &header_lines(new, ignore));
+    ResponseDiff { status: (old.status, new.status), headers, changes, lines }
+}
+
+fn header_lines(response: &Response, ignore: &[String]) -> String {
+    let mut headers: Vec<String> = response
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .filter(|(name, _)| !VOLATILE_HEADERS.contains(&name.as_str()) && !ignore.iter().any(|i| i.eq_ignore_ascii_case(name)))
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+    headers.sort();
+    headers.join("\n")
+}
+
+/// Above this many line pairs the diff degrades to "all removed, all added".
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Line diff based on the longest common subsequence.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() * new.len() > MAX_LCS_CELLS {
+        let removed = old.iter().map(|l| DiffLine::Removed(l.to_string()));
+        return removed.chain(new.iter().map(|l| DiffLine::Added(l.to_string()))).collect();
+    }
+
+    // lcs[i][j]: length of the common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    lines.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    lines
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut formatted = "$".to_string();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                formatted.push('.');
+                formatted.push_str(key);
+            }
+            Segment::Index(i) => formatted.push_str(&format!("[{}]", i)),
+        }
+    }
+    formatted
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PatternSegment {
+    Key(String),
+    AnyKey,
+    Index(usize),
+    AnyIndex,
+}
+
+/// A field to ignore: a dotted path with `*` and `[*]` wildcards.
+struct Pattern {
+    anchored: bool,
+    segments: Vec<PatternSegment>,
+}
+
+impl Pattern {
+    fn parse(pattern: &str) -> Self {
+        let anchored = pattern.starts_with('$');
+        let mut segments = Vec::new();
+        for part in pattern.trim_start_matches('$').split('.').filter(|p| !p.is_empty()) {
+            let (key, indices) = part.split_once('[').unwrap_or((part, ""));
+            match key {
+                "" => {}
+                "*" => segments.push(PatternSegment::AnyKey),
+                key => segments.push(PatternSegment::Key(key.to_string())),
+            }
+            for index in indices.split('[').map(|i| i.trim_end_matches(']')).filter(|i| !i.is_empty()) {
+                segments.push(match index.parse() {
+                    Ok(i) => PatternSegment::Index(i),
+                    Err(_) => PatternSegment::AnyIndex,
+                });
+            }
+        }
+        Self { anchored, segments }
+    }
+
+    fn matches(&self, path: &[Segment]) -> bool {
+        if self.segments.is_empty() || path.len() < self.segments.len() || (self.anchored && path.len() != self.segments.len()) {
+            return false;
+        }
+        let tail = &path[path.len() - self.segments.len()..];
+        self.segments.iter().zip(tail).all(|(pattern, segment)| match (pattern, segment) {
+            (PatternSegment::Key(p), Segment::Key(k)) => p == k,
+            (PatternSegment::AnyKey, Segment::Key(_)) => true,
+            (PatternSegment::Index(p), Segment::Index(i)) => p == i,
+            (PatternSegment::AnyIndex, Segment::Index(_)) => true,
+            _ => false,
+        })
+    }
+}
+
+/// Sorts object keys and replaces the given fields (same syntax as diff ignores) with `mask`.
+pub fn mask_fields(value: &Value, fields: &[String], mask: &str) -> Value {
+    let patterns: Vec<Pattern> = fields.iter().map(|p| Pattern::parse(p)).collect();
+    canonical(value, &mut Vec::new(), &patterns, Some(&Value::String(mask.to_string())))
+}
+
+/// Sorts object keys, so key order never shows up as a change, and drops (or masks) ignored fields.
+fn canonical(value: &Value, path: &mut Vec<Segment>, ignore: &[Pattern], mask: Option<&Value>) -> Value {
+    let ignored = |path: &[Segment]| ignore.iter().any(|p| p.matches(path));
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut sorted = Map::new();
+            for key in keys {
+                path.push(Segment::Key(key.clone()));
+                match (ignored(path), mask) {
+                    (false, _) => {
+                        sorted.insert(key.clone(), canonical(&map[key], path, ignore, mask));
+                    }
+                    (true, Some(mask)) => {
+                        sorted.insert(key.clone(), mask.clone());
+                    }
+                    (true, None) => {}
+                }
+                path.pop();
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => {
+            let mut kept = Vec::new();
+            for (i, item) in items.iter().enumerate() {
+                path.push(Segment::Index(i));
+                match (ignored(path), mask) {
+                    (false, _) => kept.push(canonical(item, path, ignore, mask)),
+                    (true, Some(mask)) => kept.push(mask.clone()),
+                    (true, None) => {}
+                }
+                path.pop();
+            }
+            Value::Array(kept)
+        }
+        other => other.clone(),
+    }
+}
+
+fn diff_values(path: &mut Vec<Segment>, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys().filter(|k| !old_map.contains_key(*k))).collect();
+            keys.sort();
+            for key in keys {
+                path.push(Segment::Key(key.clone()));
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(o), Some(n)) => diff_values(path, o, n, changes),
+                    (Some(o), None) => changes.push(JsonChange::Removed { path: format_path(path), value: o.clone() }),
+                    (None, Some(n)) => changes.push(JsonChange::Added { path: format_path(path), value: n.clone() }),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for i in 0..old_items.len().max(new_items.len()) {
+                path.push(Segment::Index(i));
+                match (old_items.get(i), new_items.get(i)) {
+                    (Some(o), Some(n)) => diff_values(path, o, n, changes),
+                    (Some(o), None) => changes.push(JsonChange::Removed { path: format_path(path), value: o.clone() }),
+                    (None, Some(n)) => changes.push(JsonChange::Added { path: format_path(path), value: n.clone() }),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        (o, n) if o != n => changes.push(JsonChange::Changed { path: format_path(path), old: o.clone(), new: n.clone() }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn response(status: u16, body: &str) -> Response {
+        Response::new(status, String::new(), body.to_string(), Duration::ZERO)
+    }
+
+    #[test]
+    fn test_key_order_is_not_a_change() {
+        let diff = diff_responses(&response(200, r#"{"a": 1, "b": [1, 2]}"#), &response(200, r#"{"b": [1, 2], "a": 1}"#), &[]);
+        assert!(diff.is_empty());
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn test_structural_changes() {
+        let old = response(200, r#"{"user": {"name": "a", "age": 1}, "tags": ["x"]}"#);
+        let new = response(201, r#"{"user": {"name": "b", "email": "e"}, "tags": ["x", "y"]}"#);
+        let diff = diff_responses(&old, &new, &[]);
+
+        assert!(!diff.is_empty());
+        assert_eq!(diff.status, (200, 201));
+        assert_eq!(
+            diff.changes,
+            vec![
+                JsonChange::Added { path: "$.tags[1]".to_string(), value: json!("y") },
+                JsonChange::Removed { path: "$.user.age".to_string(), value: json!(1) },
+                JsonChange::Added { path: "$.user.email".to_string(), value: json!("e") },
+                JsonChange::Changed { path: "$.user.name".to_string(), old: json!("a"), new: json!("b") },
+            ]
+        );
+        assert!(diff.lines.contains(&DiffLine::Removed("    \"name\": \"a\"".to_string())));
+        assert!(diff.lines.contains(&DiffLine::Added("    \"name\": \"b\"".to_string())));
+    }
+
+    #[test]
+    fn test_ignored_fields() {
+        let old = response(200, r#"{"meta": {"requestId": "1", "at": 5}, "items": [{"id": 1, "updatedAt": 10}], "at": 1}"#);
+        let new = response(200, r#"{"meta": {"requestId": "2", "at": 5}, "items": [{"id": 1, "updatedAt": 20}], "at": 2}"#);
+
+        let ignore = ["$.meta.requestId".to_string(), "items[*].updatedAt".to_string(), "$.at".to_string()];
+        assert!(diff_responses(&old, &new, &ignore).is_empty());
+
+        // Unanchored patterns match at any depth, anchored ones only at the root
+        let diff = diff_responses(&old, &new, &["requestId".to_string(), "updatedAt".to_string()]);
+        assert_eq!(diff.changes, vec![JsonChange::Changed { path: "$.at".to_string(), old: json!(1), new: json!(2) }]);
+    }
+
+    #[test]
+    fn test_header_changes() {
+        let with_headers = |headers: &[(&str, &str)]| {
+            let mut response = response(200, "{}");
+            response.headers = headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            response
+        };
+        let old = with_headers(&[("Content-Type", "application/json"), ("ETag", "1"), ("Date", "Mon"), ("X-Request-Id", "a")]);
+        let new = with_headers(&[("content-type", "application/json"), ("ETag", "2"), ("Date", "Tue"), ("X-Request-Id", "b")]);
+
+        let diff = diff_responses(&old, &new, &["x-request-id".to_string()]);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.headers,
+            vec![
+                DiffLine::Same("content-type: application/json".to_string()),
+                DiffLine::Removed("etag: 1".to_string()),
+                DiffLine::Added("etag: 2".to_string()),
+            ]
+        );
+        assert!(diff_responses(&old, &new, &["etag".to_string(), "x-request-id".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_text_bodies_use_line_diff() {
+        let diff = diff_responses(&response(200, "a\nb\nc"), &response(200, "a\nc\nd"), &[]);
+        assert!(diff.changes.is_empty());
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Same("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Same("c".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use super::request::Request;
+use super::response::Response;
+
+/// Placeholder shown instead of a secret value.
+pub const REDACTED: &str = "******";
+
+/// Variables available to the requests of a project.
+/// Secrets are kept apart from plain variables so they can be masked
+/// wherever htup displays or persists data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Environment {
+    pub variables: HashMap<String, String>,
+    pub secrets: HashMap<String, String>,
+}
+
+impl Environment {
+    pub fn new(variables: HashMap<String, String>, secrets: HashMap<String, String>) -> Self {
+        Self { variables, secrets }
+    }
+
+    /// Returns a copy with an additional secret, e.g. an acquired token.
+    pub fn with_secret(&self, name: impl Into<String>, value: impl Into<String>) -> Environment {
+        let mut env = self.clone();
+        env.secrets.insert(name.into(), value.into());
+        env
+    }
+
+    /// Looks up a variable. Secrets take precedence over plain variables, and
+    /// plain variables may themselves reference secrets with `{{name}}`.
+    pub fn get(&self, name: &str) -> Option<String> {
+        if let Some(secret) = self.secrets.get(name) {
+            return Some(secret.clone());
+        }
+        self.variables
+            .get(name)
+            .map(|value| replace_placeholders(value, |key| self.secrets.get(key).cloned()))
+    }
+
+    /// Replaces every known `{{name}}` placeholder in `text`.
+    /// Unknown placeholders are left untouched.
+    pub fn substitute(&self, text: &str) -> String {
+        replace_placeholders(text, |key| self.get(key))
+    }
+
+    /// Returns a copy of the request with all placeholders resolved.
+    pub fn apply(&self, request: &Request) -> Request {
+        let mut resolved = request.clone();
+        resolved.url = self.substitute(&request.url);
+        resolved.headers = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), self.substitute(v)))
+            .collect();
+        resolved.body = request.body.as_ref().map(|b| b.map_text(|t| self.substitute(t)));
+        resolved.auth = request.auth.as_ref().map(|a| a.map_values(|v| self.substitute(v)));
+        resolved
+    }
+
+    /// Masks every secret value occurring in `text`.
+    pub fn redact(&self, text: &str) -> String {
+        let mut values: Vec<&String> = self.secrets.values().filter(|v| !v.is_empty()).collect();
+        // Longest first, so a secret containing another one is masked as a whole
+        values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+
+        let mut redacted = text.to_string();
+        for value in values {
+            redacted = redacted.replace(value.as_str(), REDACTED);
+        }
+        redacted
+    }
+
+    /// Returns a copy of the request with secret values masked, suitable for display.
+    pub fn redact_request(&self, request: &Request) -> Request {
+        let mut redacted = request.clone();
+        redacted.url = self.redact(&request.url);
+        redacted.headers = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), self.redact(v)))
+            .collect();
+        redacted.body = request.body.as_ref().map(|b| b.map_text(|t| self.redact(t)));
+        redacted.auth = request.auth.as_ref().map(|a| a.map_values(|v| self.redact(v)));
+        redacted
+    }
+
+    /// Returns a copy of the response with secret values masked, suitable for storing.
+    pub fn redact_response(&self, response: &Response) -> Response {
+        let mut redacted = response.clone();
+        redacted.body = self.redact(&response.body);
+        redacted.headers = response.headers.iter().map(|(k, v)| (k.clone(), self.redact(v))).collect();
+        redacted.trailers = response.trailers.iter().map(|(k, v)| (k.clone(), self.redact(v))).collect();
+        redacted.graphql_errors = response.graphql_errors.iter().map(|e| self.redact(e)).collect();
+        redacted
+    }
+}
+
+fn replace_placeholders(text: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match lookup(key) {
+                    Some(value) => result.push_str(&value),
+                    None => result.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env() -> Environment {
+        let variables = HashMap::from([
+            ("host".to_string(), "api.example.com".to_string()),
+            ("auth".to_string(), "Bearer {{token}}".to_string()),
+        ]);
+        let secrets = HashMap::from([("token".to_string(), "s3cr3t".to_string())]);
+        Environment::new(variables, secrets)
+    }
+
+    #[test]
+    fn test_substitute_resolves_variables_and_secret_references() {
+        let env = env();
+        assert_eq!(env.substitute("https://{{host}}/users"), "https://api.example.com/users");
+        assert_eq!(env.substitute("{{ auth }}"), "Bearer s3cr3t");
+        assert_eq!(env.substitute("{{unknown}} {{"), "{{unknown}} {{");
+    }
+
+    #[test]
+    fn test_redact_masks_secret_values() {
+        let env = env();
+        let mut request = Request::new("GET", "https://{{host}}/?key={{token}}");
+        request.headers.insert("Authorization".to_string(), "{{auth}}".to_string());
+
+        let resolved = env.apply(&request);
+        assert_eq!(resolved.headers["Authorization"], "Bearer s3cr3t");
+
+        let redacted = env.redact_request(&resolved);
+        assert_eq!(redacted.url, "https://api.example.com/?key=******");
+        assert_eq!(redacted.headers["Authorization"], "Bearer ******");
+        assert_eq!(env.redact("error: s3cr3t rejected"), "error: ****** rejected");
+    }
+}
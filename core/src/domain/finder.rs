@@ -0,0 +1,162 @@
+use super::project::Project;
+
+/// A project or request the finder can jump to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinderEntry {
+    pub project: Project,
+    /// `None` for the project itself.
+    pub request_id: Option<String>,
+    /// `GET https://api.shop.com/users`, when the request could be read.
+    pub detail: Option<String>,
+}
+
+impl FinderEntry {
+    /// The name matched by default: `shop` or `shop/users/get_user`.
+    pub fn label(&self) -> String {
+        match &self.request_id {
+            Some(id) => format!("{}/{}", self.project.name, id),
+            None => self.project.name.clone(),
+        }
+    }
+}
+
+/// A ranked entry with the characters the query matched, in its label or its detail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinderHit {
+    /// Index of the entry in the searched list.
+    pub index: usize,
+    pub score: i64,
+    pub label_positions: Vec<usize>,
+    pub detail_positions: Vec<usize>,
+}
+
+/// Characters of `text` matched by `query` in order, with a score rewarding runs and word starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices into `text`.
+    pub positions: Vec<usize>,
+}
+
+/// Matches the characters of `query` in order, ignoring case; spaces in the query are ignored.
+/// Every occurrence of the first character is tried as a start and the best score is kept.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    let text: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+
+    let mut best: Option<FuzzyMatch> = None;
+    for start in (0..text.len()).filter(|&i| lower(text[i]) == lower(query[0])) {
+        let mut positions = vec![start];
+        let mut next = start + 1;
+        for &q in &query[1..] {
+            match (next..text.len()).find(|&i| lower(text[i]) == lower(q)) {
+                Some(i) => {
+                    positions.push(i);
+                    next = i + 1;
+                }
+                None => break,
+            }
+        }
+        if positions.len() < query.len() {
+            // Later starts only leave less text to match
+            break;
+        }
+        let score = score(&query, &text, &positions);
+        if best.as_ref().is_none_or(|b| score > b.score) {
+            best = Some(FuzzyMatch { score, positions });
+        }
+    }
+    best
+}
+
+fn score(query: &[char], text: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += 16;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += 15;
+        } else if n > 0 {
+            score -= (i - positions[n - 1] - 1).min(10) as i64;
+        }
+        let word_start = i == 0
+            || matches!(text[i - 1], '/' | '_' | '-' | '.' | ' ' | ':')
+            || (text[i - 1].is_lowercase() && text[i].is_uppercase());
+        if word_start {
+            score += 10;
+        }
+        if text[i] == query[n] {
+            score += 1;
+        }
+    }
+    score - (positions[0].min(20) as i64)
+}
+
+/// Ranks the entries matching `query` by their label, and by their detail too when `with_details`.
+/// An empty query keeps every entry in order.
+pub fn search(entries: &[FinderEntry], query: &str, with_details: bool) -> Vec<FinderHit> {
+    if query.trim().is_empty() {
+        return (0..entries.len()).map(|index| FinderHit { index, score: 0, label_positions: vec![], detail_positions: vec![] }).collect();
+    }
+    let mut hits: Vec<(FinderHit, usize)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let label = entry.label();
+            let in_label = fuzzy_match(query, &label);
+            let in_detail = entry.detail.as_deref().filter(|_| with_details).and_then(|d| fuzzy_match(query, d));
+            let hit = match (in_label, in_detail) {
+                (Some(l), Some(d)) if d.score > l.score => FinderHit { index, score: d.score, label_positions: vec![], detail_positions: d.positions },
+                (Some(l), _) => FinderHit { index, score: l.score, label_positions: l.positions, detail_positions: vec![] },
+                (None, Some(d)) => FinderHit { index, score: d.score, label_positions: vec![], detail_positions: d.positions },
+                (None, None) => return None,
+            };
+            Some((hit, label.chars().count()))
+        })
+        .collect();
+    // Shorter names first among equal scores: `users` before `users/admin/list_users`
+    hits.sort_by(|(a, a_len), (b, b_len)| b.score.cmp(&a.score).then(a_len.cmp(b_len)).then(a.index.cmp(&b.index)));
+    hits.into_iter().map(|(hit, _)| hit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(project: &str, request_id: Option<&str>, detail: Option<&str>) -> FinderEntry {
+        FinderEntry { project: Project::new(project), request_id: request_id.map(String::from), detail: detail.map(String::from) }
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_runs_and_word_starts() {
+        let m = fuzzy_match("gu", "shop/users/get_user").unwrap();
+        assert_eq!(m.positions, vec![11, 15]);
+        assert_eq!(fuzzy_match("GETU", "shop/users/get_user").unwrap().positions, vec![11, 12, 13, 15]);
+        assert!(fuzzy_match("xyz", "shop/users/get_user").is_none());
+        assert!(fuzzy_match("ab", "ba").is_none());
+        assert!(fuzzy_match("user", "users").unwrap().score > fuzzy_match("user", "u_s_e_r").unwrap().score);
+    }
+
+    #[test]
+    fn test_search_ranks_and_matches_details() {
+        let entries = vec![
+            entry("shop", None, None),
+            entry("shop", Some("admin/list_users"), Some("GET https://api.shop.com/admin/users")),
+            entry("shop", Some("users/get_user"), Some("GET https://api.shop.com/users/{{id}}")),
+            entry("blog", Some("create_post"), Some("POST https://blog.dev/posts")),
+        ];
+        let ranked: Vec<usize> = search(&entries, "getuser", false).iter().map(|h| h.index).collect();
+        assert_eq!(ranked, vec![2]);
+        let ranked: Vec<usize> = search(&entries, "shop", false).iter().map(|h| h.index).collect();
+        assert_eq!(ranked, vec![0, 2, 1]);
+
+        assert!(search(&entries, "posts", false).is_empty());
+        let hits = search(&entries, "posts", true);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].index, hits[0].detail_positions.clone()), (3, vec![22, 23, 24, 25, 26]));
+        assert_eq!(search(&entries, "", false).len(), 4);
+    }
+}
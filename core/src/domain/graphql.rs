@@ -0,0 +1,299 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Query fetching what `GraphQLSchema` needs to check field names.
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      name
+      fields(includeDeprecated: true) { name type { ...TypeRef } }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}";
+
+/// The object types of a GraphQL schema, reduced to field names and their (unwrapped) types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphQLSchema {
+    pub query_type: String,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    /// Type name -> field name -> named type of the field.
+    pub types: HashMap<String, HashMap<String, String>>,
+}
+
+impl GraphQLSchema {
+    /// Reads the `__schema` of an introspection response (with or without the `data` envelope).
+    pub fn from_introspection(content: &str) -> Result<Self> {
+        let json: Value = serde_json::from_str(content).context("Introspection response is not valid JSON")?;
+        let schema = json
+            .pointer("/data/__schema")
+            .or_else(|| json.get("__schema"))
+            .filter(|schema| schema.is_object())
+            .context("Introspection response has no __schema")?;
+        let root_name = |key: &str| schema.pointer(&format!("/{}/name", key)).and_then(Value::as_str).map(str::to_string);
+
+        let mut types = HashMap::new();
+        for ty in schema.get("types").and_then(Value::as_array).context("Introspection response has no types")? {
+            let (Some(name), Some(fields)) = (ty.get("name").and_then(Value::as_str), ty.get("fields").and_then(Value::as_array)) else {
+                continue; // Scalars, enums and input types have no fields
+            };
+            let fields = fields
+                .iter()
+                .filter_map(|f| Some((f.get("name")?.as_str()?.to_string(), named_type(f.get("type")?)?)))
+                .collect();
+            types.insert(name.to_string(), fields);
+        }
+
+        Ok(GraphQLSchema {
+            query_type: root_name("queryType").unwrap_or_else(|| "Query".to_string()),
+            mutation_type: root_name("mutationType"),
+            subscription_type: root_name("subscriptionType"),
+            types,
+        })
+    }
+
+    /// Returns the fields selected in `query` that the schema does not define, as `Type.field`.
+    /// This is a best-effort check: named fragments are not followed.
+    pub fn unknown_fields(&self, query: &str) -> Vec<String> {
+        let tokens = tokenize(query);
+        let mut unknown = Vec::new();
+        // Current type per open selection set; `None` when the type is unknown
+        let mut stack: Vec<Option<String>> = Vec::new();
+        let mut pending: Option<Option<String>> = None;
+        let mut operation = Some(self.query_type.clone());
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let token = tokens[i].as_str();
+            match token {
+                "{" => {
+                    let current = pending.take().unwrap_or_else(|| {
+                        if stack.is_empty() { operation.clone() } else { None }
+                    });
+                    stack.push(current);
+                }
+                "}" => {
+                    stack.pop();
+                    pending = None;
+                    if stack.is_empty() {
+                        operation = Some(self.query_type.clone());
+                    }
+                }
+                "(" => i = skip_group(&tokens, i, "(", ")"),
+                "@" => {
+                    // Directive: skip its name and arguments
+                    i += 1;
+                    if tokens.get(i + 1).map(String::as_str) == Some("(") {
+                        i = skip_group(&tokens, i + 1, "(", ")");
+                    }
+                }
+                "..." => {
+                    if tokens.get(i + 1).map(String::as_str) == Some("on") {
+                        pending = Some(tokens.get(i + 2).cloned());
+                        i += 2;
+                    } else if tokens.get(i + 1).map(String::as_str) == Some("{") {
+                        pending = Some(stack.last().cloned().flatten());
+                    } else {
+                        i += 1; // Named fragment spread
+                    }
+                }
+                _ if stack.is_empty() => {
+                    // Outside selections: operation or fragment definitions
+                    match token {
+                        "query" => operation = Some(self.query_type.clone()),
+                        "mutation" => operation = self.mutation_type.clone(),
+                        "subscription" => operation = self.subscription_type.clone(),
+                        "fragment" => {
+                            // fragment Name on Type
+                            operation = tokens.get(i + 3).cloned();
+                            i += 3;
+                        }
+                        _ => {}
+                    }
+                }
+                _ if is_name(token) => {
+                    // `alias: field` selects `field`
+                    let (field, next) = if tokens.get(i + 1).map(String::as_str) == Some(":") {
+                        (tokens.get(i + 2).map(String::as_str).unwrap_or(token), i + 2)
+                    } else {
+                        (token, i)
+                    };
+                    i = next;
+
+                    let parent = stack.last().cloned().flatten();
+                    pending = Some(match (&parent, field) {
+                        (_, "__typename") => None,
+                        (Some(parent), field) => match self.types.get(parent).and_then(|fields| fields.get(field)) {
+                            Some(field_type) => Some(field_type.clone()),
+                            None => {
+                                unknown.push(format!("{}.{}", parent, field));
+                                None
+                            }
+                        },
+                        (None, _) => None,
+                    });
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        unknown
+    }
+}
+
+fn is_name(token: &str) -> bool {
+    token.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+}
+
+/// Returns the index of the token closing the group opened at `start`.
+fn skip_group(tokens: &[String], start: usize, open: &str, close: &str) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if token == open {
+            depth += 1;
+        } else if token == close {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Splits a GraphQL document into names, punctuation and literals, dropping comments.
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut literal = String::from('"');
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    literal.push(c);
+                    if c == '"' && !escaped {
+                        break;
+                    }
+                    escaped = c == '\\' && !escaped;
+                }
+                tokens.push(literal);
+            }
+            '.' => {
+                while chars.peek() == Some(&'.') {
+                    chars.next();
+                }
+                tokens.push("...".to_string());
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '-' => {
+                let mut name = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '.' && c.is_ascii_digit() {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(name);
+            }
+            c if c.is_whitespace() || c == ',' => {}
+            c => tokens.push(c.to_string()),
+        }
+    }
+    tokens
+}
+
+/// The named type under `NON_NULL` and `LIST` wrappers.
+fn named_type(type_ref: &Value) -> Option<String> {
+    match type_ref.get("name").and_then(Value::as_str) {
+        Some(name) => Some(name.to_string()),
+        None => named_type(type_ref.get("ofType")?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_introspection() {
+        let introspection = r#"{"data":{"__schema":{
+            "queryType":{"name":"Query"},"mutationType":null,"subscriptionType":null,
+            "types":[
+                {"name":"Query","fields":[{"name":"users","type":{"kind":"NON_NULL","name":null,"ofType":{"kind":"LIST","name":null,"ofType":{"kind":"OBJECT","name":"User","ofType":null}}}}]},
+                {"name":"User","fields":[{"name":"id","type":{"kind":"SCALAR","name":"ID","ofType":null}}]},
+                {"name":"ID","fields":null}
+            ]}}}"#;
+        let schema = GraphQLSchema::from_introspection(introspection).unwrap();
+        assert_eq!(schema.query_type, "Query");
+        assert_eq!(schema.mutation_type, None);
+        assert_eq!(schema.types["Query"]["users"], "User");
+        assert_eq!(schema.types["User"]["id"], "ID");
+        assert!(!schema.types.contains_key("ID"));
+
+        for (body, error) in [
+            ("<html>Sign in</html>", "Introspection response is not valid JSON"),
+            (r#"{"data":null}"#, "Introspection response has no __schema"),
+            (r#"{"data":{"__schema":null}}"#, "Introspection response has no __schema"),
+            (r#"{"data":{"__schema":{"queryType":{"name":"Query"}}}}"#, "Introspection response has no types"),
+        ] {
+            assert_eq!(GraphQLSchema::from_introspection(body).unwrap_err().to_string(), error, "{}", body);
+        }
+    }
+
+    fn schema() -> GraphQLSchema {
+        let fields = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        GraphQLSchema {
+            query_type: "Query".to_string(),
+            mutation_type: Some("Mutation".to_string()),
+            subscription_type: None,
+            types: HashMap::from([
+                ("Query".to_string(), fields(&[("user", "User"), ("node", "Node")])),
+                ("Mutation".to_string(), fields(&[("rename", "User")])),
+                ("User".to_string(), fields(&[("id", "ID"), ("name", "String"), ("friends", "User")])),
+                ("Node".to_string(), fields(&[("id", "ID")])),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_known_fields_pass() {
+        let query = r#"query GetUser($id: ID!) {
+  me: user(id: $id) @include(if: true) {
+    id
+    __typename
+    friends { name }
+  }
+  node(id: "1") { ... on User { name } ...NodeFields }
+}"#;
+        assert!(schema().unknown_fields(query).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_reported() {
+        let query = "{ user { id email friends { nickname } } }\nmutation { rename(name: \"x\") { age } }";
+        assert_eq!(
+            schema().unknown_fields(query),
+            vec!["User.email", "User.nickname", "User.age"]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Where the call of a `GRPC host/package.Service/Method` request goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcTarget {
+    /// `http://host:port`, or `https://` for `grpcs://` and `https://` URLs.
+    pub endpoint: String,
+    /// Fully qualified service name, e.g. `helloworld.Greeter`.
+    pub service: String,
+    pub method: String,
+}
+
+impl GrpcTarget {
+    pub fn parse(url: &str) -> Result<Self> {
+        let lower = url.to_lowercase();
+        let (scheme, rest) = if lower.starts_with("grpcs://") || lower.starts_with("https://") {
+            ("https", &url[url.find("://").unwrap_or(0) + 3..])
+        } else if lower.starts_with("grpc://") || lower.starts_with("http://") {
+            ("http", &url[url.find("://").unwrap_or(0) + 3..])
+        } else {
+            ("http", url)
+        };
+
+        let mut segments = rest.rsplitn(3, '/');
+        let method = segments.next().filter(|s| !s.is_empty());
+        let service = segments.next().filter(|s| !s.is_empty());
+        let host = segments.next().filter(|s| !s.is_empty());
+        let (Some(host), Some(service), Some(method)) = (host, service, method) else {
+            anyhow::bail!("gRPC URL must look like host/package.Service/Method: {}", url);
+        };
+
+        Ok(Self {
+            endpoint: format!("{}://{}", scheme, host),
+            service: service.to_string(),
+            method: method.to_string(),
+        })
+    }
+}
+
+/// `.proto` files of a project, with the directory imports are resolved against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtoSource {
+    pub include_dir: PathBuf,
+    pub files: Vec<PathBuf>,
+}
+
+/// Name of a gRPC status code, e.g. `NOT_FOUND` for 5.
+pub fn status_name(code: u16) -> &'static str {
+    const NAMES: [&str; 17] = [
+        "OK",
+        "CANCELLED",
+        "UNKNOWN",
+        "INVALID_ARGUMENT",
+        "DEADLINE_EXCEEDED",
+        "NOT_FOUND",
+        "ALREADY_EXISTS",
+        "PERMISSION_DENIED",
+        "RESOURCE_EXHAUSTED",
+        "FAILED_PRECONDITION",
+        "ABORTED",
+        "OUT_OF_RANGE",
+        "UNIMPLEMENTED",
+        "INTERNAL",
+        "UNAVAILABLE",
+        "DATA_LOSS",
+        "UNAUTHENTICATED",
+    ];
+    NAMES.get(code as usize).copied().unwrap_or("UNKNOWN")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let target = GrpcTarget::parse("localhost:50051/helloworld.Greeter/SayHello").unwrap();
+        assert_eq!(target.endpoint, "http://localhost:50051");
+        assert_eq!(target.service, "helloworld.Greeter");
+        assert_eq!(target.method, "SayHello");
+
+        let target = GrpcTarget::parse("grpcs://api.example.com/pkg.Svc/Get").unwrap();
+        assert_eq!(target.endpoint, "https://api.example.com");
+        assert!(GrpcTarget::parse("localhost:50051/SayHello").is_err());
+    }
+}
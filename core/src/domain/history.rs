@@ -0,0 +1,13 @@
+use super::request::Request;
+use super::response::Response;
+use std::time::SystemTime;
+
+/// A response received for a request, kept to compare later runs against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: SystemTime,
+    /// The request as sent, resolved and with secrets masked. `None` for baselines
+    /// and entries recorded before requests were kept.
+    pub request: Option<Request>,
+    pub response: Response,
+}
@@ -0,0 +1,383 @@
+use super::environment::Environment;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Request header overriding the status of one mocked call, e.g. to try error handling.
+pub const STATUS_HEADER: &str = "x-mock-status";
+/// Request header adding latency (in milliseconds) to one mocked call.
+pub const DELAY_HEADER: &str = "x-mock-delay";
+
+/// An example response served by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    /// May reference path parameters as `{{name}}`.
+    pub body: String,
+    /// Latency to inject before answering.
+    pub delay: Option<Duration>,
+}
+
+/// A request of the project the mock server answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRoute {
+    pub request_id: String,
+    pub method: String,
+    /// Path with parameters written as `:id`, `{id}` or `{{id}}`.
+    pub path: String,
+    /// Query the call must carry, e.g. `page=2`, in any order. `None` accepts any query.
+    pub query: Option<String>,
+    /// Body the call must carry, compared as JSON when both sides are. `None` accepts any body.
+    pub body: Option<String>,
+    /// `None` when the request has neither a `.mock` file nor history.
+    pub response: Option<MockResponse>,
+}
+
+impl MockRoute {
+    /// Returns the path parameters if this route answers `method` and `path`.
+    pub fn matches(&self, method: &str, path: &str) -> Option<HashMap<String, String>> {
+        if !self.method.eq_ignore_ascii_case(method) {
+            return None;
+        }
+        let template = segments(&self.path);
+        let actual = segments(path);
+        if template.len() != actual.len() {
+            return None;
+        }
+        let mut params = HashMap::new();
+        for (t, a) in template.iter().zip(&actual) {
+            match parameter(t) {
+                Some(name) => {
+                    params.insert(name.to_string(), a.to_string());
+                }
+                None if t == a => {}
+                None => return None,
+            }
+        }
+        Some(params)
+    }
+
+    /// Whether the query and body of a call are the ones this route was recorded with.
+    pub fn accepts(&self, query: &str, body: &str) -> bool {
+        let query_matches = self.query.as_ref().is_none_or(|expected| query_pairs(expected) == query_pairs(query));
+        let body_matches = self.body.as_ref().is_none_or(|expected| same_body(expected, body));
+        query_matches && body_matches
+    }
+
+    /// Literal segments, then recorded query and body; a route with more of them is more specific.
+    fn specificity(&self) -> (usize, usize) {
+        let literals = segments(&self.path).iter().filter(|s| parameter(s).is_none()).count();
+        (literals, self.query.is_some() as usize + self.body.is_some() as usize)
+    }
+}
+
+/// Overrides applied to every mocked response.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockOptions {
+    pub delay: Option<Duration>,
+    pub status: Option<u16>,
+    /// Routes also require the literal query and body of their request file, to replay recorded calls exactly.
+    pub exact: bool,
+}
+
+/// What the mock server answers to one call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockReply {
+    /// The request that answered, if any matched.
+    pub request_id: Option<String>,
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    pub delay: Duration,
+}
+
+/// A call received by the mock server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockCall {
+    pub method: String,
+    pub path: String,
+    /// Raw query string, without the `?`.
+    pub query: String,
+    /// Header names are lowercase.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// A call answered by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockHit {
+    pub method: String,
+    pub path: String,
+    pub reply: MockReply,
+}
+
+/// Picks the route answering a call and builds its response.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockRouter {
+    routes: Vec<MockRoute>,
+    options: MockOptions,
+}
+
+impl MockRouter {
+    pub fn new(mut routes: Vec<MockRoute>, options: MockOptions) -> Self {
+        // `/users/me` wins over `/users/:id`
+        routes.sort_by_key(|r| std::cmp::Reverse(r.specificity()));
+        Self { routes, options }
+    }
+
+    pub fn routes(&self) -> &[MockRoute] {
+        &self.routes
+    }
+
+    /// Answers a call with the most specific route matching its method, path, query and body.
+    pub fn respond(&self, call: &MockCall) -> MockReply {
+        let (method, path, headers) = (&call.method, &call.path, &call.headers);
+        let matched = self
+            .routes
+            .iter()
+            .filter(|r| r.accepts(&call.query, &call.body))
+            .find_map(|r| r.matches(method, path).map(|params| (r, params)));
+        let mut reply = match matched {
+            Some((route, params)) => {
+                let mut reply = match &route.response {
+                    Some(response) => MockReply {
+                        request_id: None,
+                        // Overrides only apply to example responses, never to the errors below
+                        status: headers.get(STATUS_HEADER).and_then(|s| s.parse().ok()).or(self.options.status).unwrap_or(response.status),
+                        headers: response.headers.clone(),
+                        body: Environment::new(params, HashMap::new()).substitute(&response.body),
+                        delay: response.delay.unwrap_or_default(),
+                    },
+                    None => error_reply(501, format!("No example response for '{0}': add {0}.mock or run it once", route.request_id)),
+                };
+                reply.request_id = Some(route.request_id.clone());
+                reply
+            }
+            None => error_reply(404, format!("No request matches {} {}", method, path)),
+        };
+
+        let delay = headers.get(DELAY_HEADER).and_then(|d| d.parse().ok()).map(Duration::from_millis).or(self.options.delay);
+        reply.delay += delay.unwrap_or_default();
+        reply
+    }
+}
+
+fn error_reply(status: u16, message: String) -> MockReply {
+    MockReply {
+        request_id: None,
+        status,
+        headers: HashMap::from([("Content-Type".to_string(), "text/plain; charset=utf-8".to_string())]),
+        body: message,
+        delay: Duration::ZERO,
+    }
+}
+
+/// Extracts the path of a request URL, keeping parameter placeholders:
+/// `{{base_url}}/users/{{id}}?x=1` and `https://api.com/users/:id` both give `/users/...`.
+pub fn url_path(url: &str) -> String {
+    let rest = if let Some((_, after_scheme)) = url.split_once("://") {
+        after_scheme.find('/').map_or("", |i| &after_scheme[i..])
+    } else if url.starts_with("{{") {
+        url.find("}}").map_or("", |i| &url[i + 2..])
+    } else {
+        url.find('/').map_or("", |i| &url[i..])
+    };
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Extracts the query of a request URL to match calls against, unless it holds placeholders.
+pub fn url_query(url: &str) -> Option<String> {
+    let query = url.split('#').next().unwrap_or_default().split_once('?')?.1;
+    (!query.is_empty() && !query.contains("{{")).then(|| query.to_string())
+}
+
+/// Writes fields as an `application/x-www-form-urlencoded` body.
+pub fn form_body(fields: &[(String, String)]) -> String {
+    let encode = |text: &str| {
+        text.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'*' => (b as char).to_string(),
+                b' ' => "+".to_string(),
+                b => format!("%{:02X}", b),
+            })
+            .collect::<String>()
+    };
+    fields.iter().map(|(k, v)| format!("{}={}", encode(k), encode(v))).collect::<Vec<_>>().join("&")
+}
+
+/// Decoded and sorted, so that encoding and order don't matter.
+fn query_pairs(query: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+/// Undoes form encoding: `+` is a space and `%XX` a byte; malformed escapes are kept as they are.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn same_body(expected: &str, actual: &str) -> bool {
+    match (serde_json::from_str::<serde_json::Value>(expected), serde_json::from_str::<serde_json::Value>(actual)) {
+        (Ok(expected), Ok(actual)) => expected == actual,
+        _ => expected.trim() == actual.trim(),
+    }
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+fn parameter(segment: &str) -> Option<&str> {
+    segment
+        .strip_prefix(':')
+        .or_else(|| segment.strip_prefix("{{").and_then(|s| s.strip_suffix("}}")))
+        .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(id: &str, method: &str, path: &str, body: Option<&str>) -> MockRoute {
+        MockRoute {
+            request_id: id.to_string(),
+            method: method.to_string(),
+            path: path.to_string(),
+            query: None,
+            body: None,
+            response: body.map(|b| MockResponse { status: 200, headers: HashMap::new(), body: b.to_string(), delay: None }),
+        }
+    }
+
+    fn call(method: &str, path: &str) -> MockCall {
+        MockCall { method: method.to_string(), path: path.to_string(), ..Default::default() }
+    }
+
+    fn call_with(method: &str, path: &str, headers: &[(&str, &str)]) -> MockCall {
+        let headers = headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        MockCall { headers, ..call(method, path) }
+    }
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(url_path("{{base_url}}/users/{{id}}?expand=1"), "/users/{{id}}");
+        assert_eq!(url_path("https://api.com/users/:id#top"), "/users/:id");
+        assert_eq!(url_path("localhost:8080/health"), "/health");
+        assert_eq!(url_path("https://api.com"), "/");
+    }
+
+    #[test]
+    fn test_routing_with_parameters() {
+        let router = MockRouter::new(
+            vec![
+                route("get_user", "GET", "/users/{{id}}", Some(r#"{"id": "{{id}}"}"#)),
+                route("me", "GET", "/users/me", Some("me")),
+                route("delete_user", "DELETE", "/users/:id", None),
+            ],
+            MockOptions::default(),
+        );
+        let reply = router.respond(&call("GET", "/users/42/"));
+        assert_eq!(reply.request_id.as_deref(), Some("get_user"));
+        assert_eq!(reply.body, r#"{"id": "42"}"#);
+        assert_eq!(router.respond(&call("GET", "/users/me")).body, "me");
+        assert_eq!(router.respond(&call("DELETE", "/users/1")).status, 501);
+        assert_eq!(router.respond(&call("POST", "/users")).status, 404);
+    }
+
+    #[test]
+    fn test_replay_matches_query_and_body() {
+        let mut page_2 = route("users_2", "GET", "/users", Some("page 2"));
+        page_2.query = Some("page=2&size=10".to_string());
+        let mut create = route("create", "POST", "/users", Some("created"));
+        create.body = Some(r#"{"name": "a", "admin": false}"#.to_string());
+        let router = MockRouter::new(vec![route("users", "GET", "/users", Some("any")), page_2, create], MockOptions::default());
+
+        let get = |query: &str| router.respond(&MockCall { query: query.to_string(), ..call("GET", "/users") }).body;
+        assert_eq!(get("size=10&page=2"), "page 2");
+        assert_eq!(get("page=%32&size=1%30"), "page 2");
+        assert_eq!(get("page=3&size=10"), "any");
+        assert_eq!(get(""), "any");
+
+        let post = |body: &str| router.respond(&MockCall { body: body.to_string(), ..call("POST", "/users") }).status;
+        assert_eq!(post(r#"{"admin":false,"name":"a"}"#), 200);
+        assert_eq!(post(r#"{"name": "b", "admin": false}"#), 404);
+    }
+
+    #[test]
+    fn test_form_encoding() {
+        let fields = vec![("q".to_string(), "a b&c=é".to_string()), ("n".to_string(), "1".to_string())];
+        let body = form_body(&fields);
+        assert_eq!(body, "q=a+b%26c%3D%C3%A9&n=1");
+        assert_eq!(query_pairs(&body), vec![("n".to_string(), "1".to_string()), ("q".to_string(), "a b&c=é".to_string())]);
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn test_url_query() {
+        assert_eq!(url_query("{{base_url}}/users?page=2#top").as_deref(), Some("page=2"));
+        assert_eq!(url_query("{{base_url}}/users?page={{page}}"), None);
+        assert_eq!(url_query("{{base_url}}/users?"), None);
+    }
+
+    #[test]
+    fn test_status_and_latency_overrides() {
+        let mut get = route("get", "GET", "/health", Some("ok"));
+        get.response.as_mut().unwrap().delay = Some(Duration::from_millis(100));
+        let options = MockOptions { delay: Some(Duration::from_millis(50)), ..Default::default() };
+        let router = MockRouter::new(vec![get], options);
+
+        let reply = router.respond(&call("GET", "/health"));
+        assert_eq!((reply.status, reply.delay), (200, Duration::from_millis(150)));
+
+        let headers = [(STATUS_HEADER, "503"), (DELAY_HEADER, "10")];
+        let reply = router.respond(&call_with("GET", "/health", &headers));
+        assert_eq!((reply.status, reply.delay), (503, Duration::from_millis(110)));
+    }
+
+    #[test]
+    fn test_status_override_keeps_errors() {
+        let options = MockOptions { status: Some(500), ..Default::default() };
+        let router = MockRouter::new(vec![route("get", "GET", "/health", Some("ok")), route("post", "POST", "/health", None)], options);
+        let headers = [(STATUS_HEADER, "503")];
+
+        assert_eq!(router.respond(&call("GET", "/health")).status, 500);
+        assert_eq!(router.respond(&call("POST", "/health")).status, 501);
+        assert_eq!(router.respond(&call("GET", "/missing")).status, 404);
+        assert_eq!(router.respond(&call_with("GET", "/missing", &headers)).status, 404);
+    }
+}
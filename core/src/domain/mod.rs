@@ -2,3 +2,22 @@ pub mod request;
 pub mod response;
 pub mod repository;
 pub mod project;
+pub mod environment;
+pub mod auth;
+pub mod oauth2;
+pub mod body;
+pub mod graphql;
+pub mod websocket;
+pub mod stream;
+pub mod grpc;
+pub mod diff;
+pub mod history;
+pub mod snapshot;
+pub mod mock;
+pub mod capture;
+pub mod collection;
+pub mod openapi;
+pub mod trash;
+pub mod finder;
+pub mod search;
+pub mod watch;
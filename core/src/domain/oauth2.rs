@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::environment::Environment;
+
+/// Name of the variable exposing the acquired access token to requests.
+pub const ACCESS_TOKEN_VARIABLE: &str = "oauth2_access_token";
+
+/// Tokens expiring within this many seconds are renewed before sending.
+const EXPIRY_MARGIN_SECS: u64 = 30;
+
+/// How an OAuth2 access token is obtained.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OAuth2Grant {
+    ClientCredentials,
+    Password { username: String, password: String },
+    RefreshToken { refresh_token: String },
+    /// Authorization code with PKCE, received on a loopback redirect listener.
+    AuthorizationCode { auth_url: String, redirect_port: u16 },
+}
+
+/// OAuth2 settings of an environment, read from `oauth2_*` variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuth2Config {
+    pub grant: OAuth2Grant,
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub scope: Option<String>,
+}
+
+impl OAuth2Config {
+    /// Reads the configuration from the environment.
+    /// Returns `None` when no `oauth2_grant_type` is set.
+    pub fn from_environment(env: &Environment) -> Result<Option<Self>> {
+        let Some(grant_type) = env.get("oauth2_grant_type") else {
+            return Ok(None);
+        };
+        let require = |name: &str| env.get(name).with_context(|| format!("OAuth2 requires '{}'", name));
+
+        let grant = match grant_type.as_str() {
+            "client_credentials" => OAuth2Grant::ClientCredentials,
+            "password" => OAuth2Grant::Password {
+                username: require("oauth2_username")?,
+                password: require("oauth2_password")?,
+            },
+            "refresh_token" => OAuth2Grant::RefreshToken {
+                refresh_token: require("oauth2_refresh_token")?,
+            },
+            "authorization_code" => OAuth2Grant::AuthorizationCode {
+                auth_url: require("oauth2_auth_url")?,
+                redirect_port: match env.get("oauth2_redirect_port") {
+                    Some(port) => port.parse().with_context(|| format!("Invalid oauth2_redirect_port: {}", port))?,
+                    None => 0,
+                },
+            },
+            other => anyhow::bail!("Unknown oauth2_grant_type: {}", other),
+        };
+
+        Ok(Some(Self {
+            grant,
+            token_url: require("oauth2_token_url")?,
+            client_id: require("oauth2_client_id")?,
+            client_secret: env.get("oauth2_client_secret"),
+            scope: env.get("oauth2_scope"),
+        }))
+    }
+
+    /// Key identifying tokens issued for this configuration in the cache.
+    /// Tokens of different grants or users are never shared.
+    pub fn cache_key(&self) -> String {
+        let grant = match &self.grant {
+            OAuth2Grant::ClientCredentials => "client_credentials".to_string(),
+            OAuth2Grant::Password { username, .. } => format!("password:{}", username),
+            OAuth2Grant::RefreshToken { .. } => "refresh_token".to_string(),
+            OAuth2Grant::AuthorizationCode { .. } => "authorization_code".to_string(),
+        };
+        format!("{}|{}|{}|{}", grant, self.token_url, self.client_id, self.scope.as_deref().unwrap_or(""))
+    }
+}
+
+/// An access token with its expiry (seconds since the Unix epoch).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<u64>,
+}
+
+impl OAuth2Token {
+    /// Builds a token from an `expires_in` lifetime relative to `now`.
+    pub fn new(access_token: impl Into<String>, refresh_token: Option<String>, expires_in: Option<u64>, now: SystemTime) -> Self {
+        Self {
+            access_token: access_token.into(),
+            refresh_token,
+            expires_at: expires_in.map(|secs| epoch_secs(now) + secs),
+        }
+    }
+
+    /// Whether the token must be renewed before use at `now`.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => epoch_secs(now) + EXPIRY_MARGIN_SECS >= expires_at,
+            None => false,
+        }
+    }
+}
+
+fn epoch_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn config(grant: OAuth2Grant) -> OAuth2Config {
+        OAuth2Config {
+            grant,
+            token_url: "https://auth.example.com/token".to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+            scope: Some("read".to_string()),
+        }
+    }
+
+    fn password(username: &str) -> OAuth2Grant {
+        OAuth2Grant::Password { username: username.to_string(), password: "secret".to_string() }
+    }
+
+    #[test]
+    fn test_cache_key_separates_grants_and_users() {
+        let alice = config(password("alice")).cache_key();
+        assert_ne!(alice, config(password("bob")).cache_key());
+        assert_ne!(alice, config(OAuth2Grant::ClientCredentials).cache_key());
+        assert_eq!(alice, config(OAuth2Grant::Password { username: "alice".to_string(), password: "other".to_string() }).cache_key());
+    }
+
+    #[test]
+    fn test_expiry_is_relative_to_the_given_time() {
+        let issued = UNIX_EPOCH + Duration::from_secs(1_000);
+        let token = OAuth2Token::new("t", None, Some(3600), issued);
+        assert_eq!(token.expires_at, Some(4_600));
+        assert!(!token.is_expired(issued));
+        assert!(token.is_expired(issued + Duration::from_secs(3600 - EXPIRY_MARGIN_SECS)));
+        assert!(!OAuth2Token::new("t", None, None, issued).is_expired(issued + Duration::from_secs(1 << 40)));
+    }
+}
@@ -0,0 +1,527 @@
+use super::auth::{ApiKeyLocation, Auth};
+use super::body::Body;
+use super::repository::SchemaValidator;
+use super::mock::url_path;
+use super::request::Request;
+use super::response::Response;
+use serde_json::{Map, Value};
+
+/// Variable holding the server URL that scaffolded requests start with.
+pub const BASE_URL_VARIABLE: &str = "baseUrl";
+
+/// The operations of an OpenAPI 3 or Swagger 2 document, with every `$ref` resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiSpec {
+    pub title: String,
+    /// URL of the first declared server, with its variables set to their defaults.
+    pub base_url: Option<String>,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    /// The request id: the `operationId`, or the method and path when there is none.
+    pub id: String,
+    pub method: String,
+    /// Path template relative to the server, e.g. `/users/{id}`.
+    pub path: String,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<MediaType>,
+    pub security: Option<SecurityScheme>,
+    pub responses: Vec<ApiResponse>,
+}
+
+/// A response an operation declares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiResponse {
+    /// `200`, a range such as `2XX`, or `default`.
+    pub status: String,
+    /// Declared as header parameters.
+    pub headers: Vec<Parameter>,
+    pub content: Vec<MediaType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub location: ParameterLocation,
+    pub required: bool,
+    pub schema: Value,
+    pub example: Option<Value>,
+}
+
+/// A body of a given content type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaType {
+    pub content_type: String,
+    pub schema: Value,
+    pub example: Option<Value>,
+}
+
+/// The authentication an operation requires, as far as a `.http` file can express it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecurityScheme {
+    Basic,
+    /// HTTP bearer, OAuth 2 and OpenID Connect: a token obtained elsewhere.
+    Bearer,
+    ApiKey { location: ApiKeyLocation, name: String },
+}
+
+impl ApiSpec {
+    /// The operation a request URL calls, either scaffolded (`{{baseUrl}}/users/{{id}}`) or resolved.
+    /// A literal segment beats a path parameter, so `/users/me` wins over `/users/{id}`.
+    pub fn operation_for(&self, method: &str, url: &str) -> Option<&Operation> {
+        let path = url_path(url);
+        let base_path = self.base_url.as_deref().map(url_path).unwrap_or_default();
+        let path = match path.strip_prefix(base_path.trim_end_matches('/')) {
+            Some(rest) if rest.starts_with('/') => rest.to_string(),
+            _ => path,
+        };
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        self.operations
+            .iter()
+            .filter(|operation| operation.method.eq_ignore_ascii_case(method))
+            .filter_map(|operation| {
+                let template: Vec<&str> = operation.path.trim_matches('/').split('/').collect();
+                if template.len() != segments.len() {
+                    return None;
+                }
+                let mut literals = 0;
+                for (expected, actual) in template.iter().zip(&segments) {
+                    if expected.starts_with('{') {
+                        continue;
+                    }
+                    if expected != actual {
+                        return None;
+                    }
+                    literals += 1;
+                }
+                Some((literals, operation))
+            })
+            .max_by_key(|(literals, _)| *literals)
+            .map(|(_, operation)| operation)
+    }
+}
+
+impl Operation {
+    /// Scaffolds a request: parameters become `{{variables}}` and the body an example of its schema.
+    /// Optional query parameters are only added when the document gives them a value.
+    pub fn to_request(&self) -> Request {
+        let mut url = format!("{{{{{}}}}}{}", BASE_URL_VARIABLE, self.path.replace('{', "{{").replace('}', "}}"));
+        let query: Vec<String> = self
+            .parameters
+            .iter()
+            .filter(|p| p.location == ParameterLocation::Query && p.is_scaffolded())
+            .map(|p| format!("{}={{{{{}}}}}", p.name, p.name))
+            .collect();
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query.join("&"));
+        }
+
+        let mut request = Request::new(self.method.clone(), url);
+        for parameter in self.parameters.iter().filter(|p| p.location == ParameterLocation::Header && p.is_scaffolded()) {
+            request.headers.insert(parameter.name.clone(), format!("{{{{{}}}}}", parameter.name));
+        }
+        let cookies: Vec<String> = self
+            .parameters
+            .iter()
+            .filter(|p| p.location == ParameterLocation::Cookie && p.is_scaffolded())
+            .map(|p| format!("{}={{{{{}}}}}", p.name, p.name))
+            .collect();
+        if !cookies.is_empty() {
+            request.headers.insert("Cookie".to_string(), cookies.join("; "));
+        }
+
+        request.auth = match &self.security {
+            Some(SecurityScheme::Basic) => Some(Auth::Basic { username: "{{username}}".to_string(), password: "{{password}}".to_string() }),
+            Some(SecurityScheme::Bearer) => Some(Auth::Bearer { token: "{{token}}".to_string() }),
+            Some(SecurityScheme::ApiKey { location, name }) => {
+                Some(Auth::ApiKey { location: location.clone(), name: name.clone(), value: "{{apiKey}}".to_string() })
+            }
+            None => None,
+        };
+
+        if let Some(body) = &self.request_body {
+            request.headers.insert("Content-Type".to_string(), body.content_type.clone());
+            request.body = body.example_body();
+        }
+        request
+    }
+
+    /// Values of the parameters used by `to_request`, from their examples or defaults.
+    pub fn variables(&self) -> Vec<(String, String)> {
+        self.parameters
+            .iter()
+            .filter(|p| p.is_scaffolded())
+            .map(|p| (p.name.clone(), p.value().map(|v| scalar(&v)).unwrap_or_default()))
+            .collect()
+    }
+
+    /// Secrets the scaffolded authentication refers to, left empty for the user to fill in.
+    pub fn secrets(&self) -> Vec<(String, String)> {
+        let names: &[&str] = match &self.security {
+            Some(SecurityScheme::Basic) => &["username", "password"],
+            Some(SecurityScheme::Bearer) => &["token"],
+            Some(SecurityScheme::ApiKey { .. }) => &["apiKey"],
+            None => &[],
+        };
+        names.iter().map(|n| (n.to_string(), String::new())).collect()
+    }
+
+    /// Checks a response against the declaration of its status: the status must be declared,
+    /// required headers present and valid, and the body must match the schema of its content type.
+    /// A declared JSON body must be there. An operation without declared responses accepts any.
+    pub fn validate_response(&self, response: &Response, validator: &dyn SchemaValidator) -> Vec<String> {
+        if self.responses.is_empty() {
+            return Vec::new();
+        }
+        let Some(declared) = self.response_for(response.status) else {
+            let statuses: Vec<&str> = self.responses.iter().map(|r| r.status.as_str()).collect();
+            return vec![format!("status {} is not declared (expected {})", response.status, statuses.join(", "))];
+        };
+        let mut violations = Vec::new();
+        let header = |name: &str| response.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str());
+
+        for parameter in &declared.headers {
+            match header(&parameter.name) {
+                Some(value) => violations.extend(
+                    validator
+                        .validate(&parameter.schema, &header_value(&parameter.schema, value))
+                        .into_iter()
+                        .map(|e| format!("header {}{}", parameter.name, e.trim_start_matches('$'))),
+                ),
+                None if parameter.required => violations.push(format!("header {} is missing", parameter.name)),
+                None => {}
+            }
+        }
+
+        if declared.content.is_empty() {
+            return violations;
+        }
+        let content_type = header("content-type").map(|c| c.split(';').next().unwrap_or_default().trim().to_lowercase());
+        let media = match &content_type {
+            Some(content_type) => declared.content.iter().find(|m| media_range_matches(&m.content_type, content_type)),
+            None => declared.content.iter().find(|m| m.content_type.contains("json")).or(declared.content.first()),
+        };
+        let Some(media) = media else {
+            let declared: Vec<&str> = declared.content.iter().map(|m| m.content_type.as_str()).collect();
+            violations.push(format!("content type {} is not declared (expected {})", content_type.unwrap_or_default(), declared.join(", ")));
+            return violations;
+        };
+        if media.content_type.contains("json") {
+            match serde_json::from_str::<Value>(&response.body) {
+                Ok(body) => violations.extend(validator.validate(&media.schema, &body).into_iter().map(|e| format!("body {}", e))),
+                // A schema that constrains nothing accepts no body too
+                Err(_) if response.body.trim().is_empty() && unconstrained(&media.schema) => {}
+                Err(_) if response.body.trim().is_empty() => violations.push(format!("body is empty (expected {})", media.content_type)),
+                Err(e) => violations.push(format!("body is not valid JSON: {}", e)),
+            }
+        }
+        violations
+    }
+
+    /// The exact status first, then its range (`2XX`), then `default`.
+    fn response_for(&self, status: u16) -> Option<&ApiResponse> {
+        let range = format!("{}XX", status / 100);
+        let find = |wanted: &str| self.responses.iter().find(|r| r.status.eq_ignore_ascii_case(wanted));
+        find(&status.to_string()).or_else(|| find(&range)).or_else(|| find("default"))
+    }
+}
+
+fn unconstrained(schema: &Value) -> bool {
+    schema.is_null() || schema.as_object().is_some_and(|fields| fields.is_empty())
+}
+
+/// Whether a declared media range such as `application/*` covers a content type.
+fn media_range_matches(range: &str, content_type: &str) -> bool {
+    let range = range.to_lowercase();
+    match range.split_once('/') {
+        _ if range == content_type || range == "*/*" => true,
+        Some((kind, "*")) => content_type.split('/').next() == Some(kind),
+        _ => false,
+    }
+}
+
+/// Headers are text: read them as the type their schema declares.
+fn header_value(schema: &Value, value: &str) -> Value {
+    match schema["type"].as_str() {
+        Some("integer") | Some("number") => serde_json::from_str(value).unwrap_or_else(|_| Value::from(value)),
+        Some("boolean") => value.parse::<bool>().map(Value::Bool).unwrap_or_else(|_| Value::from(value)),
+        _ => Value::from(value),
+    }
+}
+
+impl Parameter {
+    /// Whether `to_request` writes the parameter: optional headers and cookies are left out.
+    fn is_scaffolded(&self) -> bool {
+        match self.location {
+            ParameterLocation::Path => true,
+            ParameterLocation::Query => self.required || self.value().is_some(),
+            ParameterLocation::Header | ParameterLocation::Cookie => self.required,
+        }
+    }
+
+    /// The example, default or first allowed value of the parameter, if the document has one.
+    pub fn value(&self) -> Option<Value> {
+        self.example.clone().or_else(|| declared_value(&self.schema))
+    }
+}
+
+impl MediaType {
+    fn example_body(&self) -> Option<Body> {
+        let example = self.example.clone().unwrap_or_else(|| example(&self.schema));
+        let content_type = self.content_type.to_lowercase();
+        if content_type.contains("json") {
+            Some(Body::Text(serde_json::to_string_pretty(&example).unwrap_or_default()))
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            let fields = example.as_object().map(|o| o.iter().map(|(k, v)| (k.clone(), scalar(v))).collect()).unwrap_or_default();
+            Some(Body::Form(fields))
+        } else if let Value::String(text) = example {
+            Some(Body::Text(text))
+        } else {
+            None
+        }
+    }
+}
+
+/// Generates an example value of a JSON schema, preferring the values the schema declares.
+pub fn example(schema: &Value) -> Value {
+    example_at(schema, 0)
+}
+
+// Deep enough for real payloads, shallow enough to stop on self-referencing schemas
+const MAX_EXAMPLE_DEPTH: usize = 8;
+
+fn example_at(schema: &Value, depth: usize) -> Value {
+    if let Some(value) = declared_value(schema) {
+        return value;
+    }
+    if depth > MAX_EXAMPLE_DEPTH {
+        return Value::Null;
+    }
+    if let Some(all) = schema["allOf"].as_array() {
+        let mut merged = Map::new();
+        for part in all {
+            if let Value::Object(fields) = example_at(part, depth) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+    if let Some(first) = schema["oneOf"].as_array().or_else(|| schema["anyOf"].as_array()).and_then(|s| s.first()) {
+        return example_at(first, depth);
+    }
+    let kind = match &schema["type"] {
+        Value::String(kind) => kind.as_str(),
+        // OpenAPI 3.1 allows `["string", "null"]`
+        Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).find(|k| *k != "null").unwrap_or("null"),
+        _ if schema.get("properties").is_some() => "object",
+        _ if schema.get("items").is_some() => "array",
+        _ => "",
+    };
+    match kind {
+        "object" => Value::Object(
+            schema["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(_, property)| property["readOnly"] != Value::Bool(true))
+                .map(|(name, property)| (name.clone(), example_at(property, depth + 1)))
+                .collect(),
+        ),
+        "array" => Value::Array(vec![example_at(&schema["items"], depth + 1)]),
+        "integer" => Value::from(schema["minimum"].as_i64().unwrap_or(0)),
+        "number" => Value::from(schema["minimum"].as_f64().unwrap_or(0.0)),
+        "boolean" => Value::Bool(true),
+        "string" => Value::from(match schema["format"].as_str().unwrap_or_default() {
+            "date-time" => "2024-01-01T00:00:00Z",
+            "date" => "2024-01-01",
+            "email" => "user@example.com",
+            "uuid" => "00000000-0000-0000-0000-000000000000",
+            "uri" | "url" => "https://example.com",
+            _ => "string",
+        }),
+        _ => Value::Null,
+    }
+}
+
+fn declared_value(schema: &Value) -> Option<Value> {
+    schema
+        .get("example")
+        .or_else(|| schema.get("default"))
+        .or_else(|| schema.get("const"))
+        .or_else(|| schema["enum"].as_array().and_then(|e| e.first()))
+        .cloned()
+}
+
+/// A value as it is written in a URL or `.env` file.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::domain::repository::MockSchemaValidator;
+
+    fn parameter(name: &str, location: ParameterLocation, required: bool, schema: Value) -> Parameter {
+        Parameter { name: name.to_string(), location, required, schema, example: None }
+    }
+
+    #[test]
+    fn test_example_from_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "readOnly": true },
+                "name": { "type": "string", "example": "Ada" },
+                "email": { "type": "string", "format": "email" },
+                "role": { "type": "string", "enum": ["admin", "user"] },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "address": { "allOf": [{ "properties": { "city": { "type": "string" } } }, { "properties": { "zip": { "type": "integer" } } }] }
+            }
+        });
+        assert_eq!(example(&schema), json!({
+            "name": "Ada",
+            "email": "user@example.com",
+            "role": "admin",
+            "tags": ["string"],
+            "address": { "city": "string", "zip": 0 }
+        }));
+    }
+
+    #[test]
+    fn test_operation_to_request() {
+        let operation = Operation {
+            id: "update_user".to_string(),
+            method: "PUT".to_string(),
+            path: "/users/{id}".to_string(),
+            parameters: vec![
+                Parameter { example: Some(json!(42)), ..parameter("id", ParameterLocation::Path, true, json!({ "type": "integer" })) },
+                parameter("dryRun", ParameterLocation::Query, true, json!({ "type": "boolean", "default": false })),
+                parameter("limit", ParameterLocation::Query, false, json!({ "type": "integer" })),
+                parameter("X-Request-Id", ParameterLocation::Header, true, json!({ "type": "string" })),
+                parameter("X-Debug", ParameterLocation::Header, false, json!({ "type": "string" })),
+            ],
+            request_body: Some(MediaType {
+                content_type: "application/json".to_string(),
+                schema: json!({ "type": "object", "properties": { "name": { "type": "string" } } }),
+                example: None,
+            }),
+            security: Some(SecurityScheme::Bearer),
+            responses: vec![],
+        };
+
+        let request = operation.to_request();
+        assert_eq!(request.url, "{{baseUrl}}/users/{{id}}?dryRun={{dryRun}}");
+        assert_eq!(request.headers["X-Request-Id"], "{{X-Request-Id}}");
+        assert_eq!(request.headers["Content-Type"], "application/json");
+        assert_eq!(request.headers.len(), 2);
+        assert_eq!(request.auth, Some(Auth::Bearer { token: "{{token}}".to_string() }));
+        assert_eq!(request.body, Some(Body::Text("{\n  \"name\": \"string\"\n}".to_string())));
+
+        assert_eq!(operation.variables(), vec![
+            ("id".to_string(), "42".to_string()),
+            ("dryRun".to_string(), "false".to_string()),
+            ("X-Request-Id".to_string(), String::new()),
+        ]);
+        assert_eq!(operation.secrets(), vec![("token".to_string(), String::new())]);
+    }
+
+    fn get(id: &str, path: &str) -> Operation {
+        Operation {
+            id: id.to_string(),
+            method: "GET".to_string(),
+            path: path.to_string(),
+            parameters: vec![],
+            request_body: None,
+            security: None,
+            responses: vec![],
+        }
+    }
+
+    #[test]
+    fn test_operation_for_url() {
+        let spec = ApiSpec {
+            title: "Shop".to_string(),
+            base_url: Some("https://api.shop.com/v1".to_string()),
+            operations: vec![get("get_user", "/users/{id}"), get("get_me", "/users/me"), get("list_users", "/users")],
+        };
+        let id = |method: &str, url: &str| spec.operation_for(method, url).map(|o| o.id.as_str());
+        assert_eq!(id("GET", "{{baseUrl}}/users/{{id}}"), Some("get_user"));
+        assert_eq!(id("GET", "https://staging.shop.com/v1/users/42?expand=true"), Some("get_user"));
+        assert_eq!(id("get", "https://api.shop.com/v1/users/me"), Some("get_me"));
+        assert_eq!(id("GET", "https://api.shop.com/v1/users"), Some("list_users"));
+        assert_eq!(id("DELETE", "https://api.shop.com/v1/users"), None);
+        assert_eq!(id("GET", "https://api.shop.com/v1/orders"), None);
+    }
+
+    #[test]
+    fn test_validate_response() {
+        let mut operation = get("get_user", "/users/{id}");
+        operation.responses = vec![
+            ApiResponse {
+                status: "200".to_string(),
+                headers: vec![parameter("X-Rate-Limit", ParameterLocation::Header, true, json!({ "type": "integer" }))],
+                content: vec![MediaType {
+                    content_type: "application/json".to_string(),
+                    schema: json!({ "type": "object", "required": ["id"], "properties": { "id": { "type": "integer" } } }),
+                    example: None,
+                }],
+            },
+            ApiResponse { status: "4XX".to_string(), headers: vec![], content: vec![] },
+        ];
+        let response = |status: u16, headers: &[(&str, &str)], body: &str| {
+            let mut response = Response::new(status, String::new(), body.to_string(), std::time::Duration::ZERO);
+            response.headers = headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            response
+        };
+
+        // Checks only what this test declares: integers, at the top level or as `id`
+        let mut validator = MockSchemaValidator::new();
+        validator.expect_validate().returning(|schema, value| {
+            let (schema, value, path) = match schema.get("properties") {
+                Some(properties) => (&properties["id"], &value["id"], "$.id"),
+                None => (schema, value, "$"),
+            };
+            match schema["type"] == "integer" && !value.is_i64() {
+                true => vec![format!("{}: expected integer", path)],
+                false => vec![],
+            }
+        });
+
+        let ok = response(200, &[("content-type", "application/json; charset=utf-8"), ("x-rate-limit", "10")], r#"{"id": 1}"#);
+        assert_eq!(operation.validate_response(&ok, &validator), Vec::<String>::new());
+        assert_eq!(operation.validate_response(&response(404, &[], "Not found"), &validator), Vec::<String>::new());
+
+        let drifted = response(200, &[("Content-Type", "application/json"), ("X-Rate-Limit", "many")], r#"{"id": "1"}"#);
+        assert_eq!(operation.validate_response(&drifted, &validator), vec![
+            "header X-Rate-Limit: expected integer",
+            "body $.id: expected integer",
+        ]);
+        assert_eq!(operation.validate_response(&response(200, &[("Content-Type", "text/html")], "<html>"), &validator), vec![
+            "header X-Rate-Limit is missing",
+            "content type text/html is not declared (expected application/json)",
+        ]);
+        assert_eq!(operation.validate_response(&response(200, &[("X-Rate-Limit", "1")], ""), &validator), vec![
+            "body is empty (expected application/json)",
+        ]);
+        assert_eq!(
+            operation.validate_response(&response(500, &[], ""), &validator),
+            vec!["status 500 is not declared (expected 200, 4XX)"]
+        );
+    }
+}
@@ -4,17 +4,49 @@ use mockall::automock;
 use super::request::Request;
 use super::response::Response;
 use super::project::Project;
+use super::environment::Environment;
+use super::oauth2::{OAuth2Config, OAuth2Token};
+use super::graphql::GraphQLSchema;
+use super::websocket::WebSocketConnection;
+use super::stream::ResponseStream;
+use super::grpc::ProtoSource;
+use super::history::HistoryEntry;
+use super::snapshot::Snapshot;
+use super::mock::{MockHit, MockResponse, MockRouter};
+use super::capture::{ArchivedCall, CapturedCall};
+use super::collection::Collection;
+use super::openapi::ApiSpec;
+use super::trash::TrashEntry;
+use super::watch::ProjectChange;
 use anyhow::Result;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// Repository for managing Projects.
 #[cfg_attr(test, automock)]
 pub trait ProjectRepository: Send + Sync {
     /// Lists all available projects.
     fn list_projects(&self) -> Result<Vec<Project>>;
-    /// Lists all request IDs (names) in a project.
+    /// Lists all request IDs in a project, including sub-folders: `users/get_user`.
     fn list_requests(&self, project: &Project) -> Result<Vec<String>>;
+    /// Lists the folders of a project as relative paths, empty ones included.
+    fn list_folders(&self, project: &Project) -> Result<Vec<String>>;
     /// Creates a new project (directory).
     fn create_project(&self, name: &str) -> Result<()>;
+    /// Creates a folder (and its parents) in a project.
+    fn create_folder(&self, project: &Project, path: &str) -> Result<()>;
+    /// Moves a folder with everything in it, e.g. `users` to `admin/users`.
+    fn rename_folder(&self, project: &Project, from: &str, to: &str) -> Result<()>;
+    /// Where a folder and its response history are stored, relative to the projects root.
+    fn folder_paths(&self, project: &Project, path: &str) -> Vec<PathBuf>;
+    /// Renames a project directory.
+    fn rename_project(&self, project: &Project, to: &str) -> Result<()>;
+    /// Copies a project with its requests and environment, leaving out the response history.
+    fn duplicate_project(&self, project: &Project, to: &str) -> Result<()>;
+    /// Where a project is stored, relative to the projects root.
+    fn project_paths(&self, project: &Project) -> Vec<PathBuf>;
 }
 
 /// Repository for loading and saving Requests.
@@ -22,8 +54,147 @@ pub trait ProjectRepository: Send + Sync {
 pub trait RequestRepository: Send + Sync {
     /// Loads a request by ID within a project.
     fn load(&self, project: &Project, request_id: &str) -> Result<Request>;
+    /// Reads the text of a request file as written, for searching.
+    fn read_source(&self, project: &Project, request_id: &str) -> Result<String>;
     /// Saves a request by ID within a project.
     fn save(&self, project: &Project, request_id: &str, request: &Request) -> Result<()>;
+    /// Moves a request to another ID, possibly in another folder, with the files kept beside it.
+    fn rename(&self, project: &Project, from: &str, to: &str) -> Result<()>;
+    /// Copies a request file to another ID; the files kept beside it are not copied.
+    fn duplicate(&self, project: &Project, from: &str, to: &str) -> Result<()>;
+    /// Where a request and the files kept beside it are stored, relative to the projects root.
+    fn paths(&self, project: &Project, request_id: &str) -> Vec<PathBuf>;
+}
+
+/// Notices changes to the projects on disk.
+#[cfg_attr(test, automock)]
+pub trait ProjectWatcher: Send + Sync {
+    /// Starts watching in the background, reporting each change to the sink as it happens.
+    fn watch(&self, sink: Arc<dyn ProjectChangeSink>) -> Result<()>;
+}
+
+/// Receives the changes a `ProjectWatcher` notices, from the thread it watches on.
+#[cfg_attr(test, automock)]
+pub trait ProjectChangeSink: Send + Sync {
+    fn changed(&self, change: ProjectChange);
+}
+
+/// Holds what was deleted so it can be put back.
+#[cfg_attr(test, automock)]
+pub trait TrashRepository: Send + Sync {
+    /// Moves paths of the projects root into a new entry; missing ones are skipped.
+    fn put(&self, label: &str, paths: &[PathBuf]) -> Result<TrashEntry>;
+    /// Moves the content of an entry back where it was, unless something took its place.
+    fn restore(&self, entry: &TrashEntry) -> Result<()>;
+    /// Removes for good the entries put longer than `max_age` ago.
+    fn purge(&self, max_age: Duration) -> Result<()>;
+}
+
+/// Repository for loading the variables and secrets of a project.
+#[cfg_attr(test, automock)]
+pub trait EnvironmentRepository: Send + Sync {
+    /// Loads the environment of a project.
+    fn load(&self, project: &Project) -> Result<Environment>;
+    /// Adds variables to the project, to its gitignored secrets file when `secret`.
+    /// Variables already defined keep their value.
+    fn add_variables(&self, project: &Project, variables: &[(String, String)], secret: bool) -> Result<()>;
+}
+
+/// Repository caching the GraphQL schema of each project.
+#[cfg_attr(test, automock)]
+pub trait SchemaRepository: Send + Sync {
+    /// Loads the cached schema of a project, if it was introspected before.
+    fn load(&self, project: &Project) -> Result<Option<GraphQLSchema>>;
+    /// Stores the raw introspection response of a project, already read as a schema.
+    fn save(&self, project: &Project, introspection: &str) -> Result<()>;
+}
+
+/// Repository locating the `.proto` definitions of a project.
+#[cfg_attr(test, automock)]
+pub trait ProtoRepository: Send + Sync {
+    /// Returns the proto files of a project, or `None` to fall back to server reflection.
+    fn find(&self, project: &Project, environment: &Environment) -> Result<Option<ProtoSource>>;
+}
+
+/// Repository keeping past responses of each request, and the baseline pinned for it.
+#[cfg_attr(test, automock)]
+pub trait HistoryRepository: Send + Sync {
+    /// Records a response of a request.
+    fn append(&self, project: &Project, request_id: &str, entry: &HistoryEntry) -> Result<()>;
+    /// Lists the recorded responses of a request, oldest first.
+    fn list(&self, project: &Project, request_id: &str) -> Result<Vec<HistoryEntry>>;
+    /// Loads the pinned baseline of a request, if any.
+    fn load_baseline(&self, project: &Project, request_id: &str) -> Result<Option<HistoryEntry>>;
+    /// Pins a response as the baseline of a request.
+    fn save_baseline(&self, project: &Project, request_id: &str, entry: &HistoryEntry) -> Result<()>;
+}
+
+/// Repository for the approved response snapshot of each request.
+#[cfg_attr(test, automock)]
+pub trait SnapshotRepository: Send + Sync {
+    fn load(&self, project: &Project, request_id: &str) -> Result<Option<Snapshot>>;
+    fn save(&self, project: &Project, request_id: &str, snapshot: &Snapshot) -> Result<()>;
+}
+
+/// Repository for the hand-written example responses (`.mock` files) of requests.
+#[cfg_attr(test, automock)]
+pub trait MockRepository: Send + Sync {
+    fn load(&self, project: &Project, request_id: &str) -> Result<Option<MockResponse>>;
+    fn save(&self, project: &Project, request_id: &str, response: &MockResponse) -> Result<()>;
+}
+
+/// Reads and writes HTTP archives (`.har` files) of browser devtools and proxies.
+#[cfg_attr(test, automock)]
+pub trait HarRepository: Send + Sync {
+    fn read(&self, path: &Path) -> Result<Vec<ArchivedCall>>;
+    fn write(&self, path: &Path, calls: &[ArchivedCall]) -> Result<()>;
+}
+
+/// Reads collections exported from other API clients.
+#[cfg_attr(test, automock)]
+pub trait CollectionRepository: Send + Sync {
+    fn read(&self, path: &Path) -> Result<Collection>;
+}
+
+/// Tells the current time, so that expiry and timestamps can be tested.
+#[cfg_attr(test, automock)]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// Compiles search queries into matchers.
+#[cfg_attr(test, automock)]
+pub trait PatternCompiler: Send + Sync {
+    /// Plain text matches anywhere ignoring case, `regex` takes the query as a regular expression.
+    fn compile(&self, query: &str, regex: bool) -> Result<Box<dyn super::search::Matcher>>;
+}
+
+/// Repository caching OAuth2 tokens between runs.
+#[cfg_attr(test, automock)]
+pub trait TokenCache: Send + Sync {
+    /// Loads the cached token for a key, if any.
+    fn load(&self, key: &str) -> Result<Option<OAuth2Token>>;
+    /// Stores a token under a key.
+    fn save(&self, key: &str, token: &OAuth2Token) -> Result<()>;
+}
+
+/// Gateway for talking to an OAuth2 authorization server.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait OAuth2Client: Send + Sync {
+    /// Obtains a new token using the configured grant.
+    async fn fetch_token(&self, config: &OAuth2Config) -> Result<OAuth2Token>;
+    /// Exchanges a refresh token for a new token.
+    async fn refresh_token(&self, config: &OAuth2Config, refresh_token: &str) -> Result<OAuth2Token>;
+}
+
+/// Signs a request after variable substitution and right before it is sent.
+#[cfg_attr(test, automock)]
+pub trait RequestSigner: Send + Sync {
+    /// Name requests use to select this signer (`# @sign <name>`).
+    fn name(&self) -> &str;
+    /// Signs the request in place, typically by adding headers.
+    fn sign(&self, request: &mut Request, environment: &Environment) -> Result<()>;
 }
 
 /// Gateway for sending HTTP requests.
@@ -32,6 +203,80 @@ pub trait RequestRepository: Send + Sync {
 pub trait HttpClient: Send + Sync {
     /// Sends the request and returns the response.
     async fn send(&self, request: &Request) -> Result<Response>;
+    /// Sends the request and returns once the response head arrives, streaming the body.
+    async fn send_stream(&self, request: &Request) -> Result<ResponseStream>;
+}
+
+/// Gateway for opening WebSocket connections.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait WebSocketClient: Send + Sync {
+    /// Performs the handshake described by the request and returns the open connection.
+    async fn connect(&self, request: &Request) -> Result<Box<dyn WebSocketConnection>>;
+}
+
+/// Gateway for making gRPC calls with JSON messages.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait GrpcClient: Send + Sync {
+    /// Calls the method named by the request URL. Without `protos`, the server's reflection service is used.
+    /// Non-OK statuses are returned as responses, not errors.
+    async fn call(&self, request: &Request, protos: Option<ProtoSource>) -> Result<Response>;
+}
+
+/// Checks values against the JSON schemas of an OpenAPI document.
+#[cfg_attr(test, automock)]
+pub trait SchemaValidator: Send + Sync {
+    /// Returns one message per violation, each starting with the JSON path of the offending value.
+    fn validate(&self, schema: &serde_json::Value, value: &serde_json::Value) -> Vec<String>;
+}
+
+/// Repository reading OpenAPI documents, and keeping the one a project was imported from.
+#[cfg_attr(test, automock)]
+pub trait ApiSpecRepository: Send + Sync {
+    /// Whether the file is an OpenAPI or Swagger document.
+    fn is_spec(&self, path: &Path) -> bool;
+    /// Reads an OpenAPI 3 or Swagger 2 document.
+    fn read(&self, path: &Path) -> Result<ApiSpec>;
+    /// Stores a copy of the document in the project, replacing the previous one.
+    fn save(&self, project: &Project, path: &Path) -> Result<()>;
+    /// Loads the document of a project, if one was imported.
+    fn load(&self, project: &Project) -> Result<Option<ApiSpec>>;
+}
+
+/// Receives what the mock server does, as it happens.
+#[cfg_attr(test, automock)]
+pub trait MockHitSink: Send + Sync {
+    /// The server is bound and accepting calls.
+    fn listening(&self, address: SocketAddr);
+    /// A call was answered.
+    fn hit(&self, hit: MockHit);
+}
+
+/// HTTP server answering calls from a `MockRouter`.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait MockServer: Send + Sync {
+    /// Binds `address` and serves until accepting fails, reporting to `sink`.
+    async fn serve(&self, address: SocketAddr, router: Arc<MockRouter>, sink: Arc<dyn MockHitSink>) -> Result<()>;
+}
+
+/// Receives what the recording proxy does, as it happens.
+#[cfg_attr(test, automock)]
+pub trait CaptureSink: Send + Sync {
+    /// The proxy is bound and accepting calls.
+    fn listening(&self, address: SocketAddr);
+    /// A call was passed through.
+    fn captured(&self, call: CapturedCall);
+}
+
+/// HTTP proxy passing calls through to their upstream.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait ProxyServer: Send + Sync {
+    /// Binds `address` and serves until accepting fails, reporting every passed call to `sink`.
+    /// Calls with a relative path go to `upstream`; absolute-form calls (`HTTP_PROXY`) go where they point.
+    async fn serve(&self, address: SocketAddr, upstream: Option<String>, sink: Arc<dyn CaptureSink>) -> Result<()>;
 }
 
 /// Gateway for interacting with an external editor.
@@ -41,5 +286,6 @@ pub trait Editor: Send + Sync {
     /// Note: This still maps to a physical file, so the implementation needs to resolve it.
     /// Ideally the Editor trait should perhaps take a Project + RequestId too, 
     /// but for now let's abstract it: `edit(&self, project: &Project, request_id: &str)`.
-    fn edit(&self, project: &Project, request_id: &str) -> Result<()>;
+    /// `line` (1-based) places the cursor, for editors known to accept it.
+    fn edit(&self, project: &Project, request_id: &str, line: Option<usize>) -> Result<()>;
 }
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use super::auth::Auth;
+use super::body::Body;
+
 /// Represents an HTTP Request in the domain.
 /// This is a pure data structure.
 #[derive(Debug, Clone, PartialEq)]
@@ -7,7 +10,16 @@ pub struct Request {
     pub method: String,
     pub url: String,
     pub headers: HashMap<String, String>,
-    pub body: Option<String>,
+    pub body: Option<Body>,
+    pub auth: Option<Auth>,
+    /// Name of the `RequestSigner` to run before sending (`# @sign <name>`).
+    pub signer: Option<String>,
+    /// Show the response while it arrives (`# @stream`).
+    pub stream: bool,
+    /// Volatile response fields left out of diffs (`# @diff-ignore <path>...`).
+    pub diff_ignore: Vec<String>,
+    /// Response fields masked in snapshots (`# @snapshot-redact <path>...`).
+    pub snapshot_redact: Vec<String>,
 }
 
 impl Request {
@@ -17,6 +29,34 @@ impl Request {
             url: url.into(),
             headers: HashMap::new(),
             body: None,
+            auth: None,
+            signer: None,
+            stream: false,
+            diff_ignore: Vec::new(),
+            snapshot_redact: Vec::new(),
         }
     }
+
+    /// Whether the response should be streamed: `# @stream` or an SSE `Accept` header.
+    pub fn is_streaming(&self) -> bool {
+        self.stream
+            || self
+                .headers
+                .iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case("accept") && v.to_lowercase().contains("text/event-stream"))
+    }
+
+    /// Whether this is a `GRPC host/package.Service/Method` call.
+    pub fn is_grpc(&self) -> bool {
+        self.method.eq_ignore_ascii_case("grpc")
+    }
+
+    /// Whether this request opens a WebSocket (`WS` method or a `ws://`/`wss://` URL).
+    pub fn is_websocket(&self) -> bool {
+        let url = self.url.to_lowercase();
+        self.method.eq_ignore_ascii_case("ws")
+            || self.method.eq_ignore_ascii_case("wss")
+            || url.starts_with("ws://")
+            || url.starts_with("wss://")
+    }
 }
@@ -9,6 +9,10 @@ pub struct Response {
     pub headers: HashMap<String, String>,
     pub body: String,
     pub latency: Duration,
+    /// `errors[].message` of a GraphQL response, reported apart from the HTTP status.
+    pub graphql_errors: Vec<String>,
+    /// Trailing metadata, e.g. of a gRPC call.
+    pub trailers: HashMap<String, String>,
 }
 
 impl Response {
@@ -19,6 +23,8 @@ impl Response {
             headers: HashMap::new(),
             body,
             latency,
+            graphql_errors: Vec::new(),
+            trailers: HashMap::new(),
         }
     }
 }
@@ -0,0 +1,57 @@
+use super::project::Project;
+use std::ops::Range;
+
+/// A line of a request file matching a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub project: Project,
+    pub request_id: String,
+    /// 1-based, as editors count.
+    pub line: usize,
+    pub text: String,
+    /// Byte ranges of the matches within `text`.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// A compiled search query.
+pub trait Matcher: Send + Sync {
+    /// Byte ranges of the matches within a line, in order.
+    fn find(&self, line: &str) -> Vec<Range<usize>>;
+}
+
+/// The matching lines of a file: line number, text and the ranges matched.
+pub fn matching_lines<'a>(matcher: &dyn Matcher, source: &'a str) -> Vec<(usize, &'a str, Vec<Range<usize>>)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let ranges: Vec<Range<usize>> = matcher.find(line).into_iter().filter(|r| !r.is_empty()).collect();
+            (!ranges.is_empty()).then_some((i + 1, line, ranges))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "# @name orders\nGET https://api.shop.com/v2/orders?page=1\nX-Api-Version: 2\n\n{\"orders\": []}";
+
+    struct Text(&'static str);
+
+    impl Matcher for Text {
+        fn find(&self, line: &str) -> Vec<Range<usize>> {
+            line.match_indices(self.0).map(|(i, m)| i..i + m.len()).collect()
+        }
+    }
+
+    #[test]
+    fn test_matching_lines() {
+        let lines = matching_lines(&Text("orders"), SOURCE);
+        let numbers: Vec<usize> = lines.iter().map(|(n, _, _)| *n).collect();
+        assert_eq!(numbers, vec![1, 2, 5]);
+        assert_eq!(lines[1], (2, "GET https://api.shop.com/v2/orders?page=1", vec![Range { start: 28, end: 34 }]));
+        // Empty matches mark nothing
+        assert!(matching_lines(&Text(""), SOURCE).is_empty());
+    }
+}
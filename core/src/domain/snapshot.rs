@@ -0,0 +1,221 @@
+use super::diff::{diff_responses, mask_fields, ResponseDiff};
+use super::response::Response;
+use anyhow::{Context, Result};
+
+/// Appended to the request file name: `users.http` is snapshotted in `users.http.snap`.
+pub const SNAPSHOT_SUFFIX: &str = ".snap";
+/// Replaces the fields named by `@snapshot-redact`.
+pub const REDACTED_FIELD: &str = "[redacted]";
+
+/// An approved response, normalized so it only changes when the API does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+impl Snapshot {
+    /// Normalizes a response: JSON is pretty-printed with sorted keys and `fields` masked,
+    /// and timestamps and UUIDs are replaced everywhere.
+    pub fn capture(response: &Response, fields: &[String]) -> Self {
+        let body = match serde_json::from_str(&response.body) {
+            Ok(json) => serde_json::to_string_pretty(&mask_fields(&json, fields, REDACTED_FIELD)).unwrap_or_default(),
+            Err(_) => response.body.clone(),
+        };
+        let content_type = response.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")).map(|(_, v)| v.clone());
+        Self { status: response.status, content_type, body: mask_volatile(&body) }
+    }
+
+    /// Formats the snapshot file: a status line, the content type and the body.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("HTTP {}\n", self.status);
+        if let Some(content_type) = &self.content_type {
+            text.push_str(&format!("Content-Type: {}\n", content_type));
+        }
+        text.push('\n');
+        text.push_str(&self.body);
+        text.push('\n');
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let (head, body) = text.split_once("\n\n").unwrap_or((text.trim_end(), ""));
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|l| l.strip_prefix("HTTP "))
+            .context("Snapshot must start with `HTTP <status>`")?;
+        let status = status.trim().parse().with_context(|| format!("Invalid snapshot status: {}", status))?;
+        let content_type = lines
+            .filter_map(|l| l.split_once(':'))
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.trim().to_string());
+        let body = body.strip_suffix('\n').unwrap_or(body).to_string();
+        Ok(Self { status, content_type, body })
+    }
+
+    fn to_response(&self) -> Response {
+        let mut response = Response::new(self.status, String::new(), self.body.clone(), Default::default());
+        response.headers.extend(self.content_type.clone().map(|v| ("Content-Type".to_string(), v)));
+        response
+    }
+}
+
+/// Outcome of comparing a response with the approved snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotResult {
+    /// Nothing approved yet; this is what would be recorded.
+    New(Snapshot),
+    Match,
+    Mismatch { expected: Snapshot, actual: Snapshot },
+}
+
+impl SnapshotResult {
+    pub fn compare(expected: Option<Snapshot>, actual: Snapshot) -> Self {
+        match expected {
+            None => Self::New(actual),
+            Some(expected) if expected == actual => Self::Match,
+            Some(expected) => Self::Mismatch { expected, actual },
+        }
+    }
+
+    /// What changed against the snapshot, for a mismatch.
+    pub fn diff(&self) -> Option<ResponseDiff> {
+        match self {
+            Self::Mismatch { expected, actual } => Some(diff_responses(&expected.to_response(), &actual.to_response(), &[])),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces ISO 8601 timestamps and UUIDs, which differ on every run.
+fn mask_volatile(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut masked = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        // Both start at a word boundary
+        let starts_word = i == 0 || !is_word(bytes[i - 1]);
+        let found = if starts_word {
+            timestamp_len(&bytes[i..]).map(|len| (len, "[timestamp]")).or_else(|| uuid_len(&bytes[i..]).map(|len| (len, "[uuid]")))
+        } else {
+            None
+        };
+        match found {
+            Some((len, replacement)) => {
+                masked.push_str(replacement);
+                // Matches are ASCII, so this ends on a character boundary
+                chars.nth(len - 2);
+            }
+            None => masked.push(c),
+        }
+    }
+    masked
+}
+
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Whether `bytes` starts with `n` ASCII digits, as an `Option` for `?`.
+fn digits(bytes: &[u8], n: usize) -> Option<()> {
+    (bytes.len() >= n && bytes[..n].iter().all(u8::is_ascii_digit)).then_some(())
+}
+
+/// `2024-05-01T10:20:30`, with optional fraction and offset (`.123`, `Z`, `+02:00`, `-0500`).
+fn timestamp_len(bytes: &[u8]) -> Option<usize> {
+    let at = |i: usize| bytes.get(i).copied();
+    digits(bytes, 4)?;
+    (at(4) == Some(b'-')).then_some(())?;
+    digits(&bytes[5..], 2)?;
+    (at(7) == Some(b'-')).then_some(())?;
+    digits(&bytes[8..], 2)?;
+    matches!(at(10), Some(b'T' | b' ')).then_some(())?;
+    digits(&bytes[11..], 2)?;
+    (at(13) == Some(b':')).then_some(())?;
+    digits(&bytes[14..], 2)?;
+    (at(16) == Some(b':')).then_some(())?;
+    digits(&bytes[17..], 2)?;
+    let mut len = 19;
+    if at(len) == Some(b'.') && at(len + 1).is_some_and(|b| b.is_ascii_digit()) {
+        len += 1;
+        while at(len).is_some_and(|b| b.is_ascii_digit()) {
+            len += 1;
+        }
+    }
+    match at(len) {
+        Some(b'Z') => len += 1,
+        Some(b'+' | b'-') if digits(&bytes[len + 1..], 2).is_some() => {
+            let minutes = if at(len + 3) == Some(b':') { len + 4 } else { len + 3 };
+            if digits(&bytes[minutes.min(bytes.len())..], 2).is_some() {
+                len = minutes + 2;
+            }
+        }
+        _ => {}
+    }
+    Some(len)
+}
+
+/// `0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2c`, ending at a word boundary.
+fn uuid_len(bytes: &[u8]) -> Option<usize> {
+    let mut len = 0;
+    for (i, group) in [8, 4, 4, 4, 12].into_iter().enumerate() {
+        if i > 0 {
+            (bytes.get(len) == Some(&b'-')).then_some(())?;
+            len += 1;
+        }
+        let hex = bytes.get(len..len + group)?;
+        hex.iter().all(u8::is_ascii_hexdigit).then_some(())?;
+        len += group;
+    }
+    (!bytes.get(len).copied().is_some_and(is_word)).then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn response(body: &str) -> Response {
+        let mut response = Response::new(200, "OK".to_string(), body.to_string(), Duration::ZERO);
+        response.headers.insert("Content-Type".to_string(), "application/json".to_string());
+        response
+    }
+
+    #[test]
+    fn test_capture_masks_volatile_values() {
+        let body = r#"{"when": "2024-05-01T10:20:30.123Z", "id": "0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2c", "seq": 7, "name": "a"}"#;
+        let snapshot = Snapshot::capture(&response(body), &["seq".to_string()]);
+        assert_eq!(
+            snapshot.body,
+            "{\n  \"id\": \"[uuid]\",\n  \"name\": \"a\",\n  \"seq\": \"[redacted]\",\n  \"when\": \"[timestamp]\"\n}"
+        );
+        assert_eq!(Snapshot::parse(&snapshot.to_text()).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_compare() {
+        let old = Snapshot::capture(&response(r#"{"name": "a", "at": "2024-01-01 00:00:00"}"#), &[]);
+        let same = Snapshot::capture(&response(r#"{"at": "2025-02-02 10:00:00", "name": "a"}"#), &[]);
+        let changed = Snapshot::capture(&response(r#"{"name": "b"}"#), &[]);
+
+        assert_eq!(SnapshotResult::compare(None, old.clone()), SnapshotResult::New(old.clone()));
+        assert_eq!(SnapshotResult::compare(Some(old.clone()), same), SnapshotResult::Match);
+        let mismatch = SnapshotResult::compare(Some(old), changed);
+        assert_eq!(mismatch.diff().unwrap().changes.len(), 2);
+    }
+
+    #[test]
+    fn test_mask_volatile() {
+        assert_eq!(
+            mask_volatile("at 2024-05-01 10:20:30+02:00, 2024-05-01T10:20:30.5-0500 or 2024-05-01T10:20:30Z."),
+            "at [timestamp], [timestamp] or [timestamp]."
+        );
+        assert_eq!(mask_volatile("é 0B9E4C3A-1d2f-4e5a-9b8c-7d6e5f4a3b2c é"), "é [uuid] é");
+        // Only whole words
+        assert_eq!(mask_volatile("x0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2c"), "x0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2c");
+        assert_eq!(mask_volatile("0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2cz"), "0b9e4c3a-1d2f-4e5a-9b8c-7d6e5f4a3b2cz");
+        assert_eq!(mask_volatile("2024-05-01 10:20"), "2024-05-01 10:20");
+    }
+}
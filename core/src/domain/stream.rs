@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// What a streaming response reports while its body arrives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// A piece of the body, with the time since the request was sent.
+    Chunk { text: String, elapsed: Duration },
+    /// The body is complete.
+    End { elapsed: Duration },
+    Error(String),
+}
+
+/// The events of a body still downloading. Dropping or closing them cancels the download.
+pub trait StreamEvents: Send {
+    /// What arrived since the last call, without waiting.
+    fn try_next(&mut self) -> Option<StreamEvent>;
+    /// Stops the download and closes its connection.
+    fn close(&mut self);
+}
+
+/// A response whose head has arrived and whose body is still streaming.
+pub struct ResponseStream {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: HashMap<String, String>,
+    pub events: Box<dyn StreamEvents>,
+}
+
+impl ResponseStream {
+    /// Whether the server answered with Server-Sent Events.
+    pub fn is_event_stream(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case("content-type") && v.to_lowercase().starts_with("text/event-stream"))
+    }
+}
+
+/// A dispatched Server-Sent Event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub id: Option<String>,
+    pub data: String,
+    pub retry: Option<u64>,
+    /// Arrival time, relative to when the request was sent.
+    pub elapsed: Duration,
+}
+
+/// Incremental `text/event-stream` parser; chunks may split lines anywhere.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+    event: Option<String>,
+    id: Option<String>,
+    data: Vec<String>,
+    retry: Option<u64>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk and returns the events it completed.
+    pub fn feed(&mut self, chunk: &str, elapsed: Duration) -> Vec<SseEvent> {
+        self.buffer.push_str(chunk);
+        let mut events = Vec::new();
+
+        while let Some(end) = self.buffer.find(['\n', '\r']) {
+            // A trailing '\r' may still be followed by '\n' in the next chunk
+            if &self.buffer[end..] == "\r" {
+                break;
+            }
+            let line = self.buffer[..end].to_string();
+            let skip = if self.buffer[end..].starts_with("\r\n") { 2 } else { 1 };
+            self.buffer.drain(..end + skip);
+            if let Some(event) = self.process_line(&line, elapsed) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str, elapsed: Duration) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch(elapsed);
+        }
+        if line.starts_with(':') {
+            return None; // Comment, often used as keep-alive
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            "retry" => self.retry = value.parse().ok().or(self.retry),
+            _ => {} // Unknown fields are ignored
+        }
+        None
+    }
+
+    fn dispatch(&mut self, elapsed: Duration) -> Option<SseEvent> {
+        if self.data.is_empty() {
+            self.event = None;
+            return None;
+        }
+        Some(SseEvent {
+            event: self.event.take(),
+            // The last event ID persists across events
+            id: self.id.clone(),
+            data: std::mem::take(&mut self.data).join("\n"),
+            retry: self.retry.take(),
+            elapsed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        let ms = Duration::from_millis;
+
+        assert!(parser.feed(": keep-alive\nevent: delta\nid: 1\nda", ms(10)).is_empty());
+        let events = parser.feed("ta: {\"text\": \"he\"}\ndata: llo\r\n\r", ms(20));
+        assert!(events.is_empty(), "the event ends only with the blank line");
+
+        let events = parser.feed("\nretry: 3000\ndata:done\n\n", ms(30));
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("delta".to_string()),
+                    id: Some("1".to_string()),
+                    data: "{\"text\": \"he\"}\nllo".to_string(),
+                    retry: None,
+                    elapsed: ms(30),
+                },
+                SseEvent { event: None, id: Some("1".to_string()), data: "done".to_string(), retry: Some(3000), elapsed: ms(30) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunks_ending_in_multibyte_characters_or_cr() {
+        let mut parser = SseParser::new();
+        let ms = Duration::from_millis;
+
+        assert!(parser.feed("data: caf\u{e9}", ms(1)).is_empty());
+        assert!(parser.feed("\r", ms(2)).is_empty());
+        assert!(parser.feed("\ndata: \u{1f600}", ms(3)).is_empty());
+        // A lone '\r' ends a line too
+        let events = parser.feed("\r\r\n", ms(4));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "caf\u{e9}\n\u{1f600}");
+    }
+}
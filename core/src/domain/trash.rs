@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+/// A deleted project, folder or request kept in the trash until it is restored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    /// Identifies the entry within the trash.
+    pub id: String,
+    /// What was deleted, e.g. `shop/users/get_user`.
+    pub label: String,
+}
+
+/// How long deleted items are kept before the trash drops them.
+pub const TRASH_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
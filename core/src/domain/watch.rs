@@ -0,0 +1,8 @@
+/// A change below the projects root made outside of htup: an editor saving, a `git pull`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectChange {
+    /// Projects were added, removed or renamed.
+    Projects,
+    /// Requests, folders or the environment of a project changed.
+    Project(String),
+}
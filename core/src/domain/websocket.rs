@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::time::SystemTime;
+
+/// Line separating the predefined messages in the body of a WebSocket request.
+pub const MESSAGE_SEPARATOR: &str = "===";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// A text frame in the message log of a session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsMessage {
+    pub direction: Direction,
+    pub text: String,
+    pub timestamp: SystemTime,
+}
+
+/// What the server side of a connection reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsEvent {
+    Message(String),
+    /// The connection ended, with the close reason or error.
+    Closed(String),
+}
+
+/// An open WebSocket connection, closed when dropped.
+pub trait WebSocketConnection: Send {
+    /// Queues a text frame; fails once the connection is gone.
+    fn send(&mut self, text: String) -> Result<()>;
+    /// What the server reported since the last call, without waiting.
+    fn try_next(&mut self) -> Option<WsEvent>;
+}
@@ -0,0 +1,70 @@
+//! Helpers shared by the infrastructure that sends or signs request bodies.
+
+use crate::domain::body::Body;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+
+/// Encodes form fields as `application/x-www-form-urlencoded`.
+pub fn encode_form(fields: &[(String, String)]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(fields)
+        .finish()
+}
+
+/// Builds the JSON payload of a GraphQL request.
+pub fn graphql_payload(query: &str, variables: Option<&str>) -> Result<String> {
+    let variables = match variables {
+        Some(variables) => serde_json::from_str(variables).context("GraphQL variables are not valid JSON")?,
+        None => serde_json::Value::Null,
+    };
+    Ok(serde_json::json!({ "query": query, "variables": variables }).to_string())
+}
+
+/// Returns the exact bytes that will be sent, for signing templates that embed the body.
+/// Multipart bodies get their boundary at send time, so they have no fixed payload (`None`).
+pub fn payload_bytes(body: Option<&Body>) -> Result<Option<Vec<u8>>> {
+    let bytes = match body {
+        None => Vec::new(),
+        Some(Body::Text(text)) => text.as_bytes().to_vec(),
+        Some(Body::Form(fields)) => encode_form(fields).into_bytes(),
+        Some(Body::File(path)) => fs::read(path).with_context(|| format!("Failed to read body file: {:?}", path))?,
+        Some(Body::GraphQL { query, variables }) => graphql_payload(query, variables.as_deref())?.into_bytes(),
+        // Messages are sent after the handshake, which itself has no body
+        Some(Body::Messages(_)) => Vec::new(),
+        Some(Body::Multipart { .. }) => return Ok(None),
+    };
+    Ok(Some(bytes))
+}
+
+/// Hashes the payload that will be sent, reading file bodies in chunks rather than whole.
+/// Multipart bodies have no fixed payload (`None`), as for `payload_bytes`.
+pub fn payload_sha256(body: Option<&Body>) -> Result<Option<Vec<u8>>> {
+    match body {
+        Some(Body::File(path)) => {
+            let mut file = fs::File::open(path).with_context(|| format!("Failed to read body file: {:?}", path))?;
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher).with_context(|| format!("Failed to read body file: {:?}", path))?;
+            Ok(Some(hasher.finalize().to_vec()))
+        }
+        body => Ok(payload_bytes(body)?.map(|bytes| Sha256::digest(bytes).to_vec())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_sha256_streams_files() {
+        let path = std::env::temp_dir().join(format!("htup-body-{}.bin", std::process::id()));
+        let content = vec![7u8; 200_000];
+        fs::write(&path, &content).unwrap();
+
+        let hash = payload_sha256(Some(&Body::File(path.clone()))).unwrap();
+        assert_eq!(hash, Some(Sha256::digest(&content).to_vec()));
+        assert_eq!(payload_sha256(Some(&Body::Text("abc".to_string()))).unwrap(), Some(Sha256::digest(b"abc").to_vec()));
+        fs::remove_file(&path).unwrap();
+    }
+}
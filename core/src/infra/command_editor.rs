@@ -1,6 +1,7 @@
 use crate::domain::{repository::Editor, project::Project};
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub struct SystemCommandEditor {
@@ -18,7 +19,7 @@ impl SystemCommandEditor {
 }
 
 impl Editor for SystemCommandEditor {
-    fn edit(&self, project: &Project, request_id: &str) -> Result<()> {
+    fn edit(&self, project: &Project, request_id: &str, line: Option<usize>) -> Result<()> {
         let path = self.resolve_path(project, request_id);
         
         let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
@@ -29,7 +30,7 @@ impl Editor for SystemCommandEditor {
         }
 
         let status = Command::new(&editor)
-            .arg(&path)
+            .args(editor_args(&editor, &path, line))
             .status()
             .with_context(|| format!("Failed to launch editor: {}", editor))?;
 
@@ -40,3 +41,34 @@ impl Editor for SystemCommandEditor {
         Ok(())
     }
 }
+
+/// The arguments opening `path` at `line`: `+12 file` for the terminal editors,
+/// `--goto file:12` for VS Code and `file:12` for Sublime Text and Helix. Others just get the file.
+fn editor_args(editor: &str, path: &Path, line: Option<usize>) -> Vec<OsString> {
+    let Some(line) = line else {
+        return vec![path.into()];
+    };
+    let name = Path::new(editor).file_stem().and_then(|n| n.to_str()).unwrap_or_default();
+    let mut with_line = path.as_os_str().to_owned();
+    with_line.push(format!(":{}", line));
+    match name {
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "emacsclient" | "kak" | "micro" | "joe" => vec![format!("+{}", line).into(), path.into()],
+        "code" | "codium" | "code-insiders" => vec!["--goto".into(), with_line],
+        "subl" | "hx" | "helix" => vec![with_line],
+        _ => vec![path.into()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_args() {
+        let path = Path::new("shop/get_user.http");
+        assert_eq!(editor_args("/usr/bin/nvim", path, Some(4)), vec![OsString::from("+4"), OsString::from("shop/get_user.http")]);
+        assert_eq!(editor_args("code", path, Some(4)), vec![OsString::from("--goto"), OsString::from("shop/get_user.http:4")]);
+        assert_eq!(editor_args("ed", path, Some(4)), vec![OsString::from("shop/get_user.http")]);
+        assert_eq!(editor_args("vim", path, None), vec![OsString::from("shop/get_user.http")]);
+    }
+}
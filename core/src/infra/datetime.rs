@@ -0,0 +1,102 @@
+//! UTC calendar conversions for the timestamps written in signatures and archives.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A UTC date and time, to the second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Civil {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+    pub hour: i64,
+    pub minute: i64,
+    pub second: i64,
+}
+
+impl Civil {
+    pub fn from_time(time: SystemTime) -> Self {
+        let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default() as i64;
+        let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+        // Civil date from days since the epoch (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self { year, month, day, hour: rem / 3_600, minute: rem % 3_600 / 60, second: rem % 60 }
+    }
+
+    /// Seconds since the epoch, the inverse of `from_time`.
+    pub fn to_epoch_seconds(&self) -> i64 {
+        let year = self.year - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = (self.month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+        days * 86_400 + self.hour * 3_600 + self.minute * 60 + self.second
+    }
+}
+
+/// Formats a time as `2024-05-01T12:30:00.123Z`.
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let c = Civil::from_time(time);
+    let millis = time.duration_since(UNIX_EPOCH).map(|d| d.subsec_millis()).unwrap_or_default();
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", c.year, c.month, c.day, c.hour, c.minute, c.second, millis)
+}
+
+/// Parses `2024-05-01T12:30:00Z`, with optional fractional seconds and a `+09:00` style offset.
+pub fn parse_rfc3339(text: &str) -> Option<SystemTime> {
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let civil = Civil {
+        year: number(0..4)?,
+        month: number(5..7)?,
+        day: number(8..10)?,
+        hour: number(11..13)?,
+        minute: number(14..16)?,
+        second: number(17..19)?,
+    };
+    let rest = text.get(19..)?;
+    let (fraction, zone) = match rest.strip_prefix('.') {
+        Some(rest) => rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len())),
+        None => ("", rest),
+    };
+    let nanos = if fraction.is_empty() { 0 } else { format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()? };
+    let offset = match zone {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match zone.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = zone[1..].split_once(':').unwrap_or((zone.get(1..3)?, zone.get(3..).unwrap_or("00")));
+            sign * (hours.parse::<i64>().ok()? * 3_600 + minutes.parse::<i64>().ok()? * 60)
+        }
+    };
+    let secs = u64::try_from(civil.to_epoch_seconds() - offset).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_714_566_600_123);
+        assert_eq!(format_rfc3339(time), "2024-05-01T12:30:00.123Z");
+        assert_eq!(parse_rfc3339("2024-05-01T12:30:00.123Z"), Some(time));
+        assert_eq!(parse_rfc3339("2024-05-01T21:30:00.123+09:00"), Some(time));
+        assert_eq!(parse_rfc3339("2024-05-01T12:30:00.123456789Z"), Some(time + Duration::from_nanos(456_789)));
+        assert_eq!(parse_rfc3339("2000-02-29T00:00:00Z").map(Civil::from_time).map(|c| (c.month, c.day)), Some((2, 29)));
+        assert_eq!(parse_rfc3339("yesterday"), None);
+    }
+}
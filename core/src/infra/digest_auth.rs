@@ -0,0 +1,132 @@
+//! HTTP Digest authentication (RFC 7616 / RFC 2617).
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// The parameters of a `WWW-Authenticate: Digest ...` challenge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub qop: Option<String>,
+    pub opaque: Option<String>,
+    pub algorithm: String,
+}
+
+impl DigestChallenge {
+    /// Parses a `WWW-Authenticate` header value. Returns `None` for other schemes.
+    pub fn parse(header: &str) -> Option<Self> {
+        let (scheme, params) = header.trim().split_once(char::is_whitespace)?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+        let params = parse_params(params);
+        Some(Self {
+            realm: params.get("realm").cloned().unwrap_or_default(),
+            nonce: params.get("nonce")?.clone(),
+            // Servers may offer "auth,auth-int"; only "auth" is supported
+            qop: params
+                .get("qop")
+                .filter(|q| q.split(',').any(|v| v.trim() == "auth"))
+                .map(|_| "auth".to_string()),
+            opaque: params.get("opaque").cloned(),
+            algorithm: params.get("algorithm").cloned().unwrap_or_else(|| "MD5".to_string()),
+        })
+    }
+
+    /// Builds the `Authorization` header value answering this challenge.
+    pub fn respond(&self, username: &str, password: &str, method: &str, uri: &str, cnonce: &str, nc: u32) -> String {
+        let hash = |data: String| -> String {
+            if self.algorithm.eq_ignore_ascii_case("SHA-256") {
+                hex(&Sha256::digest(data.as_bytes()))
+            } else {
+                hex(&Md5::digest(data.as_bytes()))
+            }
+        };
+        let nc = format!("{:08x}", nc);
+
+        let ha1 = hash(format!("{}:{}:{}", username, self.realm, password));
+        let ha2 = hash(format!("{}:{}", method, uri));
+        let response = match &self.qop {
+            Some(qop) => hash(format!("{}:{}:{}:{}:{}:{}", ha1, self.nonce, nc, cnonce, qop, ha2)),
+            None => hash(format!("{}:{}:{}", ha1, self.nonce, ha2)),
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            username, self.realm, self.nonce, uri, self.algorithm, response
+        );
+        if let Some(qop) = &self.qop {
+            header.push_str(&format!(", qop={}, nc={}, cnonce=\"{}\"", qop, nc, cnonce));
+        }
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        header
+    }
+}
+
+/// Splits `key=value, key="quoted, value"` pairs.
+fn parse_params(input: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = input.trim();
+
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let after = after.trim_start();
+        let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            match after.find(',') {
+                Some(end) => (after[..end].trim(), &after[end..]),
+                None => (after.trim(), ""),
+            }
+        };
+        params.insert(key, value.to_string());
+        rest = remaining.trim_start().trim_start_matches(',');
+    }
+    params
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc2617_example() {
+        let challenge = DigestChallenge::parse(
+            r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#,
+        )
+        .unwrap();
+        assert_eq!(challenge.realm, "testrealm@host.com");
+        assert_eq!(challenge.qop.as_deref(), Some("auth"));
+
+        let header = challenge.respond("Mufasa", "Circle Of Life", "GET", "/dir/index.html", "0a4f113b", 1);
+        assert!(header.contains(r#"response="6629fae49393a05397450978507c4ef1""#));
+        assert!(header.contains("nc=00000001"));
+        assert!(header.contains(r#"opaque="5ccc069c403ebaf9f0171e9517f40e41""#));
+    }
+
+    #[test]
+    fn test_rfc7616_sha256_example() {
+        let challenge = DigestChallenge::parse(
+            r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        )
+        .unwrap();
+        let header = challenge.respond("Mufasa", "Circle of Life", "GET", "/dir/index.html", "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ", 1);
+        assert!(header.contains(r#"response="753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1""#));
+    }
+
+    #[test]
+    fn test_other_schemes_are_ignored() {
+        assert!(DigestChallenge::parse(r#"Basic realm="x""#).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::domain::{openapi::ApiSpec, project::Project, repository::ApiSpecRepository};
+use crate::infra::openapi;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in the project directory holding the imported OpenAPI document, converted to JSON.
+pub const SPEC_FILE: &str = ".openapi.json";
+
+pub struct FsApiSpecRepository {
+    root: PathBuf,
+}
+
+impl FsApiSpecRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, project: &Project) -> PathBuf {
+        self.root.join(&project.name).join(SPEC_FILE)
+    }
+}
+
+impl ApiSpecRepository for FsApiSpecRepository {
+    fn is_spec(&self, path: &Path) -> bool {
+        read_document(path).is_ok_and(|document| openapi::is_openapi(&document))
+    }
+
+    fn read(&self, path: &Path) -> Result<ApiSpec> {
+        openapi::parse(&read_document(path)?).with_context(|| format!("Failed to import {:?}", path))
+    }
+
+    fn save(&self, project: &Project, path: &Path) -> Result<()> {
+        let document = read_document(path)?;
+        let target = self.path(project);
+        fs::write(&target, serde_json::to_string_pretty(&document)?).with_context(|| format!("Failed to write OpenAPI document: {:?}", target))
+    }
+
+    fn load(&self, project: &Project) -> Result<Option<ApiSpec>> {
+        let path = self.path(project);
+        if !path.exists() {
+            return Ok(None);
+        }
+        self.read(&path).map(Some)
+    }
+}
+
+/// Reads a JSON or YAML document.
+fn read_document(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read OpenAPI document: {:?}", path))?;
+    match serde_json::from_str(&content) {
+        Ok(document) => Ok(document),
+        Err(json_error) => serde_yaml::from_str(&content).map_err(|_| json_error).with_context(|| format!("{:?} is not a JSON or YAML file", path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_yaml_document() {
+        let root = std::env::temp_dir().join(format!("htup-openapi-{}", std::process::id()));
+        fs::create_dir_all(root.join("shop")).unwrap();
+        let source = root.join("openapi.yaml");
+        fs::write(&source, "openapi: 3.0.0\ninfo:\n  title: Shop\npaths:\n  /health:\n    get: {}\n").unwrap();
+
+        let repo = FsApiSpecRepository::new(&root);
+        let project = Project::new("shop");
+        assert!(repo.is_spec(&source));
+        assert_eq!(repo.load(&project).unwrap(), None);
+        repo.save(&project, &source).unwrap();
+        let spec = repo.load(&project).unwrap().unwrap();
+        assert_eq!((spec.title.as_str(), spec.operations[0].id.as_str()), ("Shop", "get_health"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use crate::domain::{
+    collection::Collection,
+    repository::CollectionRepository,
+    request::Request,
+};
+use crate::infra::{insomnia, postman};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Boundary of the multipart bodies written for imported form-data requests.
+pub const MULTIPART_BOUNDARY: &str = "htup-boundary";
+
+/// Reads Postman (collection v2.1 or environment) and Insomnia (v4, JSON or YAML) exports.
+pub struct FsCollectionRepository;
+
+impl FsCollectionRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FsCollectionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CollectionRepository for FsCollectionRepository {
+    fn read(&self, path: &Path) -> Result<Collection> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read collection: {:?}", path))?;
+        parse_collection(&content).with_context(|| format!("Failed to import {:?}", path))
+    }
+}
+
+/// Detects the format of an export and parses it.
+pub fn parse_collection(content: &str) -> Result<Collection> {
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        // Insomnia also exports YAML, of which JSON is a subset
+        Err(json_error) => serde_yaml::from_str(content).map_err(|_| json_error).context("Not a JSON or YAML file")?,
+    };
+    if postman::is_postman(&value) {
+        postman::parse(&value)
+    } else if insomnia::is_insomnia(&value) {
+        insomnia::parse(&value)
+    } else {
+        anyhow::bail!("Not a Postman collection v2.1, Postman environment or Insomnia v4 export")
+    }
+}
+
+/// A JSON scalar as text; `null` and missing values are empty.
+pub fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Sets a header unless the request already has it, in any case.
+pub fn set_default_header(request: &mut Request, name: &str, value: &str) {
+    if !request.headers.keys().any(|k| k.eq_ignore_ascii_case(name)) {
+        request.headers.insert(name.to_string(), value.to_string());
+    }
+}
+
+/// Adds a variable unless one with the same name was imported already.
+pub fn add_variable(variables: &mut Vec<(String, String)>, name: &str, value: String) {
+    if !variables.iter().any(|(k, _)| k == name) {
+        variables.push((name.to_string(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_formats() {
+        let postman = r#"{"info": {"name": "API", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"}, "item": []}"#;
+        assert_eq!(parse_collection(postman).unwrap().name, "API");
+        let insomnia = "_type: export\n__export_format: 4\nresources:\n  - _id: wrk_1\n    _type: workspace\n    name: Shop\n";
+        assert_eq!(parse_collection(insomnia).unwrap().name, "Shop");
+        assert!(parse_collection(r#"{"openapi": "3.0.0"}"#).is_err());
+        assert!(parse_collection("not: [valid").is_err());
+    }
+}
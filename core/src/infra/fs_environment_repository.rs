@@ -0,0 +1,86 @@
+use crate::domain::{
+    environment::Environment,
+    project::Project,
+    repository::EnvironmentRepository,
+};
+use crate::infra::parser::{format_env_value, parse_env_file};
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File holding the plain, committed variables of a project.
+pub const ENV_FILE: &str = ".env";
+/// File holding the secrets of a project. It is gitignored and never committed.
+pub const SECRETS_FILE: &str = ".env.local";
+
+pub struct FsEnvironmentRepository {
+    root: PathBuf,
+}
+
+impl FsEnvironmentRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn read_vars(path: &Path) -> Result<HashMap<String, String>> {
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read environment file: {:?}", path))?;
+        Ok(parse_env_file(&content))
+    }
+}
+
+impl EnvironmentRepository for FsEnvironmentRepository {
+    fn load(&self, project: &Project) -> Result<Environment> {
+        let project_path = self.root.join(&project.name);
+        let variables = Self::read_vars(&project_path.join(ENV_FILE))?;
+        let secrets = Self::read_vars(&project_path.join(SECRETS_FILE))?;
+        Ok(Environment::new(variables, secrets))
+    }
+
+    fn add_variables(&self, project: &Project, variables: &[(String, String)], secret: bool) -> Result<()> {
+        if variables.is_empty() {
+            return Ok(());
+        }
+        let path = self.root.join(&project.name).join(if secret { SECRETS_FILE } else { ENV_FILE });
+        let mut existing: HashSet<String> = Self::read_vars(&path)?.into_keys().collect();
+        let mut content = if path.exists() { fs::read_to_string(&path)? } else { String::new() };
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        // A name given twice keeps its first value
+        for (name, value) in variables.iter().filter(|(name, _)| existing.insert(name.clone())) {
+            content.push_str(&format!("{}={}\n", name, format_env_value(value)));
+        }
+        fs::write(&path, content).with_context(|| format!("Failed to write environment file: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_variables_keeps_existing_values() {
+        let root = std::env::temp_dir().join(format!("htup-environment-{}", std::process::id()));
+        let project = Project::new("api");
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::write(root.join("api").join(ENV_FILE), "base_url=http://localhost").unwrap();
+        let repo = FsEnvironmentRepository::new(&root);
+
+        let variables = vec![("base_url".to_string(), "https://api.com".to_string()), ("greeting".to_string(), "hi # there".to_string()), ("greeting".to_string(), "bye".to_string())];
+        repo.add_variables(&project, &variables, false).unwrap();
+        let cookie = "sid=\"a b\"; path=C:\\tmp\nline 2".to_string();
+        repo.add_variables(&project, &[("token".to_string(), "abc".to_string()), ("cookie".to_string(), cookie.clone())], true).unwrap();
+
+        let environment = repo.load(&project).unwrap();
+        assert_eq!(environment.variables["base_url"], "http://localhost");
+        assert_eq!(environment.variables["greeting"], "hi # there");
+        assert_eq!(environment.secrets["token"], "abc");
+        assert_eq!(environment.secrets["cookie"], cookie);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,410 @@
+use crate::domain::{
+    body::Body,
+    capture::{ArchivedCall, CapturedCall, UNRECORDED_HEADERS},
+    repository::HarRepository,
+    request::Request,
+    response::Response,
+};
+use crate::infra::body::{encode_form, graphql_payload};
+use crate::infra::datetime::{format_rfc3339, parse_rfc3339};
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Reads and writes HAR 1.2 files.
+pub struct FsHarRepository;
+
+impl FsHarRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FsHarRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HarRepository for FsHarRepository {
+    fn read(&self, path: &Path) -> Result<Vec<ArchivedCall>> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read HAR: {:?}", path))?;
+        parse_har(&content).with_context(|| format!("Failed to parse HAR: {:?}", path))
+    }
+
+    fn write(&self, path: &Path, calls: &[ArchivedCall]) -> Result<()> {
+        fs::write(path, format_har(calls)?).with_context(|| format!("Failed to write HAR: {:?}", path))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Har {
+    log: Log,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Log {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    creator: Creator,
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Creator {
+    name: String,
+    version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    started_date_time: String,
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    #[serde(default)]
+    cache: serde_json::Value,
+    #[serde(default)]
+    timings: Timings,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<NameValue>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    query_string: Vec<NameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<NameValue>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<NameValue>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    content: Content,
+    #[serde(default, rename = "redirectURL")]
+    redirect_url: String,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    size: i64,
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Timings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NameValue {
+    name: String,
+    value: String,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+/// Parses the entries of a HAR file.
+pub fn parse_har(content: &str) -> Result<Vec<ArchivedCall>> {
+    let har: Har = serde_json::from_str(content)?;
+    har.log
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let started = parse_rfc3339(&entry.started_date_time)
+                .with_context(|| format!("Invalid startedDateTime: {}", entry.started_date_time))?;
+            if !is_token(&entry.request.method) {
+                anyhow::bail!("Invalid method: {:?}", entry.request.method);
+            }
+            Ok(ArchivedCall { started, call: CapturedCall { request: request(entry.request), response: response(entry.response, entry.time)? } })
+        })
+        .collect()
+}
+
+/// Whether a method is an HTTP token (RFC 9110), as `GET` or `PROPFIND`; HAR files come from anywhere.
+fn is_token(method: &str) -> bool {
+    !method.is_empty() && method.chars().all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+fn request(har: HarRequest) -> Request {
+    let mut request = Request::new(har.method, har.url);
+    request.headers = recorded_headers(har.headers);
+    request.body = har.post_data.and_then(|data| match data.text {
+        Some(text) if !text.is_empty() => Some(Body::Text(text)),
+        _ if !data.params.is_empty() => Some(Body::Form(data.params.into_iter().map(|p| (p.name, p.value)).collect())),
+        _ => None,
+    });
+    request
+}
+
+fn response(har: HarResponse, time: f64) -> Result<Response> {
+    let body = match (har.content.text, har.content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(text.trim()).context("Invalid base64 response content")?;
+            String::from_utf8_lossy(&bytes).to_string()
+        }
+        (text, _) => text.unwrap_or_default(),
+    };
+    let latency = Duration::from_micros((time.max(0.0) * 1000.0).round() as u64);
+    let mut response = Response::new(har.status, har.status_text, body, latency);
+    response.headers = recorded_headers(har.headers);
+    if !har.content.mime_type.is_empty() && !response.headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+        response.headers.insert("content-type".to_string(), har.content.mime_type);
+    }
+    Ok(response)
+}
+
+/// Drops HTTP/2 pseudo headers and the ones describing the connection.
+fn recorded_headers(headers: Vec<NameValue>) -> HashMap<String, String> {
+    let mut recorded: HashMap<String, String> = HashMap::new();
+    for header in headers {
+        if header.name.starts_with(':') || UNRECORDED_HEADERS.contains(&header.name.to_lowercase().as_str()) {
+            continue;
+        }
+        // Browsers may split cookies over several headers, which join with `;` rather than `,`
+        let separator = if header.name.eq_ignore_ascii_case("cookie") { "; " } else { ", " };
+        recorded
+            .entry(header.name)
+            .and_modify(|v| {
+                v.push_str(separator);
+                v.push_str(&header.value);
+            })
+            .or_insert(header.value);
+    }
+    recorded
+}
+
+/// Writes calls as a HAR file, oldest first.
+pub fn format_har(calls: &[ArchivedCall]) -> Result<String> {
+    let mut calls: Vec<&ArchivedCall> = calls.iter().collect();
+    calls.sort_by_key(|c| c.started);
+    let entries = calls.into_iter().map(entry).collect::<Result<Vec<_>>>()?;
+    let har = Har {
+        log: Log {
+            version: "1.2".to_string(),
+            creator: Creator { name: "htup".to_string(), version: env!("CARGO_PKG_VERSION").to_string() },
+            entries,
+        },
+    };
+    Ok(serde_json::to_string_pretty(&har)?)
+}
+
+fn entry(archived: &ArchivedCall) -> Result<Entry> {
+    let CapturedCall { request, response } = &archived.call;
+    let time = response.latency.as_secs_f64() * 1000.0;
+    let request_mime = header(&request.headers, "content-type");
+    let post_data = match &request.body {
+        None | Some(Body::Messages(_)) => None,
+        Some(Body::Text(text)) => Some(PostData { mime_type: request_mime.unwrap_or_default(), text: Some(text.clone()), params: Vec::new() }),
+        Some(Body::Form(fields)) => Some(PostData {
+            mime_type: request_mime.unwrap_or_else(|| "application/x-www-form-urlencoded".to_string()),
+            text: Some(encode_form(fields)),
+            params: fields.iter().map(|(name, value)| NameValue { name: name.clone(), value: value.clone() }).collect(),
+        }),
+        Some(Body::GraphQL { query, variables }) => Some(PostData {
+            mime_type: request_mime.unwrap_or_else(|| "application/json".to_string()),
+            text: Some(graphql_payload(query, variables.as_deref())?),
+            params: Vec::new(),
+        }),
+        // Files are not embedded, only named
+        Some(Body::File(path)) => Some(PostData { mime_type: request_mime.unwrap_or_default(), text: Some(format!("< {}", path.display())), params: Vec::new() }),
+        Some(Body::Multipart { .. }) => Some(PostData { mime_type: request_mime.unwrap_or_default(), text: None, params: Vec::new() }),
+    };
+    let query_string = url::Url::parse(&request.url)
+        .map(|url| url.query_pairs().map(|(name, value)| NameValue { name: name.to_string(), value: value.to_string() }).collect())
+        .unwrap_or_default();
+
+    Ok(Entry {
+        started_date_time: format_rfc3339(archived.started),
+        time,
+        request: HarRequest {
+            method: request.method.clone(),
+            url: request.url.clone(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: name_values(&request.headers),
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size: -1,
+        },
+        response: HarResponse {
+            status: response.status,
+            status_text: response.status_text.clone(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: name_values(&response.headers),
+            content: Content {
+                size: response.body.len() as i64,
+                mime_type: header(&response.headers, "content-type").unwrap_or_default(),
+                text: Some(response.body.clone()),
+                encoding: None,
+            },
+            redirect_url: header(&response.headers, "location").unwrap_or_default(),
+            headers_size: -1,
+            body_size: response.body.len() as i64,
+        },
+        cache: serde_json::json!({}),
+        timings: Timings { send: 0.0, wait: time, receive: 0.0 },
+    })
+}
+
+fn header(headers: &HashMap<String, String>, name: &str) -> Option<String> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+}
+
+fn name_values(headers: &HashMap<String, String>) -> Vec<NameValue> {
+    let mut pairs: Vec<NameValue> = headers.iter().map(|(name, value)| NameValue { name: name.clone(), value: value.clone() }).collect();
+    pairs.sort_by(|a, b| a.name.cmp(&b.name));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEVTOOLS_HAR: &str = r#"{
+      "log": {
+        "version": "1.2",
+        "creator": { "name": "WebInspector", "version": "537.36" },
+        "entries": [{
+          "startedDateTime": "2024-05-01T12:30:00.123Z",
+          "time": 42.5,
+          "request": {
+            "method": "POST",
+            "url": "https://api.example.com/users?notify=1",
+            "httpVersion": "http/2.0",
+            "headers": [
+              { "name": ":authority", "value": "api.example.com" },
+              { "name": "content-type", "value": "application/json" },
+              { "name": "cookie", "value": "sid=1" },
+              { "name": "cookie", "value": "theme=dark" },
+              { "name": "content-length", "value": "17" }
+            ],
+            "queryString": [{ "name": "notify", "value": "1" }],
+            "cookies": [],
+            "headersSize": -1,
+            "bodySize": 17,
+            "postData": { "mimeType": "application/json", "text": "{\"name\": \"htup\"}" }
+          },
+          "response": {
+            "status": 201,
+            "statusText": "",
+            "httpVersion": "http/2.0",
+            "headers": [{ "name": "set-cookie", "value": "a=1" }, { "name": "set-cookie", "value": "b=2" }],
+            "cookies": [],
+            "content": { "size": 11, "mimeType": "application/json", "text": "eyJpZCI6IDF9", "encoding": "base64" },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": -1,
+            "_transferSize": 300
+          },
+          "cache": {},
+          "timings": { "blocked": 1, "send": 0.1, "wait": 40, "receive": 1.4 }
+        }]
+      }
+    }"#;
+
+    #[test]
+    fn test_parse_devtools_har() {
+        let calls = parse_har(DEVTOOLS_HAR).unwrap();
+        assert_eq!(calls.len(), 1);
+        let ArchivedCall { started, call } = &calls[0];
+        assert_eq!(format_rfc3339(*started), "2024-05-01T12:30:00.123Z");
+        assert_eq!(call.request.url, "https://api.example.com/users?notify=1");
+        assert_eq!(
+            call.request.headers,
+            HashMap::from([
+                ("content-type".to_string(), "application/json".to_string()),
+                ("cookie".to_string(), "sid=1; theme=dark".to_string()),
+            ])
+        );
+        assert_eq!(call.request.body, Some(Body::Text("{\"name\": \"htup\"}".to_string())));
+        assert_eq!((call.response.status, call.response.body.as_str()), (201, "{\"id\": 1}"));
+        assert_eq!(call.response.headers["set-cookie"], "a=1, b=2");
+        assert_eq!(call.response.headers["content-type"], "application/json");
+        assert_eq!(call.response.latency, Duration::from_micros(42_500));
+    }
+
+    #[test]
+    fn test_rejects_methods_that_are_not_tokens() {
+        for method in ["../../../x", "GET /", ""] {
+            let har = DEVTOOLS_HAR.replace(r#""method": "POST""#, &format!("\"method\": {:?}", method));
+            assert_ne!(har, DEVTOOLS_HAR);
+            assert_eq!(parse_har(&har).unwrap_err().to_string(), format!("Invalid method: {:?}", method));
+        }
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let mut calls = parse_har(DEVTOOLS_HAR).unwrap();
+        calls[0].call.request.body = Some(Body::Form(vec![("q".to_string(), "a b".to_string())]));
+        let written = format_har(&calls).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(json["log"]["creator"]["name"], "htup");
+        assert_eq!(json["log"]["entries"][0]["request"]["postData"]["text"], "q=a+b");
+        assert_eq!(json["log"]["entries"][0]["request"]["queryString"][0]["name"], "notify");
+        assert_eq!(json["log"]["entries"][0]["response"]["content"]["text"], "{\"id\": 1}");
+
+        let read = parse_har(&written).unwrap();
+        assert_eq!(read[0].started, calls[0].started);
+        assert_eq!(read[0].call.response, calls[0].call.response);
+        assert_eq!(read[0].call.request.body, Some(Body::Text("q=a+b".to_string())));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::domain::{history::HistoryEntry, project::Project, repository::HistoryRepository, response::Response};
+use crate::infra::parser::{format_http_file, parse_http_file};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Directory in the project holding past responses and pinned baselines.
+pub const HISTORY_DIR: &str = ".history";
+/// Number of responses kept per request; older ones are dropped.
+pub const MAX_ENTRIES: usize = 50;
+
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    timestamp_ms: u64,
+    status: u16,
+    status_text: String,
+    headers: HashMap<String, String>,
+    body: String,
+    latency_ms: u64,
+    #[serde(default)]
+    graphql_errors: Vec<String>,
+    #[serde(default)]
+    trailers: HashMap<String, String>,
+    /// The request as sent, in `.http` format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request: Option<String>,
+}
+
+impl StoredEntry {
+    fn from_entry(entry: &HistoryEntry) -> Self {
+        let response = &entry.response;
+        Self {
+            timestamp_ms: entry.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            status: response.status,
+            status_text: response.status_text.clone(),
+            headers: response.headers.clone(),
+            body: response.body.clone(),
+            latency_ms: response.latency.as_millis() as u64,
+            graphql_errors: response.graphql_errors.clone(),
+            trailers: response.trailers.clone(),
+            request: entry.request.as_ref().map(format_http_file),
+        }
+    }
+
+    fn into_entry(self) -> HistoryEntry {
+        let mut response = Response::new(self.status, self.status_text, self.body, Duration::from_millis(self.latency_ms));
+        response.headers = self.headers;
+        response.graphql_errors = self.graphql_errors;
+        response.trailers = self.trailers;
+        // A request that no longer parses only loses the pairing, not the response
+        let request = self.request.and_then(|r| parse_http_file(&r).ok());
+        HistoryEntry { timestamp: UNIX_EPOCH + Duration::from_millis(self.timestamp_ms), request, response }
+    }
+}
+
+/// Keeps the responses of each request as JSON lines in `<project>/.history/<request>.jsonl`,
+/// and its baseline in `<request>.baseline.json` next to it.
+pub struct FsHistoryRepository {
+    root: PathBuf,
+}
+
+impl FsHistoryRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, project: &Project, request_id: &str, extension: &str) -> PathBuf {
+        self.root.join(&project.name).join(HISTORY_DIR).join(format!("{}.{}", request_id, extension))
+    }
+}
+
+fn write(path: &Path, content: String) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write history: {:?}", path))
+}
+
+impl HistoryRepository for FsHistoryRepository {
+    fn append(&self, project: &Project, request_id: &str, entry: &HistoryEntry) -> Result<()> {
+        let mut entries = self.list(project, request_id)?;
+        entries.push(entry.clone());
+        let skip = entries.len().saturating_sub(MAX_ENTRIES);
+
+        let mut content = String::new();
+        for entry in &entries[skip..] {
+            content.push_str(&serde_json::to_string(&StoredEntry::from_entry(entry))?);
+            content.push('\n');
+        }
+        write(&self.path(project, request_id, "jsonl"), content)
+    }
+
+    fn list(&self, project: &Project, request_id: &str) -> Result<Vec<HistoryEntry>> {
+        let path = self.path(project, request_id, "jsonl");
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read history: {:?}", path))?;
+        content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                let stored: StoredEntry = serde_json::from_str(l).with_context(|| format!("Corrupt history entry in {:?}", path))?;
+                Ok(stored.into_entry())
+            })
+            .collect()
+    }
+
+    fn load_baseline(&self, project: &Project, request_id: &str) -> Result<Option<HistoryEntry>> {
+        let path = self.path(project, request_id, "baseline.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read baseline: {:?}", path))?;
+        let stored: StoredEntry = serde_json::from_str(&content).with_context(|| format!("Failed to parse baseline: {:?}", path))?;
+        Ok(Some(stored.into_entry()))
+    }
+
+    fn save_baseline(&self, project: &Project, request_id: &str, entry: &HistoryEntry) -> Result<()> {
+        let content = serde_json::to_string_pretty(&StoredEntry::from_entry(entry))?;
+        write(&self.path(project, request_id, "baseline.json"), content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(body: &str, ms: u64) -> HistoryEntry {
+        let mut response = Response::new(200, "OK".to_string(), body.to_string(), Duration::from_millis(12));
+        response.headers.insert("content-type".to_string(), "application/json".to_string());
+        let mut request = crate::domain::request::Request::new("GET", "https://api.example.com/users");
+        request.headers.insert("Accept".to_string(), "application/json".to_string());
+        HistoryEntry { timestamp: UNIX_EPOCH + Duration::from_millis(ms), request: Some(request), response }
+    }
+
+    #[test]
+    fn test_history_and_baseline_roundtrip() {
+        let root = std::env::temp_dir().join(format!("htup-history-{}", std::process::id()));
+        let repo = FsHistoryRepository::new(&root);
+        let project = Project::new("api");
+
+        assert!(repo.list(&project, "get_users").unwrap().is_empty());
+        for i in 0..MAX_ENTRIES as u64 + 2 {
+            repo.append(&project, "get_users", &entry(&format!("{{\"n\": {}}}", i), i)).unwrap();
+        }
+        let entries = repo.list(&project, "get_users").unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0], entry("{\"n\": 2}", 2));
+        assert_eq!(entries.last().unwrap().response.body, format!("{{\"n\": {}}}", MAX_ENTRIES + 1));
+
+        assert_eq!(repo.load_baseline(&project, "get_users").unwrap(), None);
+        repo.save_baseline(&project, "get_users", &entry("{}", 7)).unwrap();
+        assert_eq!(repo.load_baseline(&project, "get_users").unwrap(), Some(entry("{}", 7)));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use crate::domain::{mock::MockResponse, project::Project, repository::MockRepository};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Reads example responses from `<request>.mock` next to the request file.
+pub struct FsMockRepository {
+    root: PathBuf,
+}
+
+impl FsMockRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl MockRepository for FsMockRepository {
+    fn load(&self, project: &Project, request_id: &str) -> Result<Option<MockResponse>> {
+        let path = self.root.join(&project.name).join(format!("{}.mock", request_id));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read mock: {:?}", path))?;
+        parse_mock(&content).with_context(|| format!("Failed to parse mock: {:?}", path)).map(Some)
+    }
+
+    fn save(&self, project: &Project, request_id: &str, response: &MockResponse) -> Result<()> {
+        let dir = self.root.join(&project.name);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create directory: {:?}", dir))?;
+        let path = dir.join(format!("{}.mock", request_id));
+        fs::write(&path, format_mock(response)).with_context(|| format!("Failed to write mock: {:?}", path))
+    }
+}
+
+/// Writes a mock in the format read by `parse_mock`.
+pub fn format_mock(response: &MockResponse) -> String {
+    let mut content = String::new();
+    if let Some(delay) = response.delay {
+        content.push_str(&format!("# @delay {}\n", delay.as_millis()));
+    }
+    content.push_str(&format!("HTTP {}\n", response.status));
+    let mut headers: Vec<_> = response.headers.iter().collect();
+    headers.sort();
+    for (key, value) in headers {
+        content.push_str(&format!("{}: {}\n", key, value));
+    }
+    content.push('\n');
+    content.push_str(&response.body);
+    if !response.body.is_empty() && !response.body.ends_with('\n') {
+        content.push('\n');
+    }
+    content
+}
+
+/// Parses a mock file: `# @delay <ms>` directives, an `HTTP <status>` line, headers, a blank line and the body.
+pub fn parse_mock(content: &str) -> Result<MockResponse> {
+    let mut lines = content.lines();
+    let mut delay = None;
+    let status_line = loop {
+        let line = lines.next().context("Missing `HTTP <status>` line")?.trim();
+        match line.strip_prefix('#').map(str::trim) {
+            Some(comment) => {
+                if let Some(ms) = comment.strip_prefix("@delay") {
+                    let ms = ms.trim().trim_end_matches("ms");
+                    delay = Some(Duration::from_millis(ms.parse().with_context(|| format!("Invalid @delay: {}", ms))?));
+                }
+            }
+            None if line.is_empty() => {}
+            None => break line,
+        }
+    };
+    let status = status_line
+        .strip_prefix("HTTP")
+        .and_then(|s| s.split_whitespace().next())
+        .with_context(|| format!("Expected `HTTP <status>`, found: {}", status_line))?;
+    let status = status.parse().with_context(|| format!("Invalid status: {}", status))?;
+
+    let mut headers = HashMap::new();
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+        let (key, value) = line.split_once(':').with_context(|| format!("Invalid header: {}", line))?;
+        headers.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    let body = lines.collect::<Vec<_>>().join("\n");
+    Ok(MockResponse { status, headers, body, delay })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mock() {
+        let mock = parse_mock("# Created user\n# @delay 250ms\nHTTP 201 Created\nContent-Type: application/json\n\n{\n  \"id\": \"{{id}}\"\n}\n").unwrap();
+        assert_eq!(mock.status, 201);
+        assert_eq!(mock.delay, Some(Duration::from_millis(250)));
+        assert_eq!(mock.headers["Content-Type"], "application/json");
+        assert_eq!(mock.body, "{\n  \"id\": \"{{id}}\"\n}");
+
+        let mock = parse_mock("HTTP 204").unwrap();
+        assert_eq!((mock.status, mock.body.as_str(), mock.delay), (204, "", None));
+        assert!(parse_mock("{}").is_err());
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let mock = MockResponse {
+            status: 404,
+            headers: HashMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body: "{\n  \"error\": \"not found\"\n}".to_string(),
+            delay: Some(Duration::from_millis(30)),
+        };
+        assert_eq!(parse_mock(&format_mock(&mock)).unwrap(), mock);
+    }
+}
@@ -2,9 +2,11 @@ use crate::domain::{
     project::Project,
     repository::ProjectRepository,
 };
+use crate::infra::fs_environment_repository::SECRETS_FILE;
+use crate::infra::fs_history_repository::HISTORY_DIR;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct FsProjectRepository {
     root: PathBuf,
@@ -27,7 +29,8 @@ impl ProjectRepository for FsProjectRepository {
             let entry = entry?;
             let path = entry.path();
             if path.is_dir() {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                // Hidden directories (.git, the trash) are not projects
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()).filter(|n| !n.starts_with('.')) {
                     // Project only stores name now
                     projects.push(Project::new(name));
                 }
@@ -39,34 +42,246 @@ impl ProjectRepository for FsProjectRepository {
 
     fn list_requests(&self, project: &Project) -> Result<Vec<String>> {
         let mut requests = Vec::new();
-        // Construct the project path manually
         let project_path = self.root.join(&project.name);
-
-        if project_path.exists() {
-            for entry in fs::read_dir(&project_path)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(ext) = path.extension() {
-                        if ext == "http" {
-                            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                                requests.push(stem.to_string());
-                            }
-                        }
-                    }
-                }
+        walk(&project_path, "", &mut |id, path| {
+            if let (true, Some(id)) = (path.is_file(), id.strip_suffix(".http")) {
+                requests.push(id.to_string());
             }
-        }
+        })?;
         requests.sort();
         Ok(requests)
     }
 
+    fn list_folders(&self, project: &Project) -> Result<Vec<String>> {
+        let mut folders = Vec::new();
+        walk(&self.root.join(&project.name), "", &mut |id, path| {
+            if path.is_dir() {
+                folders.push(id.to_string());
+            }
+        })?;
+        folders.sort();
+        Ok(folders)
+    }
+
     fn create_project(&self, name: &str) -> Result<()> {
         let path = self.root.join(name);
         if path.exists() {
             anyhow::bail!("Project already exists: {}", name);
         }
         fs::create_dir_all(&path).with_context(|| format!("Failed to create project directory: {:?}", path))?;
+        // Keep secrets out of git from the start
+        fs::write(path.join(".gitignore"), format!("{}\n", SECRETS_FILE))
+            .with_context(|| format!("Failed to write .gitignore in {:?}", path))?;
         Ok(())
     }
+
+    fn create_folder(&self, project: &Project, path: &str) -> Result<()> {
+        let dir = self.root.join(&project.name).join(path);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create folder: {:?}", dir))
+    }
+
+    fn rename_folder(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let project_path = self.root.join(&project.name);
+        // The history of the requests inside moves along
+        let moves: Vec<(PathBuf, PathBuf)> = [project_path.clone(), project_path.join(HISTORY_DIR)]
+            .into_iter()
+            .map(|base| (base.join(from), base.join(to)))
+            .filter(|(source, _)| source.exists())
+            .collect();
+        // `fs::rename` would silently replace an empty directory
+        if moves.iter().any(|(_, target)| target.exists()) {
+            anyhow::bail!("Folder already exists: {}", to);
+        }
+        let mut created = Vec::new(); // Directories made to hold the targets
+        for (i, (source, target)) in moves.iter().enumerate() {
+            let parent = target.parent().unwrap_or(target);
+            created.extend(parent.ancestors().take_while(|dir| !dir.exists()).last().map(Path::to_path_buf));
+            let moved = fs::create_dir_all(parent).and_then(|_| fs::rename(source, target));
+            if let Err(e) = moved {
+                // Put back what already moved, so requests never part from their history
+                for (source, target) in moves[..i].iter().rev() {
+                    let _ = fs::rename(target, source);
+                }
+                for dir in created.iter().rev() {
+                    let _ = fs::remove_dir_all(dir);
+                }
+                return Err(e).with_context(|| format!("Failed to move {:?} to {:?}", source, target));
+            }
+        }
+        Ok(())
+    }
+
+    fn folder_paths(&self, project: &Project, path: &str) -> Vec<PathBuf> {
+        let project_path = PathBuf::from(&project.name);
+        vec![project_path.join(path), project_path.join(HISTORY_DIR).join(path)]
+    }
+
+    fn rename_project(&self, project: &Project, to: &str) -> Result<()> {
+        let (source, target) = (self.root.join(&project.name), self.root.join(to));
+        if target.exists() {
+            anyhow::bail!("Project already exists: {}", to);
+        }
+        fs::rename(&source, &target).with_context(|| format!("Failed to move {:?} to {:?}", source, target))
+    }
+
+    fn duplicate_project(&self, project: &Project, to: &str) -> Result<()> {
+        let target = self.root.join(to);
+        if target.exists() {
+            anyhow::bail!("Project already exists: {}", to);
+        }
+        copy_dir(&self.root.join(&project.name), &target)
+    }
+
+    fn project_paths(&self, project: &Project) -> Vec<PathBuf> {
+        vec![PathBuf::from(&project.name)]
+    }
+}
+
+/// Copies a project directory; the response history stays with the original.
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    fs::create_dir_all(target).with_context(|| format!("Failed to create directory: {:?}", target))?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(name) = path.file_name() else { continue };
+        // A symlinked directory could make the copy endless
+        if name == HISTORY_DIR || is_linked_dir(&entry)? {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &target.join(name))?;
+        } else {
+            fs::copy(&path, target.join(name)).with_context(|| format!("Failed to copy {:?}", path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Calls `visit` with the relative path (`/`-separated) of every entry below `dir`.
+/// Hidden entries, such as `.history`, are skipped with their content, and so are
+/// symlinked directories, which may point back up the tree.
+fn walk(dir: &Path, prefix: &str, visit: &mut dyn FnMut(&str, &Path)) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        if name.starts_with('.') || is_linked_dir(&entry)? {
+            continue;
+        }
+        let id = format!("{}{}", prefix, name);
+        visit(&id, &path);
+        if entry.file_type()?.is_dir() {
+            walk(&path, &format!("{}/", id), visit)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_linked_dir(entry: &fs::DirEntry) -> Result<bool> {
+    Ok(entry.file_type()?.is_symlink() && entry.path().is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{repository::RequestRepository, request::Request};
+    use crate::domain::repository::TrashRepository;
+    use crate::infra::{fs_repository::FsRequestRepository, fs_trash_repository::FsTrashRepository, system_clock::SystemClock};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_nested_requests_and_folders() {
+        let root = std::env::temp_dir().join(format!("htup-projects-{}", std::process::id()));
+        let repo = FsProjectRepository::new(&root);
+        let project = Project::new("shop");
+        repo.create_project("shop").unwrap();
+        repo.create_folder(&project, "users/admin").unwrap();
+        repo.create_folder(&project, "empty").unwrap();
+        for file in ["health.http", "users/get_user.http", "users/admin/ban.http", "users/get_user.mock", ".history/health.jsonl"] {
+            fs::create_dir_all(root.join("shop").join(file).parent().unwrap()).unwrap();
+            fs::write(root.join("shop").join(file), "").unwrap();
+        }
+
+        assert_eq!(repo.list_requests(&project).unwrap(), vec!["health", "users/admin/ban", "users/get_user"]);
+        assert_eq!(repo.list_folders(&project).unwrap(), vec!["empty", "users", "users/admin"]);
+
+        // Requests saved into a folder, as a collection import does, are listed too
+        let requests = FsRequestRepository::new(&root);
+        requests.save(&project, "users/admin/unban", &Request::new("POST", "{{base_url}}/unban")).unwrap();
+        assert!(repo.list_requests(&project).unwrap().contains(&"users/admin/unban".to_string()));
+        fs::remove_file(root.join("shop/users/admin/unban.http")).unwrap();
+
+        fs::create_dir_all(root.join("shop/.history/users")).unwrap();
+        fs::write(root.join("shop/.history/users/get_user.jsonl"), "").unwrap();
+        repo.rename_folder(&project, "users", "team/people").unwrap();
+        assert_eq!(repo.list_requests(&project).unwrap(), vec!["health", "team/people/admin/ban", "team/people/get_user"]);
+        assert!(root.join("shop/.history/team/people/get_user.jsonl").exists());
+
+        // An existing folder, even an empty one, is never replaced
+        let error = repo.rename_folder(&project, "team/people", "empty").unwrap_err();
+        assert_eq!(error.to_string(), "Folder already exists: empty");
+        assert!(root.join("shop/team/people/get_user.http").exists());
+
+        // When the history cannot follow, the requests move back
+        fs::write(root.join("shop/.history/staff"), "").unwrap();
+        assert!(repo.rename_folder(&project, "team/people", "staff/people").is_err());
+        assert!(root.join("shop/team/people/get_user.http").exists());
+        assert!(!root.join("shop/staff").exists());
+        assert!(root.join("shop/.history/team/people/get_user.jsonl").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_directories_are_not_followed() {
+        let root = std::env::temp_dir().join(format!("htup-symlinks-{}", std::process::id()));
+        let repo = FsProjectRepository::new(&root);
+        let project = Project::new("shop");
+        repo.create_folder(&project, "users").unwrap();
+        fs::write(root.join("shop/users/get_user.http"), "").unwrap();
+        // A loop back to the project itself
+        std::os::unix::fs::symlink(root.join("shop"), root.join("shop/users/loop")).unwrap();
+
+        assert_eq!(repo.list_requests(&project).unwrap(), vec!["users/get_user"]);
+        assert_eq!(repo.list_folders(&project).unwrap(), vec!["users"]);
+        repo.duplicate_project(&project, "shop_copy").unwrap();
+        assert!(root.join("shop_copy/users/get_user.http").exists());
+        assert!(!root.join("shop_copy/users/loop").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_project_housekeeping() {
+        let root = std::env::temp_dir().join(format!("htup-housekeeping-{}", std::process::id()));
+        let repo = FsProjectRepository::new(&root);
+        repo.create_project("shop").unwrap();
+        repo.create_project("blog").unwrap();
+        for file in ["shop/users/get_user.http", "shop/.history/users/get_user.jsonl"] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        repo.duplicate_project(&Project::new("shop"), "store").unwrap();
+        assert!(root.join("store/users/get_user.http").exists());
+        assert!(root.join("store/.gitignore").exists());
+        assert!(!root.join("store/.history").exists());
+        assert!(repo.rename_project(&Project::new("store"), "blog").is_err());
+        repo.rename_project(&Project::new("store"), "shop-v2").unwrap();
+
+        let folder = repo.folder_paths(&Project::new("shop"), "users");
+        assert_eq!(folder, vec![PathBuf::from("shop/users"), PathBuf::from("shop/.history/users")]);
+        assert_eq!(repo.project_paths(&Project::new("blog")), vec![PathBuf::from("blog")]);
+        let trash = FsTrashRepository::new(&root, Arc::new(SystemClock));
+        trash.put("shop/users", &folder).unwrap();
+        assert!(!root.join("shop/.history/users").exists());
+        trash.put("blog", &repo.project_paths(&Project::new("blog"))).unwrap();
+        let names: Vec<String> = repo.list_projects().unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["shop", "shop-v2"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
 }
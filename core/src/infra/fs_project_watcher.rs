@@ -0,0 +1,78 @@
+use crate::domain::{
+    repository::{ProjectChangeSink, ProjectWatcher},
+    watch::ProjectChange,
+};
+use crate::infra::fs_history_repository::HISTORY_DIR;
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Watches the projects root recursively with the notification API of the OS.
+pub struct FsProjectWatcher {
+    root: PathBuf,
+    // Watching stops when the watcher is dropped
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl FsProjectWatcher {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), watcher: Mutex::new(None) }
+    }
+}
+
+impl ProjectWatcher for FsProjectWatcher {
+    fn watch(&self, sink: Arc<dyn ProjectChangeSink>) -> Result<()> {
+        let root = self.root.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else { return };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for change in event.paths.iter().filter_map(|path| change_for(&root, path)) {
+                sink.changed(change);
+            }
+        })
+        .context("Failed to start the file watcher")?;
+        watcher.watch(&self.root, RecursiveMode::Recursive).with_context(|| format!("Failed to watch {:?}", self.root))?;
+        *self.watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+        Ok(())
+    }
+}
+
+/// What a changed path means for the app. Hidden directories at the top (.git, the trash)
+/// and the response history, which htup writes itself, are ignored.
+fn change_for(root: &Path, path: &Path) -> Option<ProjectChange> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut names = relative.components().map(|c| match c {
+        Component::Normal(name) => name.to_str(),
+        _ => None,
+    });
+    let project = names.next()??;
+    if project.starts_with('.') {
+        return None;
+    }
+    match names.next() {
+        None => Some(ProjectChange::Projects),
+        Some(Some(HISTORY_DIR)) => None,
+        Some(_) => Some(ProjectChange::Project(project.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_for() {
+        let root = Path::new("/work/apis");
+        let change = |path: &str| change_for(root, Path::new(path));
+        assert_eq!(change("/work/apis/shop"), Some(ProjectChange::Projects));
+        assert_eq!(change("/work/apis/shop/users/get_user.http"), Some(ProjectChange::Project("shop".to_string())));
+        assert_eq!(change("/work/apis/shop/.env"), Some(ProjectChange::Project("shop".to_string())));
+        assert_eq!(change("/work/apis/shop/.history/get_user.jsonl"), None);
+        assert_eq!(change("/work/apis/.git/index"), None);
+        assert_eq!(change("/work/apis"), None);
+        assert_eq!(change("/elsewhere/shop"), None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::domain::{environment::Environment, grpc::ProtoSource, project::Project, repository::ProtoRepository};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing at the proto directory, relative to the project directory.
+pub const PROTO_PATH_VARIABLE: &str = "grpc_proto_path";
+/// Proto directory used when the variable is not set.
+pub const DEFAULT_PROTO_DIR: &str = "proto";
+
+pub struct FsProtoRepository {
+    root: PathBuf,
+}
+
+impl FsProtoRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl ProtoRepository for FsProtoRepository {
+    fn find(&self, project: &Project, environment: &Environment) -> Result<Option<ProtoSource>> {
+        let dir = environment.variables.get(PROTO_PATH_VARIABLE).map(String::as_str).unwrap_or(DEFAULT_PROTO_DIR);
+        let include_dir = self.root.join(&project.name).join(dir);
+        if !include_dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut files = Vec::new();
+        collect_protos(&include_dir, &mut files)?;
+        files.sort();
+        Ok((!files.is_empty()).then_some(ProtoSource { include_dir, files }))
+    }
+}
+
+fn collect_protos(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read proto directory: {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_protos(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "proto") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_find_protos_recursively() {
+        let root = std::env::temp_dir().join(format!("htup-proto-repo-{}", std::process::id()));
+        fs::create_dir_all(root.join("api/protos/common")).unwrap();
+        fs::write(root.join("api/protos/service.proto"), "").unwrap();
+        fs::write(root.join("api/protos/common/types.proto"), "").unwrap();
+        fs::write(root.join("api/protos/README.md"), "").unwrap();
+
+        let repo = FsProtoRepository::new(&root);
+        let project = Project::new("api");
+        let env = Environment::new(HashMap::from([(PROTO_PATH_VARIABLE.to_string(), "protos".to_string())]), HashMap::new());
+        let source = repo.find(&project, &env).unwrap().unwrap();
+        assert_eq!(source.include_dir, root.join("api/protos"));
+        assert_eq!(source.files, vec![root.join("api/protos/common/types.proto"), root.join("api/protos/service.proto")]);
+
+        // No `proto` directory: reflection is used
+        let env = Environment::new(HashMap::new(), HashMap::new());
+        assert_eq!(repo.find(&project, &env).unwrap(), None);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
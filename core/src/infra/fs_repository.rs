@@ -1,5 +1,6 @@
-use crate::domain::{repository::RequestRepository, request::Request, project::Project};
-use crate::infra::parser::parse_http_file;
+use crate::domain::{repository::RequestRepository, request::Request, project::Project, snapshot::SNAPSHOT_SUFFIX};
+use crate::infra::fs_history_repository::HISTORY_DIR;
+use crate::infra::parser::{format_http_file, parse_http_file};
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -16,14 +17,71 @@ impl FsRequestRepository {
     fn resolve_path(&self, project: &Project, request_id: &str) -> PathBuf {
         self.root.join(&project.name).join(format!("{}.http", request_id))
     }
+
+    /// The request file and the files other repositories keep for it: snapshot, mock and history.
+    fn files(&self, project: &Project, request_id: &str) -> Vec<PathBuf> {
+        let dir = self.root.join(&project.name);
+        vec![
+            self.resolve_path(project, request_id),
+            dir.join(format!("{}.http{}", request_id, SNAPSHOT_SUFFIX)),
+            dir.join(format!("{}.mock", request_id)),
+            dir.join(HISTORY_DIR).join(format!("{}.jsonl", request_id)),
+            dir.join(HISTORY_DIR).join(format!("{}.baseline.json", request_id)),
+        ]
+    }
 }
 
 impl RequestRepository for FsRequestRepository {
+    fn rename(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let target = self.resolve_path(project, to);
+        if target.exists() {
+            anyhow::bail!("Request already exists: {}", to);
+        }
+        for (source, target) in self.files(project, from).into_iter().zip(self.files(project, to)) {
+            if !source.exists() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&source, &target).with_context(|| format!("Failed to move {:?} to {:?}", source, target))?;
+        }
+        Ok(())
+    }
+
+    fn duplicate(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let (source, target) = (self.resolve_path(project, from), self.resolve_path(project, to));
+        if target.exists() {
+            anyhow::bail!("Request already exists: {}", to);
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &target).with_context(|| format!("Failed to copy {:?} to {:?}", source, target))?;
+        Ok(())
+    }
+
+    fn paths(&self, project: &Project, request_id: &str) -> Vec<PathBuf> {
+        let files = self.files(project, request_id);
+        files.iter().filter_map(|file| file.strip_prefix(&self.root).ok()).map(Path::to_path_buf).collect()
+    }
+
     fn load(&self, project: &Project, request_id: &str) -> Result<Request> {
         let path = self.resolve_path(project, request_id);
         let content = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read request file: {:?}", path))?;
-        parse_http_file(&content).with_context(|| format!("Failed to parse request file: {:?}", path))
+        let mut request = parse_http_file(&content).with_context(|| format!("Failed to parse request file: {:?}", path))?;
+
+        // `< ./file` references are relative to the .http file
+        if let (Some(body), Some(dir)) = (&request.body, path.parent()) {
+            request.body = Some(body.map_paths(|p| if p.is_relative() { dir.join(p) } else { p.clone() }));
+        }
+        Ok(request)
+    }
+
+    fn read_source(&self, project: &Project, request_id: &str) -> Result<String> {
+        let path = self.resolve_path(project, request_id);
+        fs::read_to_string(&path).with_context(|| format!("Failed to read request file: {:?}", path))
     }
 
     fn save(&self, project: &Project, request_id: &str, request: &Request) -> Result<()> {
@@ -34,20 +92,49 @@ impl RequestRepository for FsRequestRepository {
             fs::create_dir_all(parent)?;
         }
 
-        let mut content = format!("{} {}\n", request.method, request.url);
-        
-        for (key, value) in &request.headers {
-            content.push_str(&format!("{}: {}\n", key, value));
-        }
-        
-        content.push('\n');
-        
-        if let Some(body) = &request.body {
-            content.push_str(body);
+        // Write file references relative to the .http file again
+        let mut request = request.clone();
+        if let (Some(body), Some(dir)) = (&request.body, path.parent()) {
+            request.body = Some(body.map_paths(|p| match p.strip_prefix(dir) {
+                Ok(relative) => Path::new(".").join(relative),
+                Err(_) => p.clone(),
+            }));
         }
+        let content = format_http_file(&request);
 
         fs::write(&path, content)
             .with_context(|| format!("Failed to write request file: {:?}", path))?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_moves_companion_files() {
+        let root = std::env::temp_dir().join(format!("htup-requests-{}", std::process::id()));
+        let repo = FsRequestRepository::new(&root);
+        let project = Project::new("shop");
+        repo.save(&project, "users/get_user", &Request::new("GET", "https://api.shop.com/users/1")).unwrap();
+        repo.save(&project, "health", &Request::new("GET", "https://api.shop.com/health")).unwrap();
+        fs::write(root.join("shop/users/get_user.http.snap"), "HTTP 200\n").unwrap();
+        fs::create_dir_all(root.join("shop/.history/users")).unwrap();
+        fs::write(root.join("shop/.history/users/get_user.jsonl"), "").unwrap();
+
+        repo.rename(&project, "users/get_user", "admin/find_user").unwrap();
+        assert_eq!(repo.load(&project, "admin/find_user").unwrap().url, "https://api.shop.com/users/1");
+        assert!(root.join("shop/admin/find_user.http.snap").exists());
+        assert!(root.join("shop/.history/admin/find_user.jsonl").exists());
+        assert!(!root.join("shop/users/get_user.http").exists());
+        assert!(repo.rename(&project, "admin/find_user", "health").is_err());
+        assert_eq!(repo.paths(&project, "health")[..3], [
+            PathBuf::from("shop/health.http"),
+            PathBuf::from("shop/health.http.snap"),
+            PathBuf::from("shop/health.mock"),
+        ]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
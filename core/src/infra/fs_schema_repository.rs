@@ -0,0 +1,69 @@
+use crate::domain::{graphql::GraphQLSchema, project::Project, repository::SchemaRepository};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// File in the project directory holding the last introspection response.
+pub const SCHEMA_FILE: &str = ".graphql_schema.json";
+
+pub struct FsSchemaRepository {
+    root: PathBuf,
+}
+
+impl FsSchemaRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, project: &Project) -> PathBuf {
+        self.root.join(&project.name).join(SCHEMA_FILE)
+    }
+}
+
+impl SchemaRepository for FsSchemaRepository {
+    fn load(&self, project: &Project) -> Result<Option<GraphQLSchema>> {
+        let path = self.path(project);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read schema: {:?}", path))?;
+        GraphQLSchema::from_introspection(&content).map(Some)
+    }
+
+    fn save(&self, project: &Project, introspection: &str) -> Result<()> {
+        let path = self.path(project);
+        fs::write(&path, introspection).with_context(|| format!("Failed to write schema: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTROSPECTION: &str = r#"{"data":{"__schema":{
+        "queryType":{"name":"Query"},"mutationType":null,"subscriptionType":null,
+        "types":[
+            {"name":"Query","fields":[{"name":"users","type":{"kind":"NON_NULL","name":null,"ofType":{"kind":"LIST","name":null,"ofType":{"kind":"OBJECT","name":"User","ofType":null}}}}]},
+            {"name":"User","fields":[{"name":"id","type":{"kind":"SCALAR","name":"ID","ofType":null}}]},
+            {"name":"ID","fields":null}
+        ]}}}"#;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("htup_schema_test_{}", std::process::id()));
+        fs::create_dir_all(dir.join("api")).unwrap();
+        let repo = FsSchemaRepository::new(&dir);
+        let project = Project::new("api");
+
+        assert_eq!(repo.load(&project).unwrap(), None);
+        repo.save(&project, INTROSPECTION).unwrap();
+
+        let schema = repo.load(&project).unwrap().unwrap();
+        assert_eq!(schema.query_type, "Query");
+        assert_eq!(schema.types["Query"]["users"], "User");
+        assert_eq!(schema.types["User"]["id"], "ID");
+        assert!(!schema.types.contains_key("ID"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use crate::domain::{
+    project::Project,
+    repository::SnapshotRepository,
+    snapshot::{Snapshot, SNAPSHOT_SUFFIX},
+};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Keeps snapshots next to the request files, e.g. `users.http.snap`, so they are reviewed with them.
+pub struct FsSnapshotRepository {
+    root: PathBuf,
+}
+
+impl FsSnapshotRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, project: &Project, request_id: &str) -> PathBuf {
+        self.root.join(&project.name).join(format!("{}.http{}", request_id, SNAPSHOT_SUFFIX))
+    }
+}
+
+impl SnapshotRepository for FsSnapshotRepository {
+    fn load(&self, project: &Project, request_id: &str) -> Result<Option<Snapshot>> {
+        let path = self.path(project, request_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read snapshot: {:?}", path))?;
+        Snapshot::parse(&content).with_context(|| format!("Failed to parse snapshot: {:?}", path)).map(Some)
+    }
+
+    fn save(&self, project: &Project, request_id: &str, snapshot: &Snapshot) -> Result<()> {
+        let path = self.path(project, request_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, snapshot.to_text()).with_context(|| format!("Failed to write snapshot: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_next_to_request() {
+        let root = std::env::temp_dir().join(format!("htup-snapshots-{}", std::process::id()));
+        let repo = FsSnapshotRepository::new(&root);
+        let project = Project::new("api");
+        let snapshot = Snapshot { status: 200, content_type: Some("application/json".to_string()), body: "{}".to_string() };
+
+        assert_eq!(repo.load(&project, "users").unwrap(), None);
+        repo.save(&project, "users", &snapshot).unwrap();
+        assert!(root.join("api/users.http.snap").exists());
+        assert_eq!(repo.load(&project, "users").unwrap(), Some(snapshot));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
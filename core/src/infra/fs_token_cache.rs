@@ -0,0 +1,113 @@
+use crate::domain::{oauth2::OAuth2Token, repository::TokenCache};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize)]
+struct CachedToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<u64>,
+}
+
+/// Caches OAuth2 tokens in a single JSON file outside of any project.
+pub struct FsTokenCache {
+    path: PathBuf,
+}
+
+impl FsTokenCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn read_all(&self) -> Result<HashMap<String, CachedToken>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read token cache: {:?}", self.path))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse token cache: {:?}", self.path))
+    }
+}
+
+impl TokenCache for FsTokenCache {
+    fn load(&self, key: &str) -> Result<Option<OAuth2Token>> {
+        Ok(self.read_all()?.remove(key).map(|t| OAuth2Token {
+            access_token: t.access_token,
+            refresh_token: t.refresh_token,
+            expires_at: t.expires_at,
+        }))
+    }
+
+    fn save(&self, key: &str, token: &OAuth2Token) -> Result<()> {
+        let mut tokens = self.read_all()?;
+        tokens.insert(
+            key.to_string(),
+            CachedToken {
+                access_token: token.access_token.clone(),
+                refresh_token: token.refresh_token.clone(),
+                expires_at: token.expires_at,
+            },
+        );
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_private(&self.path, serde_json::to_string_pretty(&tokens)?.as_bytes())
+            .with_context(|| format!("Failed to write token cache: {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+/// Writes a file only its owner can read, as tokens are secrets.
+fn write_private(path: &std::path::Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files; tighten caches written by older versions
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token() -> OAuth2Token {
+        OAuth2Token { access_token: "abc".to_string(), refresh_token: Some("r1".to_string()), expires_at: Some(42) }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let root = std::env::temp_dir().join(format!("htup-tokens-{}", std::process::id()));
+        let cache = FsTokenCache::new(root.join("htup").join("tokens.json"));
+        cache.save("key", &token()).unwrap();
+        assert_eq!(cache.load("key").unwrap(), Some(token()));
+        assert_eq!(cache.load("other").unwrap(), None);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = std::env::temp_dir().join(format!("htup-tokens-private-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("tokens.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        FsTokenCache::new(&path).save("key", &token()).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
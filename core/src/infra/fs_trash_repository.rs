@@ -0,0 +1,175 @@
+use crate::domain::{
+    repository::{Clock, TrashRepository},
+    trash::TrashEntry,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Directory of the trash in the projects root; hidden, so it is never listed as a project.
+pub const TRASH_DIR: &str = ".trash";
+const MANIFEST_FILE: &str = ".manifest.json";
+
+/// What an entry holds, relative to the projects root.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    label: String,
+    paths: Vec<PathBuf>,
+}
+
+/// Keeps deleted files in `.trash/<id>/` under the paths they had in the projects root.
+/// Ids start with the time of the deletion in milliseconds, which is what purging goes by.
+pub struct FsTrashRepository {
+    root: PathBuf,
+    clock: Arc<dyn Clock>,
+}
+
+impl FsTrashRepository {
+    pub fn new(root: impl Into<PathBuf>, clock: Arc<dyn Clock>) -> Self {
+        Self { root: root.into(), clock }
+    }
+}
+
+impl TrashRepository for FsTrashRepository {
+    fn put(&self, label: &str, paths: &[PathBuf]) -> Result<TrashEntry> {
+        if let Some(outside) = paths.iter().find(|p| p.components().any(|c| !matches!(c, Component::Normal(_)))) {
+            anyhow::bail!("{:?} is outside of {:?}", outside, self.root);
+        }
+        let paths: Vec<&PathBuf> = paths.iter().filter(|p| self.root.join(p).exists()).collect();
+        if paths.is_empty() {
+            anyhow::bail!("Nothing to delete: {}", label);
+        }
+        let trash = self.root.join(TRASH_DIR);
+        fs::create_dir_all(&trash).with_context(|| format!("Failed to create trash: {:?}", trash))?;
+        // Ignores the whole trash, whether git tracks the projects root or the projects in it
+        fs::write(trash.join(".gitignore"), "*\n")?;
+        let stamp = self.clock.now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let mut id = stamp.to_string();
+        for n in 2.. {
+            if !trash.join(&id).exists() {
+                break;
+            }
+            id = format!("{}-{}", stamp, n);
+        }
+
+        let dir = trash.join(&id);
+        let mut moved = Vec::new();
+        for relative in paths {
+            move_path(&self.root.join(relative), &dir.join(relative))?;
+            moved.push(relative.clone());
+        }
+        let manifest = Manifest { label: label.to_string(), paths: moved };
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write trash entry: {:?}", dir))?;
+        Ok(TrashEntry { id, label: label.to_string() })
+    }
+
+    fn restore(&self, entry: &TrashEntry) -> Result<()> {
+        let dir = self.root.join(TRASH_DIR).join(&entry.id);
+        let path = dir.join(MANIFEST_FILE);
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read trash entry: {:?}", path))?;
+        let manifest: Manifest = serde_json::from_str(&content).with_context(|| format!("Failed to parse trash entry: {:?}", path))?;
+        if let Some(taken) = manifest.paths.iter().find(|p| self.root.join(p).exists()) {
+            anyhow::bail!("Cannot restore '{}': {} already exists", entry.label, taken.display());
+        }
+        for relative in &manifest.paths {
+            move_path(&dir.join(relative), &self.root.join(relative))?;
+        }
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove trash entry: {:?}", dir))
+    }
+
+    fn purge(&self, max_age: Duration) -> Result<()> {
+        let trash = self.root.join(TRASH_DIR);
+        if !trash.exists() {
+            return Ok(());
+        }
+        let now = self.clock.now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let cutoff = now.saturating_sub(max_age).as_millis();
+        for entry in fs::read_dir(&trash).with_context(|| format!("Failed to read trash: {:?}", trash))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let stamp = name.split('-').next().and_then(|s| s.parse::<u128>().ok());
+            if stamp.is_some_and(|stamp| stamp < cutoff) && entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path()).with_context(|| format!("Failed to remove trash entry: {:?}", entry.path()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn move_path(source: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(source, target).with_context(|| format!("Failed to move {:?} to {:?}", source, target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::MockClock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// A clock that reads the seconds from a shared counter.
+    fn clock(seconds: Arc<AtomicU64>) -> Arc<dyn Clock> {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(move || UNIX_EPOCH + Duration::from_secs(seconds.load(Ordering::SeqCst)));
+        Arc::new(clock)
+    }
+
+    #[test]
+    fn test_put_and_restore() {
+        let root = std::env::temp_dir().join(format!("htup-trash-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("shop/users")).unwrap();
+        fs::write(root.join("shop/users/get_user.http"), "GET https://api.shop.com/users/1").unwrap();
+        let trash = FsTrashRepository::new(&root, clock(Arc::new(AtomicU64::new(42))));
+
+        let files = [PathBuf::from("shop/users/get_user.http"), PathBuf::from("shop/users/get_user.mock")];
+        let entry = trash.put("shop/users/get_user", &files).unwrap();
+        assert_eq!(entry, TrashEntry { id: "42000".to_string(), label: "shop/users/get_user".to_string() });
+        assert!(!root.join("shop/users/get_user.http").exists());
+        assert!(root.join(TRASH_DIR).join(&entry.id).join("shop/users/get_user.http").exists());
+        assert_eq!(fs::read_to_string(root.join(TRASH_DIR).join(".gitignore")).unwrap(), "*\n");
+
+        // Something new in its place blocks the restore
+        fs::write(root.join("shop/users/get_user.http"), "GET /").unwrap();
+        let error = trash.restore(&entry).unwrap_err();
+        assert!(error.to_string().starts_with("Cannot restore 'shop/users/get_user'"));
+        fs::remove_file(root.join("shop/users/get_user.http")).unwrap();
+
+        trash.restore(&entry).unwrap();
+        assert_eq!(fs::read_to_string(root.join("shop/users/get_user.http")).unwrap(), "GET https://api.shop.com/users/1");
+        assert!(!root.join(TRASH_DIR).join(&entry.id).exists());
+        assert!(trash.put("nothing", &files[1..]).is_err());
+        assert!(trash.put("outside", &[PathBuf::from("../etc")]).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_purge_drops_old_entries() {
+        let root = std::env::temp_dir().join(format!("htup-trash-purge-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for project in ["shop", "blog", "wiki"] {
+            fs::create_dir_all(root.join(project)).unwrap();
+        }
+        let seconds = Arc::new(AtomicU64::new(100));
+        let trash = FsTrashRepository::new(&root, clock(seconds.clone()));
+
+        let shop = trash.put("shop", &[PathBuf::from("shop")]).unwrap();
+        let blog = trash.put("blog", &[PathBuf::from("blog")]).unwrap();
+        assert_eq!(blog.id, "100000-2");
+        seconds.store(200, Ordering::SeqCst);
+        let wiki = trash.put("wiki", &[PathBuf::from("wiki")]).unwrap();
+
+        trash.purge(Duration::from_secs(50)).unwrap();
+        for (entry, kept) in [(shop, false), (blog, false), (wiki, true)] {
+            assert_eq!(root.join(TRASH_DIR).join(&entry.id).exists(), kept, "{}", entry.label);
+        }
+        assert!(root.join(TRASH_DIR).join(".gitignore").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,479 @@
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    grpc::{status_name, GrpcTarget, ProtoSource},
+    repository::GrpcClient,
+    request::Request,
+    response::Response,
+};
+use crate::infra::proto_parser::load_proto_files;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use std::collections::HashMap;
+use std::time::Instant;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic_reflection::pb::{
+    server_reflection_client::ServerReflectionClient,
+    server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse,
+    ServerReflectionRequest,
+};
+
+/// Calls gRPC methods with JSON messages, using `.proto` files or server reflection for the schema.
+pub struct TonicGrpcClient;
+
+impl TonicGrpcClient {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn connect(target: &GrpcTarget) -> Result<Channel> {
+        let mut endpoint = Endpoint::from_shared(target.endpoint.clone())
+            .with_context(|| format!("Invalid gRPC endpoint: {}", target.endpoint))?;
+        if target.endpoint.starts_with("https://") {
+            endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+        }
+        endpoint
+            .connect()
+            .await
+            .with_context(|| format!("Failed to connect to {}", target.endpoint))
+    }
+
+    /// Builds the descriptor pool from the project's protos, or asks the server for the service's files.
+    async fn descriptors(channel: &Channel, target: &GrpcTarget, protos: Option<ProtoSource>) -> Result<DescriptorPool> {
+        let mut pool = DescriptorPool::global();
+        match protos {
+            Some(protos) => {
+                // Well-known types come with the parser and are already in the global pool
+                let files = load_proto_files(&protos.include_dir, &protos.files)?;
+                let files: Vec<_> = files.into_iter().filter(|f| pool.get_file_by_name(f.name()).is_none()).collect();
+                pool.add_file_descriptor_protos(files)?;
+            }
+            None => {
+                let mut client = ServerReflectionClient::new(channel.clone());
+                let mut files = Vec::new();
+                let mut pending = vec![MessageRequest::FileContainingSymbol(target.service.clone())];
+                while let Some(request) = pending.pop() {
+                    for file in reflect(&mut client, request).await? {
+                        let known = pool.get_file_by_name(file.name()).is_some()
+                            || files.iter().any(|f: &prost_types::FileDescriptorProto| f.name() == file.name());
+                        if known {
+                            continue;
+                        }
+                        pending.extend(
+                            file.dependency
+                                .iter()
+                                .filter(|d| pool.get_file_by_name(d).is_none())
+                                .map(|d| MessageRequest::FileByFilename(d.clone())),
+                        );
+                        files.push(file);
+                    }
+                }
+                // Dependencies were discovered after the files that need them
+                files.reverse();
+                pool.add_file_descriptor_protos(files).context("Invalid descriptors from server reflection")?;
+            }
+        }
+        Ok(pool)
+    }
+}
+
+impl Default for TonicGrpcClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl GrpcClient for TonicGrpcClient {
+    async fn call(&self, request: &Request, protos: Option<ProtoSource>) -> Result<Response> {
+        let target = GrpcTarget::parse(&request.url)?;
+        let channel = Self::connect(&target).await?;
+        let pool = Self::descriptors(&channel, &target, protos).await?;
+        let method = find_method(&pool, &target)?;
+        if method.is_client_streaming() {
+            anyhow::bail!("Client streaming methods are not supported: {}", target.method);
+        }
+
+        let json = message_json(request.body.as_ref().and_then(|b| b.as_text()))?;
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        let message = DynamicMessage::deserialize(method.input(), &mut deserializer)
+            .with_context(|| format!("Message does not match {}", method.input().full_name()))?;
+
+        let mut call = tonic::Request::new(message);
+        insert_metadata(call.metadata_mut(), request)?;
+        let path = format!("/{}/{}", target.service, target.method)
+            .parse()
+            .context("Invalid gRPC method path")?;
+
+        let start = Instant::now();
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready().await.context("gRPC channel is not ready")?;
+        // Unary calls are sent as server streaming calls: same wire format, but trailers stay separate
+        let result = grpc.server_streaming(call, path, DynamicCodec(method.output())).await;
+
+        let mut response = match result {
+            Ok(streaming) => {
+                let headers = metadata_to_map(streaming.metadata());
+                let mut stream = streaming.into_inner();
+                let mut messages = Vec::new();
+                let status = loop {
+                    match stream.message().await {
+                        Ok(Some(message)) => messages.push(serde_json::to_value(&message)?),
+                        Ok(None) => break None,
+                        Err(status) => break Some(status),
+                    }
+                };
+                let body = if method.is_server_streaming() {
+                    serde_json::to_string_pretty(&messages)?
+                } else {
+                    messages.pop().map(|m| serde_json::to_string_pretty(&m)).transpose()?.unwrap_or_default()
+                };
+                let mut response = match &status {
+                    Some(status) => status_response(status, body),
+                    None => Response::new(0, status_name(0).to_string(), body, Default::default()),
+                };
+                response.headers = headers;
+                if status.is_none() {
+                    if let Ok(Some(trailers)) = stream.trailers().await {
+                        response.trailers = metadata_to_map(&trailers);
+                    }
+                }
+                response
+            }
+            Err(status) => status_response(&status, status.message().to_string()),
+        };
+        response.latency = start.elapsed();
+        Ok(response)
+    }
+}
+
+/// Maps a gRPC status into a response: the status code and name, with its metadata as trailers.
+fn status_response(status: &tonic::Status, body: String) -> Response {
+    let code = status.code() as u16;
+    let mut status_text = status_name(code).to_string();
+    if !status.message().is_empty() {
+        status_text = format!("{}: {}", status_text, status.message());
+    }
+    let mut response = Response::new(code, status_text, body, Default::default());
+    response.trailers = metadata_to_map(status.metadata());
+    response
+}
+
+fn find_method(pool: &DescriptorPool, target: &GrpcTarget) -> Result<MethodDescriptor> {
+    let service = pool
+        .get_service_by_name(&target.service)
+        .with_context(|| format!("Unknown gRPC service: {}", target.service))?;
+    let method = service.methods().find(|m| m.name() == target.method);
+    method.with_context(|| format!("Unknown method {} of {}", target.method, target.service))
+}
+
+/// Validates that a message body is a JSON object (an empty body is an empty message).
+pub fn message_json(body: Option<&str>) -> Result<String> {
+    let body = body.map(str::trim).filter(|b| !b.is_empty()).unwrap_or("{}");
+    let value: serde_json::Value = serde_json::from_str(body).context("gRPC message must be JSON")?;
+    if !value.is_object() {
+        anyhow::bail!("gRPC message must be a JSON object");
+    }
+    Ok(body.to_string())
+}
+
+/// Metadata set by the gRPC transport itself, which headers must not override.
+const RESERVED_METADATA: [&str; 2] = ["content-type", "te"];
+
+/// Sends headers and auth as request metadata.
+fn insert_metadata(metadata: &mut MetadataMap, request: &Request) -> Result<()> {
+    let mut entries: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .filter(|(k, _)| !RESERVED_METADATA.iter().any(|r| k.eq_ignore_ascii_case(r)))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    match &request.auth {
+        Some(Auth::Basic { username, password }) => {
+            entries.push(("authorization".to_string(), format!("Basic {}", STANDARD.encode(format!("{}:{}", username, password)))));
+        }
+        Some(Auth::Bearer { token }) => entries.push(("authorization".to_string(), format!("Bearer {}", token))),
+        Some(Auth::ApiKey { location: ApiKeyLocation::Header, name, value }) => entries.push((name.clone(), value.clone())),
+        Some(Auth::ApiKey { location: ApiKeyLocation::Query, .. }) => {
+            anyhow::bail!("gRPC requests cannot send an API key in the query")
+        }
+        Some(Auth::Digest { .. }) => anyhow::bail!("Digest auth is not supported for gRPC requests"),
+        None => {}
+    }
+
+    for (k, v) in entries {
+        let key = MetadataKey::from_bytes(k.to_lowercase().as_bytes()).with_context(|| format!("Invalid metadata key: {}", k))?;
+        let value = MetadataValue::try_from(v.as_str()).with_context(|| format!("Invalid value for metadata {}", k))?;
+        metadata.insert(key, value);
+    }
+    Ok(())
+}
+
+fn metadata_to_map(metadata: &MetadataMap) -> HashMap<String, String> {
+    metadata
+        .clone()
+        .into_headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).to_string()))
+        .collect()
+}
+
+/// Sends one reflection request and returns the file descriptors of the answer.
+async fn reflect(client: &mut ServerReflectionClient<Channel>, request: MessageRequest) -> Result<Vec<prost_types::FileDescriptorProto>> {
+    let request = ServerReflectionRequest { host: String::new(), message_request: Some(request) };
+    let mut responses = client
+        .server_reflection_info(futures_util::stream::iter(vec![request]))
+        .await
+        .context("Server reflection is not available; configure grpc_proto_path instead")?
+        .into_inner();
+    let response = responses.message().await?.context("Empty server reflection response")?;
+
+    match response.message_response {
+        Some(MessageResponse::FileDescriptorResponse(files)) => files
+            .file_descriptor_proto
+            .iter()
+            .map(|bytes| prost_types::FileDescriptorProto::decode(bytes.as_slice()).context("Invalid file descriptor"))
+            .collect(),
+        Some(MessageResponse::ErrorResponse(e)) => anyhow::bail!("Server reflection failed: {}", e.error_message),
+        _ => anyhow::bail!("Unexpected server reflection response"),
+    }
+}
+
+/// Encodes any message and decodes into the given message type.
+#[derive(Clone)]
+pub struct DynamicCodec(pub MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicCodec;
+    type Decoder = DynamicCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        self.clone()
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        self.clone()
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst).map_err(|e| tonic::Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|e| tonic::Status::internal(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::body::Body;
+    use prost_reflect::Value;
+    use tonic::codegen::{http, BoxFuture, Service};
+    use tonic::server::{NamedService, ServerStreamingService, UnaryService};
+    use tonic::transport::Server;
+
+    const PROTO: &str = r#"
+        syntax = "proto3";
+        package demo;
+        message HelloRequest { string name = 1; int32 times = 2; }
+        message HelloReply { string message = 1; }
+        service Greeter {
+            rpc SayHello (HelloRequest) returns (HelloReply);
+            rpc SayHellos (HelloRequest) returns (stream HelloReply);
+        }
+    "#;
+
+    fn reply(output: &MessageDescriptor, text: String) -> DynamicMessage {
+        let mut reply = DynamicMessage::new(output.clone());
+        reply.set_field_by_name("message", Value::String(text));
+        reply
+    }
+
+    fn field(request: &tonic::Request<DynamicMessage>, name: &str) -> Value {
+        request.get_ref().get_field_by_name(name).unwrap().into_owned()
+    }
+
+    struct SayHello(MessageDescriptor);
+
+    impl UnaryService<DynamicMessage> for SayHello {
+        type Response = DynamicMessage;
+        type Future = BoxFuture<tonic::Response<DynamicMessage>, tonic::Status>;
+
+        fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+            let name = field(&request, "name").as_str().unwrap().to_string();
+            let user = request.metadata().get("x-user").map(|v| v.to_str().unwrap().to_string()).unwrap_or_default();
+            let output = self.0.clone();
+            Box::pin(async move {
+                if name.is_empty() {
+                    let mut status = tonic::Status::invalid_argument("name is required");
+                    status.metadata_mut().insert("x-reason", "empty-name".parse().unwrap());
+                    return Err(status);
+                }
+                Ok(tonic::Response::new(reply(&output, format!("Hello {} from {}", name, user))))
+            })
+        }
+    }
+
+    struct SayHellos(MessageDescriptor);
+
+    impl ServerStreamingService<DynamicMessage> for SayHellos {
+        type Response = DynamicMessage;
+        type ResponseStream = futures_util::stream::Iter<std::vec::IntoIter<Result<DynamicMessage, tonic::Status>>>;
+        type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+
+        fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+            let name = field(&request, "name").as_str().unwrap().to_string();
+            let times = field(&request, "times").as_i32().unwrap();
+            let replies = (0..times).map(|i| reply(&self.0, format!("Hello {} #{}", name, i))).map(Ok).collect::<Vec<_>>();
+            Box::pin(async move { Ok(tonic::Response::new(futures_util::stream::iter(replies))) })
+        }
+    }
+
+    /// Serves `demo.Greeter` from the parsed descriptors, the way generated code would.
+    #[derive(Clone)]
+    struct Greeter(DescriptorPool);
+
+    impl NamedService for Greeter {
+        const NAME: &'static str = "demo.Greeter";
+    }
+
+    impl Service<http::Request<tonic::transport::Body>> for Greeter {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<tonic::transport::Body>) -> Self::Future {
+            let service = self.0.get_service_by_name(Self::NAME).unwrap();
+            let name = request.uri().path().rsplit('/').next().unwrap_or_default().to_string();
+            let method = service.methods().find(|m| m.name() == name).unwrap();
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(DynamicCodec(method.input()));
+                Ok(if method.is_server_streaming() {
+                    grpc.server_streaming(SayHellos(method.output()), request).await
+                } else {
+                    grpc.unary(SayHello(method.output()), request).await
+                })
+            })
+        }
+    }
+
+    /// Starts the greeter, with server reflection if asked. Returns its address.
+    async fn serve(reflection: bool) -> String {
+        let protos = protos();
+        let file = load_proto_files(&protos.include_dir, &protos.files).unwrap().pop().unwrap();
+        let mut pool = DescriptorPool::global();
+        pool.add_file_descriptor_proto(file.clone()).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut router = Server::builder().add_service(Greeter(pool));
+        if reflection {
+            let set = prost_types::FileDescriptorSet { file: vec![file] };
+            router = router.add_service(tonic_reflection::server::Builder::configure().register_file_descriptor_set(set).build().unwrap());
+        }
+        tokio::spawn(router.serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)));
+        addr.to_string()
+    }
+
+    fn request(addr: &str, method: &str, body: &str) -> Request {
+        let mut request = Request::new("GRPC".to_string(), format!("{}/demo.Greeter/{}", addr, method));
+        request.body = Some(Body::Text(body.to_string()));
+        request
+    }
+
+    fn protos() -> ProtoSource {
+        let dir = std::env::temp_dir().join(format!("htup-grpc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("demo.proto"), PROTO).unwrap();
+        ProtoSource { files: vec![dir.join("demo.proto")], include_dir: dir }
+    }
+
+    #[test]
+    fn test_metadata_cannot_override_transport_headers() {
+        let mut request = Request::new("GRPC", "localhost:50051/demo.Greeter/SayHello");
+        request.headers.insert("Content-Type".to_string(), "application/json".to_string());
+        request.headers.insert("TE".to_string(), "gzip".to_string());
+        request.headers.insert("X-User".to_string(), "alice".to_string());
+
+        let mut metadata = MetadataMap::new();
+        insert_metadata(&mut metadata, &request).unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata.get("x-user").unwrap(), "alice");
+    }
+
+    #[test]
+    fn test_message_json() {
+        assert_eq!(message_json(None).unwrap(), "{}");
+        assert_eq!(message_json(Some(" {\"a\": 1} ")).unwrap(), "{\"a\": 1}");
+        assert!(message_json(Some("[1]")).is_err());
+        assert!(message_json(Some("nope")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unary_call_with_proto_files() {
+        let addr = serve(false).await;
+        let mut request = request(&addr, "SayHello", r#"{"name": "htup"}"#);
+        request.headers.insert("X-User".to_string(), "alice".to_string());
+
+        let response = TonicGrpcClient::new().call(&request, Some(protos())).await.unwrap();
+        assert_eq!(response.status, 0);
+        assert_eq!(response.status_text, "OK");
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["message"], "Hello htup from alice");
+    }
+
+    #[tokio::test]
+    async fn test_server_streaming_call_with_reflection() {
+        let addr = serve(true).await;
+        let request = request(&addr, "SayHellos", r#"{"name": "htup", "times": 3}"#);
+
+        let response = TonicGrpcClient::new().call(&request, None).await.unwrap();
+        assert_eq!(response.status, 0);
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body.as_array().unwrap().len(), 3);
+        assert_eq!(body[2]["message"], "Hello htup #2");
+    }
+
+    #[tokio::test]
+    async fn test_error_status_maps_to_response() {
+        let addr = serve(false).await;
+        let request = request(&addr, "SayHello", "");
+
+        let response = TonicGrpcClient::new().call(&request, Some(protos())).await.unwrap();
+        assert_eq!(response.status, 3);
+        assert_eq!(response.status_text, "INVALID_ARGUMENT: name is required");
+        assert_eq!(response.trailers.get("x-reason").map(String::as_str), Some("empty-name"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method_fails() {
+        let addr = serve(false).await;
+        let request = request(&addr, "SayGoodbye", "{}");
+        let err = TonicGrpcClient::new().call(&request, Some(protos())).await.unwrap_err();
+        assert!(err.to_string().contains("Unknown method SayGoodbye"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Generic HMAC request signing with a configurable canonical string.
+//!
+//! Environment variables:
+//! - `hmac_secret` (required): the signing key.
+//! - `hmac_algorithm`: `sha256` (default) or `sha512`.
+//! - `hmac_template`: the string to sign, default `{method}\n{path}\n{timestamp}\n{body_sha256}`.
+//!   Placeholders: `{method}`, `{url}`, `{path}`, `{query}`, `{host}`, `{timestamp}`,
+//!   `{body}`, `{body_sha256}` and `{header:Name}`. `\n` is read as a newline.
+//! - `hmac_encoding`: `hex` (default) or `base64`.
+//! - `hmac_header`: header receiving the signature, default `X-Signature`.
+//! - `hmac_header_value`: header value format, default `{signature}`.
+//! - `hmac_timestamp_header`: if set, the timestamp is also sent in this header.
+
+use crate::domain::{
+    environment::Environment,
+    repository::{Clock, RequestSigner},
+    request::Request,
+};
+use crate::infra::body::{payload_bytes, payload_sha256};
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+const DEFAULT_TEMPLATE: &str = "{method}\\n{path}\\n{timestamp}\\n{body_sha256}";
+
+pub struct HmacSigner {
+    clock: Arc<dyn Clock>,
+}
+
+impl HmacSigner {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+}
+
+impl RequestSigner for HmacSigner {
+    fn name(&self) -> &str {
+        "hmac"
+    }
+
+    fn sign(&self, request: &mut Request, environment: &Environment) -> Result<()> {
+        let secret = environment.get("hmac_secret").context("HMAC signing requires 'hmac_secret'")?;
+        let var = |name: &str, default: &str| environment.get(name).unwrap_or_else(|| default.to_string());
+
+        let timestamp = self.clock.now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            .to_string();
+        if let Some(header) = environment.get("hmac_timestamp_header") {
+            request.headers.insert(header, timestamp.clone());
+        }
+
+        let canonical = render_template(&var("hmac_template", DEFAULT_TEMPLATE), request, &timestamp)?;
+        let digest = match var("hmac_algorithm", "sha256").to_lowercase().as_str() {
+            "sha256" => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+                mac.update(canonical.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            "sha512" => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+                mac.update(canonical.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            other => anyhow::bail!("Unsupported hmac_algorithm: {}", other),
+        };
+        let signature = match var("hmac_encoding", "hex").to_lowercase().as_str() {
+            "hex" => digest.iter().map(|b| format!("{:02x}", b)).collect(),
+            "base64" => STANDARD.encode(&digest),
+            other => anyhow::bail!("Unsupported hmac_encoding: {}", other),
+        };
+
+        let value = var("hmac_header_value", "{signature}").replace("{signature}", &signature);
+        request.headers.insert(var("hmac_header", "X-Signature"), value);
+        Ok(())
+    }
+}
+
+/// Builds the canonical string from the template.
+fn render_template(template: &str, request: &Request, timestamp: &str) -> Result<String> {
+    let url = reqwest::Url::parse(&request.url).with_context(|| format!("Invalid URL: {}", request.url))?;
+    let body = || -> Result<Vec<u8>> {
+        payload_bytes(request.body.as_ref())?.context("Multipart bodies cannot be used in hmac_template")
+    };
+
+    let template = template.replace("\\n", "\n");
+
+    let mut result = String::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            result.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let key = &rest[start + 1..start + end];
+        let value = match key {
+            "method" => request.method.to_uppercase(),
+            "url" => request.url.clone(),
+            "path" => url.path().to_string(),
+            "query" => url.query().unwrap_or("").to_string(),
+            "host" => url.host_str().unwrap_or("").to_string(),
+            "timestamp" => timestamp.to_string(),
+            "body" => String::from_utf8_lossy(&body()?).to_string(),
+            "body_sha256" => payload_sha256(request.body.as_ref())?
+                .context("Multipart bodies cannot be used in hmac_template")?
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            _ => match key.strip_prefix("header:") {
+                Some(name) => request
+                    .headers
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                    .map(|(_, v)| v.clone())
+                    .unwrap_or_default(),
+                None => anyhow::bail!("Unknown placeholder in hmac_template: {{{}}}", key),
+            },
+        };
+        result.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{body::Body, repository::MockClock};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn signer() -> HmacSigner {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        HmacSigner::new(Arc::new(clock))
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Environment {
+        let vars = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Environment::new(vars, HashMap::new())
+    }
+
+    #[test]
+    fn test_rfc4231_test_case_2() {
+        let mut request = Request::new("POST", "https://api.example.com/");
+        request.body = Some(Body::Text("what do ya want for nothing?".to_string()));
+        let environment = env(&[("hmac_secret", "Jefe"), ("hmac_template", "{body}")]);
+
+        signer().sign(&mut request, &environment).unwrap();
+        assert_eq!(
+            request.headers["X-Signature"],
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let environment = env(&[("hmac_secret", "Jefe"), ("hmac_template", "{body}"), ("hmac_algorithm", "sha512")]);
+        signer().sign(&mut request, &environment).unwrap();
+        assert_eq!(
+            request.headers["X-Signature"],
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+    }
+
+    #[test]
+    fn test_template_and_header_format() {
+        let mut request = Request::new("get", "https://api.example.com/orders?id=1");
+        request.headers.insert("X-Client".to_string(), "cli".to_string());
+        let environment = env(&[
+            ("hmac_secret", "key"),
+            ("hmac_template", "{method} {path}?{query}\\n{header:x-client}\\n{timestamp}"),
+            ("hmac_header", "Authorization"),
+            ("hmac_header_value", "HMAC {signature}"),
+            ("hmac_encoding", "base64"),
+            ("hmac_timestamp_header", "X-Timestamp"),
+        ]);
+
+        signer().sign(&mut request, &environment).unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"GET /orders?id=1\ncli\n1700000000");
+        let expected = STANDARD.encode(mac.finalize().into_bytes());
+        assert_eq!(request.headers["Authorization"], format!("HMAC {}", expected));
+        assert_eq!(request.headers["X-Timestamp"], "1700000000");
+    }
+}
@@ -0,0 +1,339 @@
+//! Insomnia v4 exports.
+
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    body::{Body, Part, PartContent},
+    collection::{Collection, CollectionItem},
+    request::Request,
+};
+use crate::infra::fs_collection_repository::{add_variable, set_default_header, text, MULTIPART_BOUNDARY};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub fn is_insomnia(value: &Value) -> bool {
+    value["_type"] == "export" && value["__export_format"].as_i64() == Some(4)
+}
+
+pub fn parse(root: &Value) -> Result<Collection> {
+    let resources = root["resources"].as_array().context("Missing resources")?;
+    let mut importer = Importer { resources, collection: Collection::default(), template: Regex::new(r"\{\{\s*(?:_\.)?([\w.-]+)\s*\}\}")? };
+
+    let workspaces: Vec<&Value> = resources.iter().filter(|r| r["_type"] == "workspace").collect();
+    match workspaces.as_slice() {
+        [workspace] => {
+            importer.collection.name = text(&workspace["name"]);
+            importer.collection.items = importer.items(&text(&workspace["_id"]), "", &None);
+            importer.environments(&text(&workspace["_id"]));
+        }
+        // Several workspaces become one folder each
+        _ => {
+            importer.collection.name = "insomnia".to_string();
+            for workspace in workspaces {
+                let (id, name) = (text(&workspace["_id"]), text(&workspace["name"]));
+                let items = importer.items(&id, &name, &None);
+                importer.collection.items.push(CollectionItem::Folder { name, items });
+                importer.environments(&id);
+            }
+        }
+    }
+    Ok(importer.collection)
+}
+
+struct Importer<'a> {
+    resources: &'a [Value],
+    collection: Collection,
+    template: Regex,
+}
+
+impl<'a> Importer<'a> {
+    /// Resources under `parent`, in the order Insomnia shows them.
+    fn children(&self, parent: &str) -> Vec<&'a Value> {
+        let mut children: Vec<&Value> = self.resources.iter().filter(|r| r["parentId"] == parent).collect();
+        children.sort_by(|a, b| {
+            let key = |v: &Value| v["metaSortKey"].as_f64().unwrap_or_default();
+            key(a).total_cmp(&key(b))
+        });
+        children
+    }
+
+    fn items(&mut self, parent: &str, parent_path: &str, inherited: &Option<(Option<Auth>, Option<String>)>) -> Vec<CollectionItem> {
+        let mut items = Vec::new();
+        for resource in self.children(parent) {
+            let name = text(&resource["name"]);
+            let path = if parent_path.is_empty() { name.clone() } else { format!("{} / {}", parent_path, name) };
+            self.scripts(resource, &path);
+            let auth = match self.auth(&resource["authentication"], &path) {
+                Some(auth) => Some(auth),
+                None => inherited.clone(),
+            };
+            match resource["_type"].as_str() {
+                Some("request_group") => {
+                    let children = self.items(&text(&resource["_id"]), &path, &auth);
+                    items.push(CollectionItem::Folder { name, items: children });
+                }
+                Some("request") => {
+                    let request = self.request(resource, auth);
+                    if uses_template_tags(&request) {
+                        self.collection.warnings.push(format!("{}: template tags ({{% %}}) are not evaluated", path));
+                    }
+                    items.push(CollectionItem::Request { name, request: Box::new(request) });
+                }
+                Some("websocket_request") => {
+                    let mut request = Request::new("WS", self.template(&text(&resource["url"])));
+                    self.headers(resource, &mut request);
+                    items.push(CollectionItem::Request { name, request: Box::new(request) });
+                }
+                Some("grpc_request") => self.collection.warnings.push(format!("{}: gRPC requests are not imported", path)),
+                _ => {}
+            }
+        }
+        items
+    }
+
+    fn request(&self, resource: &Value, auth: Option<(Option<Auth>, Option<String>)>) -> Request {
+        let mut url = self.template(&text(&resource["url"]));
+        let query: Vec<String> = enabled(&resource["parameters"])
+            .map(|p| format!("{}={}", self.template(&text(&p["name"])), self.template(&text(&p["value"]))))
+            .collect();
+        if !query.is_empty() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query.join("&"));
+        }
+        let mut request = Request::new(resource["method"].as_str().unwrap_or("GET"), url);
+        if let Some((auth, signer)) = auth {
+            request.auth = auth;
+            request.signer = signer;
+        }
+        self.headers(resource, &mut request);
+
+        let body = &resource["body"];
+        let mime_type = text(&body["mimeType"]);
+        let fields = |importer: &Self| -> Vec<(String, String, &Value)> {
+            enabled(&body["params"])
+                .map(|p| (importer.template(&text(&p["name"])), importer.template(&text(&p["value"])), p))
+                .collect()
+        };
+        if mime_type == "application/x-www-form-urlencoded" {
+            let fields = fields(self).into_iter().map(|(name, value, _)| (name, value)).collect();
+            set_default_header(&mut request, "Content-Type", &mime_type);
+            request.body = Some(Body::Form(fields));
+        } else if mime_type == "multipart/form-data" {
+            let parts = fields(self)
+                .into_iter()
+                .map(|(name, value, param)| {
+                    let content = match param["type"].as_str() {
+                        Some("file") => PartContent::File(PathBuf::from(text(&param["fileName"]))),
+                        _ => PartContent::Text(value),
+                    };
+                    Part { name, filename: None, content_type: None, content }
+                })
+                .collect();
+            request.headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+            request.headers.insert("Content-Type".to_string(), format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY));
+            request.body = Some(Body::Multipart { boundary: MULTIPART_BOUNDARY.to_string(), parts });
+        } else if mime_type == "application/graphql" {
+            let payload: Value = serde_json::from_str(&text(&body["text"])).unwrap_or_default();
+            let variables = match &payload["variables"] {
+                Value::Null => None,
+                Value::Object(map) if map.is_empty() => None,
+                variables => serde_json::to_string_pretty(variables).ok(),
+            };
+            request.headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+            request.body = Some(Body::GraphQL { query: self.template(&text(&payload["query"])), variables });
+        } else if !text(&body["fileName"]).is_empty() {
+            request.body = Some(Body::File(PathBuf::from(text(&body["fileName"]))));
+        } else if !text(&body["text"]).is_empty() {
+            if !mime_type.is_empty() {
+                set_default_header(&mut request, "Content-Type", &mime_type);
+            }
+            request.body = Some(Body::Text(self.template(&text(&body["text"]))));
+        }
+        request
+    }
+
+    fn headers(&self, resource: &Value, request: &mut Request) {
+        for header in enabled(&resource["headers"]) {
+            request.headers.insert(self.template(&text(&header["name"])), self.template(&text(&header["value"])));
+        }
+    }
+
+    /// `None` when no authentication is set, so the parent's applies.
+    fn auth(&mut self, value: &Value, path: &str) -> Option<(Option<Auth>, Option<String>)> {
+        let kind = value["type"].as_str()?;
+        if kind == "inherit" {
+            return None;
+        }
+        if value["disabled"] == Value::Bool(true) || kind == "none" {
+            return Some((None, None));
+        }
+        let field = |name: &str| self.template(&text(&value[name]));
+        let auth = match kind {
+            "basic" => Auth::Basic { username: field("username"), password: field("password") },
+            "digest" => Auth::Digest { username: field("username"), password: field("password") },
+            "bearer" => match field("prefix").as_str() {
+                "" | "Bearer" => Auth::Bearer { token: field("token") },
+                prefix => Auth::ApiKey {
+                    location: ApiKeyLocation::Header,
+                    name: "Authorization".to_string(),
+                    value: format!("{} {}", prefix, field("token")),
+                },
+            },
+            "apikey" => Auth::ApiKey {
+                location: if value["addTo"] == "queryParams" { ApiKeyLocation::Query } else { ApiKeyLocation::Header },
+                name: field("key"),
+                value: field("value"),
+            },
+            "iam" => {
+                let plain = [("aws_region", "region"), ("aws_service", "service")].map(|(variable, key)| (variable, field(key)));
+                let secret = [("aws_access_key_id", "accessKeyId"), ("aws_secret_access_key", "secretAccessKey"), ("aws_session_token", "sessionToken")]
+                    .map(|(variable, key)| (variable, field(key)));
+                for (variable, value) in plain {
+                    add_variable(&mut self.collection.variables, variable, value);
+                }
+                for (variable, value) in secret.into_iter().filter(|(_, value)| !value.is_empty()) {
+                    add_variable(&mut self.collection.secrets, variable, value);
+                }
+                return Some((None, Some("sigv4".to_string())));
+            }
+            other => {
+                self.collection.warnings.push(format!("{}: {} authentication is not supported", path, other));
+                return Some((None, None));
+            }
+        };
+        Some((Some(auth), None))
+    }
+
+    /// Imports the base environment and its first sub environment; htup keeps one per project.
+    fn environments(&mut self, workspace: &str) {
+        let bases: Vec<Value> = self.children(workspace).into_iter().filter(|r| r["_type"] == "environment").cloned().collect();
+        for base in bases {
+            let subs: Vec<Value> = self.children(&text(&base["_id"])).into_iter().filter(|r| r["_type"] == "environment").cloned().collect();
+            // The sub environment overrides the base one, so it is added first
+            if let Some(sub) = subs.first() {
+                self.environment(sub);
+            }
+            self.environment(&base);
+            for skipped in subs.iter().skip(1) {
+                self.collection.warnings.push(format!("Environment {}: not imported, only the first one is", text(&skipped["name"])));
+            }
+        }
+    }
+
+    fn environment(&mut self, environment: &Value) {
+        let mut variables = Vec::new();
+        flatten("", &environment["data"], &mut variables);
+        let target = if environment["isPrivate"] == Value::Bool(true) { &mut self.collection.secrets } else { &mut self.collection.variables };
+        for (name, value) in variables {
+            let value = self.template.replace_all(&value, "{{$1}}").to_string();
+            add_variable(target, &name, value);
+        }
+    }
+
+    fn scripts(&mut self, resource: &Value, path: &str) {
+        for (key, kind) in [("preRequestScript", "pre-request script"), ("afterResponseScript", "after-response script")] {
+            if !text(&resource[key]).trim().is_empty() {
+                self.collection.warnings.push(format!("{}: {} not imported", path, kind));
+            }
+        }
+    }
+
+    /// Turns `{{ _.name }}` into `{{name}}`. Template tags (`{% %}`) are kept as they are.
+    fn template(&self, value: &str) -> String {
+        self.template.replace_all(value, "{{$1}}").to_string()
+    }
+}
+
+fn uses_template_tags(request: &Request) -> bool {
+    let found = std::cell::Cell::new(request.url.contains("{%") || request.headers.values().any(|v| v.contains("{%")));
+    if let Some(body) = &request.body {
+        body.map_text(|t| {
+            found.set(found.get() || t.contains("{%"));
+            t.to_string()
+        });
+    }
+    found.get()
+}
+
+fn enabled(values: &Value) -> impl Iterator<Item = &Value> {
+    values.as_array().into_iter().flatten().filter(|v| v["disabled"] != Value::Bool(true))
+}
+
+/// Nested environment data is referenced as `{{ _.api.url }}`.
+fn flatten(prefix: &str, value: &Value, variables: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&name, value, variables);
+            }
+        }
+        _ if !prefix.is_empty() => variables.push((prefix.to_string(), text(value))),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+      "_type": "export",
+      "__export_format": 4,
+      "resources": [
+        { "_id": "wrk_1", "_type": "workspace", "name": "Shop" },
+        { "_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base", "data": { "base_url": "https://api.shop.com", "api": { "version": "v2" } } },
+        { "_id": "env_dev", "_type": "environment", "parentId": "env_base", "name": "Dev", "data": { "base_url": "http://localhost:3000" }, "metaSortKey": 1 },
+        { "_id": "env_prod", "_type": "environment", "parentId": "env_base", "name": "Prod", "data": {}, "metaSortKey": 2 },
+        { "_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Users", "metaSortKey": 1,
+          "authentication": { "type": "bearer", "token": "{{ _.token }}", "prefix": "" } },
+        { "_id": "req_2", "_type": "request", "parentId": "fld_1", "name": "Create user", "method": "POST", "metaSortKey": 2,
+          "url": "{{ _.base_url }}/{{ _.api.version }}/users", "authentication": {},
+          "headers": [{ "name": "X-Trace", "value": "1", "disabled": true }],
+          "body": { "mimeType": "application/x-www-form-urlencoded", "params": [{ "name": "name", "value": "htup" }] } },
+        { "_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "Get user", "method": "GET", "metaSortKey": 1,
+          "url": "{{ _.base_url }}/users/{% uuid 'v4' %}", "parameters": [{ "name": "expand", "value": "true" }],
+          "authentication": { "type": "apikey", "key": "X-Key", "value": "abc", "addTo": "queryParams" },
+          "preRequestScript": "insomnia.environment.set('a', 1);" },
+        { "_id": "req_3", "_type": "request", "parentId": "wrk_1", "name": "Query", "method": "POST", "metaSortKey": 2,
+          "url": "{{ _.base_url }}/graphql",
+          "body": { "mimeType": "application/graphql", "text": "{\"query\":\"{ me { id } }\",\"variables\":{}}" },
+          "authentication": { "type": "ntlm" } }
+      ]
+    }"#;
+
+    #[test]
+    fn test_parse_export() {
+        let value = serde_json::from_str(EXPORT).unwrap();
+        assert!(is_insomnia(&value));
+        let collection = parse(&value).unwrap();
+        assert_eq!(collection.name, "Shop");
+        assert_eq!(collection.variables, vec![
+            ("base_url".to_string(), "http://localhost:3000".to_string()),
+            ("api.version".to_string(), "v2".to_string()),
+        ]);
+        assert_eq!(collection.warnings, vec![
+            "Users / Get user: pre-request script not imported".to_string(),
+            "Users / Get user: template tags ({% %}) are not evaluated".to_string(),
+            "Query: ntlm authentication is not supported".to_string(),
+            "Environment Prod: not imported, only the first one is".to_string(),
+        ]);
+
+        let [CollectionItem::Folder { items, .. }, CollectionItem::Request { request: query, .. }] = collection.items.as_slice() else {
+            panic!("unexpected items")
+        };
+        let [CollectionItem::Request { name, request: get }, CollectionItem::Request { request: create, .. }] = items.as_slice() else {
+            panic!("unexpected folder items")
+        };
+        assert_eq!(name, "Get user");
+        assert_eq!(get.url, "{{base_url}}/users/{% uuid 'v4' %}?expand=true");
+        assert_eq!(get.auth, Some(Auth::ApiKey { location: ApiKeyLocation::Query, name: "X-Key".to_string(), value: "abc".to_string() }));
+        assert_eq!(create.url, "{{base_url}}/{{api.version}}/users");
+        assert_eq!(create.auth, Some(Auth::Bearer { token: "{{token}}".to_string() }));
+        assert!(create.headers.keys().all(|k| k != "X-Trace"));
+        assert_eq!(create.body, Some(Body::Form(vec![("name".to_string(), "htup".to_string())])));
+        assert_eq!(query.body, Some(Body::GraphQL { query: "{ me { id } }".to_string(), variables: None }));
+        assert_eq!(query.auth, None);
+    }
+}
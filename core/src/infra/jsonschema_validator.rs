@@ -0,0 +1,176 @@
+use crate::domain::repository::SchemaValidator;
+use jsonschema::{paths::PathChunk, Draft, JSONSchema};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Validates with the `jsonschema` crate (draft 7), reading OpenAPI 3.0 schemas as JSON Schema.
+/// Schemas are compiled once, along with their patterns, and reused for every response.
+pub struct JsonSchemaValidator {
+    compiled: Mutex<HashMap<String, Result<Arc<JSONSchema>, String>>>,
+}
+
+impl JsonSchemaValidator {
+    pub fn new() -> Self {
+        Self { compiled: Mutex::new(HashMap::new()) }
+    }
+
+    fn compile(&self, schema: &Value) -> Result<Arc<JSONSchema>, String> {
+        let key = schema.to_string();
+        let mut compiled = self.compiled.lock().unwrap_or_else(|e| e.into_inner());
+        compiled
+            .entry(key)
+            .or_insert_with(|| {
+                JSONSchema::options()
+                    .with_draft(Draft::Draft7)
+                    .compile(&to_json_schema(schema))
+                    .map(Arc::new)
+                    .map_err(|e| e.to_string())
+            })
+            .clone()
+    }
+}
+
+impl Default for JsonSchemaValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaValidator for JsonSchemaValidator {
+    fn validate(&self, schema: &Value, value: &Value) -> Vec<String> {
+        let compiled = match self.compile(schema) {
+            Ok(compiled) => compiled,
+            Err(e) => return vec![format!("$: the schema cannot be used: {}", e)],
+        };
+        let Err(errors) = compiled.validate(value) else {
+            return Vec::new();
+        };
+        let mut violations: Vec<(String, String)> = errors.map(|e| (json_path(e.instance_path.clone()), e.to_string())).collect();
+        violations.sort();
+        violations.into_iter().map(|(path, message)| format!("{}: {}", path, message)).collect()
+    }
+}
+
+/// `/tags/1` becomes `$.tags[1]`.
+fn json_path(pointer: jsonschema::paths::JSONPointer) -> String {
+    let mut path = "$".to_string();
+    for chunk in pointer {
+        match chunk {
+            PathChunk::Property(name) => path.push_str(&format!(".{}", name)),
+            PathChunk::Index(i) => path.push_str(&format!("[{}]", i)),
+            PathChunk::Keyword(keyword) => path.push_str(&format!(".{}", keyword)),
+        }
+    }
+    path
+}
+
+/// Rewrites the OpenAPI 3.0 dialect: `nullable`, boolean `exclusiveMinimum`/`exclusiveMaximum`,
+/// and required `writeOnly` properties, which responses never carry.
+fn to_json_schema(schema: &Value) -> Value {
+    let Value::Object(fields) = schema else {
+        return schema.clone();
+    };
+    let mut converted = Map::new();
+    for (key, value) in fields {
+        let value = match (key.as_str(), value) {
+            ("properties" | "patternProperties", Value::Object(properties)) => {
+                Value::Object(properties.iter().map(|(name, s)| (name.clone(), to_json_schema(s))).collect())
+            }
+            ("allOf" | "anyOf" | "oneOf" | "items", Value::Array(schemas)) => Value::Array(schemas.iter().map(to_json_schema).collect()),
+            ("items" | "additionalProperties" | "not", Value::Object(_)) => to_json_schema(value),
+            _ => value.clone(),
+        };
+        converted.insert(key.clone(), value);
+    }
+
+    for (exclusive, bound) in [("exclusiveMinimum", "minimum"), ("exclusiveMaximum", "maximum")] {
+        if let Some(Value::Bool(flag)) = converted.get(exclusive).cloned() {
+            converted.remove(exclusive);
+            if let (true, Some(limit)) = (flag, converted.remove(bound)) {
+                converted.insert(exclusive.to_string(), limit);
+            }
+        }
+    }
+
+    if let (Some(Value::Array(required)), Some(Value::Object(properties))) = (converted.get("required"), converted.get("properties")) {
+        let write_only = |name: &Value| name.as_str().and_then(|n| properties.get(n)).is_some_and(|p| p["writeOnly"] == Value::Bool(true));
+        let kept: Vec<Value> = required.iter().filter(|name| !write_only(name)).cloned().collect();
+        converted.insert("required".to_string(), Value::Array(kept));
+    }
+
+    if converted.remove("nullable") == Some(Value::Bool(true)) {
+        if let Some(Value::Array(allowed)) = converted.get_mut("enum") {
+            allowed.push(Value::Null);
+        }
+        match converted.get_mut("type") {
+            Some(Value::String(kind)) => {
+                let kind = Value::String(std::mem::take(kind));
+                converted.insert("type".to_string(), Value::Array(vec![kind, Value::from("null")]));
+            }
+            Some(Value::Array(kinds)) => kinds.push(Value::from("null")),
+            _ => return serde_json::json!({ "anyOf": [Value::Object(converted), { "type": "null" }] }),
+        }
+    }
+    Value::Object(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "name", "password"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "name": { "type": "string", "minLength": 1 },
+                "password": { "type": "string", "writeOnly": true },
+                "email": { "type": "string", "format": "email", "nullable": true },
+                "role": { "type": "string", "enum": ["admin", "user"] },
+                "code": { "type": "string", "pattern": "^[A-Z]{3}$" },
+                "score": { "type": "number", "minimum": 0, "exclusiveMinimum": true },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_value() {
+        let value = json!({ "id": 1, "name": "Ada", "email": null, "role": "admin", "code": "ABC", "score": 0.5, "tags": ["a"] });
+        assert_eq!(JsonSchemaValidator::new().validate(&user(), &value), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_reports_every_violation_with_its_path() {
+        let value = json!({ "id": 0, "email": "nope", "role": "root", "code": "abc", "score": 0, "tags": ["a", 2, "c"], "extra": true });
+        assert_eq!(JsonSchemaValidator::new().validate(&user(), &value), vec![
+            "$: \"name\" is a required property",
+            "$: Additional properties are not allowed ('extra' was unexpected)",
+            "$.code: \"abc\" does not match \"^[A-Z]{3}$\"",
+            "$.email: \"nope\" is not a \"email\"",
+            "$.id: 0 is less than the minimum of 1",
+            "$.role: \"root\" is not one of [\"admin\",\"user\"]",
+            "$.score: 0 is less than or equal to the minimum of 0",
+            "$.tags: [\"a\",2,\"c\"] has more than 2 items",
+            "$.tags[1]: 2 is not of type \"string\"",
+        ]);
+    }
+
+    #[test]
+    fn test_combinators_and_invalid_schemas() {
+        let validator = JsonSchemaValidator::new();
+        let schema = json!({ "oneOf": [{ "type": "integer" }, { "type": "number" }] });
+        assert_eq!(validator.validate(&schema, &json!(1.5)), Vec::<String>::new());
+        assert_eq!(validator.validate(&schema, &json!(1)).len(), 1);
+        let schema = json!({ "nullable": true, "anyOf": [{ "type": "string" }, { "type": "integer" }] });
+        assert_eq!(validator.validate(&schema, &Value::Null), Vec::<String>::new());
+        assert_eq!(validator.validate(&schema, &json!(true)).len(), 1);
+
+        let broken = json!({ "type": "string", "pattern": "(" });
+        assert!(validator.validate(&broken, &json!("a"))[0].starts_with("$: the schema cannot be used"));
+    }
+}
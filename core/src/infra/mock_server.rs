@@ -0,0 +1,123 @@
+use crate::domain::{
+    mock::{MockCall, MockHit, MockRouter},
+    repository::{MockHitSink, MockServer},
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hyper::{server::conn::Http, service::service_fn, Body, Request, Response};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Headers of a recorded response that no longer describe the mocked one.
+const DROPPED_HEADERS: [&str; 4] = ["content-length", "transfer-encoding", "connection", "content-encoding"];
+
+/// Serves mocked responses over HTTP/1.1.
+pub struct HyperMockServer;
+
+impl HyperMockServer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HyperMockServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MockServer for HyperMockServer {
+    async fn serve(&self, address: SocketAddr, router: Arc<MockRouter>, sink: Arc<dyn MockHitSink>) -> Result<()> {
+        let listener = TcpListener::bind(address).await.with_context(|| format!("Failed to listen on {}", address))?;
+        sink.listening(listener.local_addr()?);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let (router, sink) = (router.clone(), sink.clone());
+            tokio::spawn(async move {
+                let service = service_fn(move |request| answer(router.clone(), sink.clone(), request));
+                // A client hanging up mid-response only ends its connection
+                let _ = Http::new().http1_only(true).serve_connection(stream, service).await;
+            });
+        }
+    }
+}
+
+async fn answer(router: Arc<MockRouter>, sink: Arc<dyn MockHitSink>, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let headers: HashMap<String, String> = parts
+        .headers
+        .iter()
+        .map(|(k, v)| (k.as_str().to_lowercase(), String::from_utf8_lossy(v.as_bytes()).to_string()))
+        .collect();
+    // An unreadable body can only match routes accepting any body
+    let body = hyper::body::to_bytes(body).await.map(|b| String::from_utf8_lossy(&b).to_string()).unwrap_or_default();
+    let call = MockCall {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().unwrap_or_default().to_string(),
+        headers,
+        body,
+    };
+
+    let reply = router.respond(&call);
+    if !reply.delay.is_zero() {
+        tokio::time::sleep(reply.delay).await;
+    }
+
+    let mut response = Response::builder().status(reply.status);
+    for (k, v) in &reply.headers {
+        if !DROPPED_HEADERS.contains(&k.to_lowercase().as_str()) {
+            response = response.header(k, v);
+        }
+    }
+    let response = response
+        .body(Body::from(reply.body.clone()))
+        .unwrap_or_else(|e| Response::builder().status(500).body(Body::from(format!("Invalid mock response: {}", e))).unwrap_or_default());
+    sink.hit(MockHit { method: call.method, path: call.path, reply });
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::mock::{MockOptions, MockResponse, MockRoute, STATUS_HEADER};
+    use std::time::Duration;
+    use crate::infra::test_server::{listening, reported, ChannelSink};
+
+    #[tokio::test]
+    async fn test_serves_routes() {
+        let response = MockResponse {
+            status: 200,
+            headers: HashMap::from([
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Content-Length".to_string(), "999".to_string()),
+            ]),
+            body: r#"{"id": "{{id}}"}"#.to_string(),
+            delay: Some(Duration::from_millis(20)),
+        };
+        let route = MockRoute { request_id: "get_user".to_string(), method: "GET".to_string(), path: "/users/:id".to_string(), query: None, body: None, response: Some(response) };
+        let router = Arc::new(MockRouter::new(vec![route], MockOptions::default()));
+
+        let (sink, mut events) = ChannelSink::new();
+        tokio::spawn(async move { HyperMockServer::new().serve("127.0.0.1:0".parse().unwrap(), router, sink).await });
+        let base_url = listening(&mut events).await;
+
+        let client = reqwest::Client::new();
+        let response = client.get(format!("{}/users/7", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(response.text().await.unwrap(), r#"{"id": "7"}"#);
+
+        let response = client.get(format!("{}/users/7", base_url)).header(STATUS_HEADER, "503").send().await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(client.get(format!("{}/posts", base_url)).send().await.unwrap().status(), 404);
+
+        let hit = reported(&mut events).await;
+        assert_eq!((hit.method.as_str(), hit.path.as_str()), ("GET", "/users/7"));
+        assert_eq!(hit.reply.request_id.as_deref(), Some("get_user"));
+    }
+}
@@ -3,3 +3,34 @@ pub mod fs_repository;
 pub mod reqwest_client;
 pub mod command_editor;
 pub mod fs_project_repository;
+pub mod fs_environment_repository;
+pub mod digest_auth;
+pub mod fs_token_cache;
+pub mod oauth2_client;
+pub mod sigv4_signer;
+pub mod hmac_signer;
+pub mod datetime;
+pub mod body;
+pub mod fs_schema_repository;
+pub mod websocket_client;
+pub mod proto_parser;
+pub mod grpc_client;
+pub mod fs_proto_repository;
+pub mod fs_history_repository;
+pub mod fs_snapshot_repository;
+pub mod fs_mock_repository;
+pub mod mock_server;
+pub mod proxy_server;
+pub mod fs_har_repository;
+pub mod fs_collection_repository;
+pub mod postman;
+pub mod insomnia;
+pub mod openapi;
+pub mod fs_api_spec_repository;
+pub mod jsonschema_validator;
+pub mod fs_trash_repository;
+pub mod fs_project_watcher;
+pub mod system_clock;
+pub mod regex_matcher;
+#[cfg(test)]
+pub mod test_server;
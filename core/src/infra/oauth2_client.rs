@@ -0,0 +1,295 @@
+use crate::domain::{
+    oauth2::{OAuth2Config, OAuth2Grant, OAuth2Token},
+    repository::{Clock, OAuth2Client},
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// How long to wait for the browser to come back to the loopback listener.
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+/// Opens the authorization URL, normally in the user's browser.
+type UrlOpener = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+pub struct ReqwestOAuth2Client {
+    client: reqwest::Client,
+    clock: Arc<dyn Clock>,
+    open_url: UrlOpener,
+}
+
+impl ReqwestOAuth2Client {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self::with_url_opener(clock, open_in_browser)
+    }
+
+    /// Uses `open_url` instead of the system browser for the authorization code grant.
+    pub fn with_url_opener(clock: Arc<dyn Clock>, open_url: impl Fn(&str) -> Result<()> + Send + Sync + 'static) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            clock,
+            open_url: Box::new(open_url),
+        }
+    }
+
+    async fn request_token(&self, config: &OAuth2Config, mut params: Vec<(&str, String)>) -> Result<OAuth2Token> {
+        if let Some(scope) = &config.scope {
+            params.push(("scope", scope.clone()));
+        }
+
+        // The lifetime counts from the request, so a slow answer errs on the side of renewing early
+        let issued_at = self.clock.now();
+        let mut builder = self.client.post(&config.token_url);
+        builder = match &config.client_secret {
+            Some(secret) => builder.basic_auth(&config.client_id, Some(secret)),
+            None => {
+                params.push(("client_id", config.client_id.clone()));
+                builder
+            }
+        };
+
+        let resp = builder
+            .form(&params)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach token endpoint: {}", config.token_url))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Token endpoint returned {}: {}", status, body);
+        }
+
+        let token: TokenResponse = resp.json().await.context("Invalid token response")?;
+        Ok(OAuth2Token::new(token.access_token, token.refresh_token, token.expires_in, issued_at))
+    }
+
+    /// Runs the authorization code flow with PKCE.
+    /// Returns the code together with the verifier and redirect URI needed to redeem it.
+    async fn authorize(&self, config: &OAuth2Config, auth_url: &str, redirect_port: u16) -> Result<(String, String, String)> {
+        let listener = TcpListener::bind(("127.0.0.1", redirect_port))
+            .await
+            .with_context(|| format!("Failed to listen for the OAuth2 redirect on port {}", redirect_port))?;
+        let redirect_uri = format!("http://127.0.0.1:{}/callback", listener.local_addr()?.port());
+
+        let verifier = random_string(64);
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        let state = random_string(16);
+
+        let mut params = vec![
+            ("response_type", "code".to_string()),
+            ("client_id", config.client_id.clone()),
+            ("redirect_uri", redirect_uri.clone()),
+            ("state", state.clone()),
+            ("code_challenge", challenge),
+            ("code_challenge_method", "S256".to_string()),
+        ];
+        if let Some(scope) = &config.scope {
+            params.push(("scope", scope.clone()));
+        }
+        let url = reqwest::Url::parse_with_params(auth_url, &params)
+            .with_context(|| format!("Invalid oauth2_auth_url: {}", auth_url))?;
+        (self.open_url)(url.as_str())?;
+
+        let code = tokio::time::timeout(AUTHORIZATION_TIMEOUT, wait_for_code(&listener, &state))
+            .await
+            .context("Timed out waiting for the OAuth2 redirect")??;
+        Ok((code, verifier, redirect_uri))
+    }
+}
+
+#[async_trait]
+impl OAuth2Client for ReqwestOAuth2Client {
+    async fn fetch_token(&self, config: &OAuth2Config) -> Result<OAuth2Token> {
+        match &config.grant {
+            OAuth2Grant::ClientCredentials => {
+                self.request_token(config, vec![("grant_type", "client_credentials".to_string())]).await
+            }
+            OAuth2Grant::Password { username, password } => {
+                let params = vec![
+                    ("grant_type", "password".to_string()),
+                    ("username", username.clone()),
+                    ("password", password.clone()),
+                ];
+                self.request_token(config, params).await
+            }
+            OAuth2Grant::RefreshToken { refresh_token } => self.refresh_token(config, refresh_token).await,
+            OAuth2Grant::AuthorizationCode { auth_url, redirect_port } => {
+                let (code, verifier, redirect_uri) = self.authorize(config, auth_url, *redirect_port).await?;
+                let params = vec![
+                    ("grant_type", "authorization_code".to_string()),
+                    ("code", code),
+                    ("redirect_uri", redirect_uri),
+                    ("code_verifier", verifier),
+                ];
+                self.request_token(config, params).await
+            }
+        }
+    }
+
+    async fn refresh_token(&self, config: &OAuth2Config, refresh_token: &str) -> Result<OAuth2Token> {
+        let params = vec![
+            ("grant_type", "refresh_token".to_string()),
+            ("refresh_token", refresh_token.to_string()),
+        ];
+        let mut token = self.request_token(config, params).await?;
+        // Servers may omit the refresh token when it stays valid
+        if token.refresh_token.is_none() {
+            token.refresh_token = Some(refresh_token.to_string());
+        }
+        Ok(token)
+    }
+}
+
+/// Accepts redirects on the loopback listener until one carries the authorization code.
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = vec![0u8; 8192];
+        let n = stream.read(&mut buf).await?;
+        let head = String::from_utf8_lossy(&buf[..n]);
+        let target = head.split_whitespace().nth(1).unwrap_or("/");
+        let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", target))?;
+        let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.to_string());
+
+        let (code, error) = (param("code"), param("error"));
+        if code.is_none() && error.is_none() {
+            // e.g. the browser asking for /favicon.ico
+            stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+            continue;
+        }
+
+        let page = "<html><body>htup: authorization complete, you can close this tab.</body></html>";
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            page.len(),
+            page
+        );
+        stream.write_all(reply.as_bytes()).await?;
+
+        if let Some(error) = error {
+            anyhow::bail!("Authorization failed: {}", error);
+        }
+        if param("state").as_deref() != Some(state) {
+            anyhow::bail!("Authorization failed: state mismatch");
+        }
+        return code.context("Missing authorization code");
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(len).map(char::from).collect()
+}
+
+fn open_in_browser(url: &str) -> Result<()> {
+    let (program, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+        ("open", vec![url])
+    } else if cfg!(windows) {
+        ("cmd", vec!["/C", "start", "", url])
+    } else {
+        ("xdg-open", vec![url])
+    };
+    std::process::Command::new(program)
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to open browser, visit {} manually", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::MockClock;
+    use crate::infra::test_server::{response, serve};
+    use std::time::UNIX_EPOCH;
+
+    const TOKEN_JSON: &str = r#"{"access_token":"abc","token_type":"Bearer","expires_in":3600,"refresh_token":"r1"}"#;
+
+    fn clock() -> Arc<dyn Clock> {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH + Duration::from_secs(1_000));
+        Arc::new(clock)
+    }
+
+    fn config(token_url: String, grant: OAuth2Grant) -> OAuth2Config {
+        OAuth2Config {
+            grant,
+            token_url,
+            client_id: "client".to_string(),
+            client_secret: Some("secret".to_string()),
+            scope: Some("read".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials() {
+        let (base_url, server) = serve(vec![response("200 OK", &[("Content-Type", "application/json")], TOKEN_JSON)]).await;
+
+        let client = ReqwestOAuth2Client::new(clock());
+        let token = client
+            .fetch_token(&config(format!("{}/token", base_url), OAuth2Grant::ClientCredentials))
+            .await
+            .unwrap();
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+        assert_eq!(token.expires_at, Some(4_600));
+        assert!(!token.is_expired(UNIX_EPOCH + Duration::from_secs(1_000)));
+
+        let received = server.await.unwrap();
+        assert!(received[0].starts_with("POST /token"));
+        assert!(received[0].contains("grant_type=client_credentials"));
+        assert!(received[0].contains("scope=read"));
+        // client_secret_basic: base64("client:secret")
+        assert!(received[0].contains("Basic Y2xpZW50OnNlY3JldA=="));
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_with_pkce() {
+        let (base_url, server) = serve(vec![response("200 OK", &[("Content-Type", "application/json")], TOKEN_JSON)]).await;
+
+        // Stand in for the browser: follow the redirect the authorization server would issue
+        let (url_tx, url_rx) = std::sync::mpsc::channel::<String>();
+        let client = ReqwestOAuth2Client::with_url_opener(clock(), move |url| {
+            let url = reqwest::Url::parse(url)?;
+            let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.to_string()).unwrap();
+            let redirect = format!("{}?code=xyz&state={}", param("redirect_uri"), param("state"));
+            url_tx.send(param("code_challenge"))?;
+            tokio::spawn(async move { reqwest::get(redirect).await });
+            Ok(())
+        });
+
+        let grant = OAuth2Grant::AuthorizationCode {
+            auth_url: "https://auth.example.com/authorize".to_string(),
+            redirect_port: 0,
+        };
+        let token = client.fetch_token(&config(format!("{}/token", base_url), grant)).await.unwrap();
+        assert_eq!(token.access_token, "abc");
+
+        let received = server.await.unwrap();
+        let body = received[0].split("\r\n\r\n").nth(1).unwrap();
+        let verifier = reqwest::Url::parse(&format!("http://x/?{}", body))
+            .unwrap()
+            .query_pairs()
+            .find(|(k, _)| k == "code_verifier")
+            .map(|(_, v)| v.to_string())
+            .unwrap();
+        assert!(body.contains("grant_type=authorization_code"));
+        assert!(body.contains("code=xyz"));
+        assert_eq!(url_rx.recv().unwrap(), URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::domain::{
+    auth::ApiKeyLocation,
+    collection::file_name,
+    openapi::{ApiResponse, ApiSpec, MediaType, Operation, Parameter, ParameterLocation, SecurityScheme},
+};
+use crate::infra::fs_collection_repository::text;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Whether a document is an OpenAPI 3 or Swagger 2 definition.
+pub fn is_openapi(value: &Value) -> bool {
+    value["openapi"].is_string() || value["swagger"].is_string()
+}
+
+pub fn parse(root: &Value) -> Result<ApiSpec> {
+    let swagger = match (root["openapi"].as_str(), root["swagger"].as_str()) {
+        (Some(version), _) if version.starts_with("3.") => false,
+        (_, Some("2.0")) => true,
+        (version, swagger) => anyhow::bail!("Unsupported OpenAPI version {}", version.or(swagger).unwrap_or_default()),
+    };
+    let mut spec = ApiSpec {
+        title: text(&root["info"]["title"]),
+        base_url: if swagger { swagger_base_url(root) } else { server_url(&root["servers"][0]) },
+        operations: Vec::new(),
+    };
+
+    let mut ids = HashSet::new();
+    for (path, item) in root["paths"].as_object().into_iter().flatten() {
+        let item = resolve(item, root, &mut Vec::new());
+        let item = &item;
+        for method in METHODS {
+            let Some(operation) = item.get(method) else { continue };
+            let mut parameters = Vec::new();
+            for parameter in item["parameters"].as_array().into_iter().flatten().chain(operation["parameters"].as_array().into_iter().flatten()) {
+                // Operation parameters override the path ones of the same name and location
+                parameters.retain(|p: &Value| p["name"] != parameter["name"] || p["in"] != parameter["in"]);
+                parameters.push(parameter.clone());
+            }
+
+            let mut operation = Operation {
+                id: unique(operation_id(operation, method, path), &mut ids),
+                method: method.to_uppercase(),
+                path: path.clone(),
+                parameters: Vec::new(),
+                request_body: None,
+                security: security(root, operation, swagger),
+                responses: Vec::new(),
+            };
+            if swagger {
+                swagger_parameters(root, &mut operation, &parameters, &item[method]);
+                operation.responses = swagger_responses(root, &item[method]);
+            } else {
+                operation.parameters = parameters.iter().filter_map(parameter).collect();
+                operation.request_body = media_type(&item[method]["requestBody"]["content"]);
+                operation.responses = responses(&item[method]["responses"], |response| {
+                    response["content"].as_object().into_iter().flatten().map(|(content_type, media)| MediaType {
+                        content_type: content_type.clone(),
+                        schema: media["schema"].clone(),
+                        example: example(media),
+                    }).collect()
+                });
+            }
+            spec.operations.push(operation);
+        }
+    }
+    Ok(spec)
+}
+
+/// Replaces every local `$ref` with its target. A reference to a schema being expanded
+/// (a recursive type) becomes an empty schema, and so does an external one.
+fn resolve(value: &Value, root: &Value, stack: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(fields) => {
+            if let Some(reference) = fields.get("$ref").and_then(Value::as_str) {
+                let target = reference.strip_prefix('#').and_then(|pointer| root.pointer(pointer));
+                return match target {
+                    Some(target) if !stack.iter().any(|r| r == reference) => {
+                        stack.push(reference.to_string());
+                        let resolved = resolve(target, root, stack);
+                        stack.pop();
+                        resolved
+                    }
+                    _ => json!({}),
+                };
+            }
+            Value::Object(fields.iter().map(|(k, v)| (k.clone(), resolve(v, root, stack))).collect())
+        }
+        Value::Array(values) => Value::Array(values.iter().map(|v| resolve(v, root, stack)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// `getUserById` becomes `get_user_by_id`; without an id, `GET /users/{id}` becomes `get_users_id`.
+fn operation_id(operation: &Value, method: &str, path: &str) -> String {
+    match operation["operationId"].as_str() {
+        Some(id) => {
+            let mut words = String::new();
+            let mut previous_lower = false;
+            for c in id.chars() {
+                if c.is_uppercase() && previous_lower {
+                    words.push('_');
+                }
+                previous_lower = c.is_lowercase() || c.is_ascii_digit();
+                words.push(c);
+            }
+            file_name(&words)
+        }
+        None => file_name(&format!("{} {}", method, path)),
+    }
+}
+
+/// Ids are assigned in document order, so a re-import gives every operation the same id again.
+fn unique(id: String, ids: &mut HashSet<String>) -> String {
+    let id = if ids.contains(&id) { (2..).map(|n| format!("{}_{}", id, n)).find(|c| !ids.contains(c)).unwrap_or(id) } else { id };
+    ids.insert(id.clone());
+    id
+}
+
+fn server_url(server: &Value) -> Option<String> {
+    let mut url = server["url"].as_str()?.to_string();
+    for (name, variable) in server["variables"].as_object().into_iter().flatten() {
+        url = url.replace(&format!("{{{}}}", name), &text(&variable["default"]));
+    }
+    Some(url.trim_end_matches('/').to_string())
+}
+
+fn swagger_base_url(root: &Value) -> Option<String> {
+    let base_path = text(&root["basePath"]);
+    let base_path = base_path.trim_end_matches('/');
+    match root["host"].as_str() {
+        Some(host) => {
+            let scheme = root["schemes"][0].as_str().unwrap_or("https");
+            Some(format!("{}://{}{}", scheme, host, base_path))
+        }
+        None if !base_path.is_empty() => Some(base_path.to_string()),
+        None => None,
+    }
+}
+
+fn location(value: &Value) -> Option<ParameterLocation> {
+    match value.as_str()? {
+        "path" => Some(ParameterLocation::Path),
+        "query" => Some(ParameterLocation::Query),
+        "header" => Some(ParameterLocation::Header),
+        "cookie" => Some(ParameterLocation::Cookie),
+        _ => None,
+    }
+}
+
+fn parameter(value: &Value) -> Option<Parameter> {
+    let location = location(&value["in"])?;
+    Some(Parameter {
+        name: text(&value["name"]),
+        location,
+        required: location == ParameterLocation::Path || value["required"] == Value::Bool(true),
+        schema: value["schema"].clone(),
+        example: example(value),
+    })
+}
+
+/// The `example`, or the first of the named `examples`.
+fn example(value: &Value) -> Option<Value> {
+    value.get("example").cloned().or_else(|| value["examples"].as_object()?.values().next()?.get("value").cloned())
+}
+
+/// Picks the JSON content of a `content` map when there is one, the first otherwise.
+fn media_type(content: &Value) -> Option<MediaType> {
+    let content = content.as_object()?;
+    let (content_type, media) = content.iter().find(|(k, _)| k.contains("json")).or_else(|| content.iter().next())?;
+    Some(MediaType { content_type: content_type.clone(), schema: media["schema"].clone(), example: example(media) })
+}
+
+/// The declared responses, their content read by `content`. Headers are parameters
+/// whose name is their key; Swagger 2 puts the schema in the header itself.
+fn responses(value: &Value, content: impl Fn(&Value) -> Vec<MediaType>) -> Vec<ApiResponse> {
+    let mut responses = Vec::new();
+    for (status, response) in value.as_object().into_iter().flatten() {
+        let headers = response["headers"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, header)| Parameter {
+                name: name.clone(),
+                location: ParameterLocation::Header,
+                required: header["required"] == Value::Bool(true),
+                schema: if header.get("schema").is_some() { header["schema"].clone() } else { header.clone() },
+                example: example(header),
+            })
+            .collect();
+        responses.push(ApiResponse { status: status.clone(), headers, content: content(response) });
+    }
+    responses
+}
+
+fn swagger_responses(root: &Value, operation: &Value) -> Vec<ApiResponse> {
+    let produces: Vec<&str> = operation["produces"]
+        .as_array()
+        .or_else(|| root["produces"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    responses(&operation["responses"], |response| match response.get("schema") {
+        Some(schema) if produces.is_empty() => {
+            vec![MediaType { content_type: "application/json".to_string(), schema: schema.clone(), example: None }]
+        }
+        Some(schema) => produces
+            .iter()
+            .map(|content_type| MediaType { content_type: content_type.to_string(), schema: schema.clone(), example: None })
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+/// Swagger 2 declares bodies as `body` and `formData` parameters, and other parameters without a `schema`.
+fn swagger_parameters(root: &Value, operation: &mut Operation, parameters: &[Value], source: &Value) {
+    let consumes: Vec<&str> = source["consumes"]
+        .as_array()
+        .or_else(|| root["consumes"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let mut form = Map::new();
+    for value in parameters {
+        match value["in"].as_str() {
+            Some("body") => {
+                let content_type = consumes.iter().find(|c| c.contains("json")).or(consumes.first()).unwrap_or(&"application/json");
+                operation.request_body = Some(MediaType { content_type: content_type.to_string(), schema: value["schema"].clone(), example: None });
+            }
+            Some("formData") => {
+                form.insert(text(&value["name"]), value.clone());
+            }
+            _ => {
+                if let Some(mut parameter) = parameter(value) {
+                    parameter.schema = value.clone();
+                    operation.parameters.push(parameter);
+                }
+            }
+        }
+    }
+    if !form.is_empty() {
+        let content_type = if consumes.contains(&"multipart/form-data") { "multipart/form-data" } else { "application/x-www-form-urlencoded" };
+        operation.request_body = Some(MediaType {
+            content_type: content_type.to_string(),
+            schema: json!({ "type": "object", "properties": form }),
+            example: None,
+        });
+    }
+}
+
+/// The first scheme of the first requirement; `security: []` on an operation turns it off.
+fn security(root: &Value, operation: &Value, swagger: bool) -> Option<SecurityScheme> {
+    let requirements = operation.get("security").unwrap_or(&root["security"]);
+    let name = requirements.as_array()?.first()?.as_object()?.keys().next()?;
+    let schemes = if swagger { &root["securityDefinitions"] } else { &root["components"]["securitySchemes"] };
+    let scheme = resolve(&schemes[name.as_str()], root, &mut Vec::new());
+    match (scheme["type"].as_str()?, scheme["scheme"].as_str().map(str::to_lowercase).as_deref()) {
+        ("basic", _) | ("http", Some("basic")) => Some(SecurityScheme::Basic),
+        ("http", Some("bearer")) | ("oauth2", _) | ("openIdConnect", _) => Some(SecurityScheme::Bearer),
+        ("apiKey", _) => {
+            let location = match scheme["in"].as_str()? {
+                "header" => ApiKeyLocation::Header,
+                "query" => ApiKeyLocation::Query,
+                _ => return None,
+            };
+            Some(SecurityScheme::ApiKey { location, name: text(&scheme["name"]) })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPENAPI: &str = r##"{
+      "openapi": "3.0.3",
+      "info": { "title": "Shop", "version": "1" },
+      "servers": [{ "url": "https://{region}.shop.com/v1/", "variables": { "region": { "default": "eu" } } }],
+      "security": [{ "bearer": [] }],
+      "paths": {
+        "/users/{id}": {
+          "parameters": [{ "$ref": "#/components/parameters/Id" }],
+          "get": {
+            "operationId": "getUserById",
+            "parameters": [{ "name": "expand", "in": "query", "schema": { "type": "boolean", "default": false } }],
+            "responses": {
+              "200": {
+                "headers": { "X-Rate-Limit": { "required": true, "schema": { "type": "integer" } } },
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } }
+              },
+              "404": { "description": "Not found" }
+            }
+          },
+          "put": {
+            "security": [],
+            "requestBody": { "content": { "application/json": { "schema": { "$ref": "#/components/schemas/User" } } } }
+          }
+        }
+      },
+      "components": {
+        "parameters": { "Id": { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" }, "example": 7 } },
+        "schemas": { "User": { "type": "object", "properties": { "name": { "type": "string" }, "manager": { "$ref": "#/components/schemas/User" } } } },
+        "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } }
+      }
+    }"##;
+
+    #[test]
+    fn test_parse_openapi() {
+        let spec = parse(&serde_json::from_str(OPENAPI).unwrap()).unwrap();
+        assert_eq!(spec.title, "Shop");
+        assert_eq!(spec.base_url.as_deref(), Some("https://eu.shop.com/v1"));
+
+        let [get, put] = spec.operations.as_slice() else { panic!("expected two operations") };
+        assert_eq!((get.id.as_str(), get.method.as_str()), ("get_user_by_id", "GET"));
+        assert_eq!(get.parameters.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["id", "expand"]);
+        assert_eq!(get.security, Some(SecurityScheme::Bearer));
+        assert_eq!(get.to_request().url, "{{baseUrl}}/users/{{id}}?expand={{expand}}");
+        let [ok, not_found] = get.responses.as_slice() else { panic!("expected two responses") };
+        assert_eq!((ok.status.as_str(), not_found.status.as_str()), ("200", "404"));
+        assert_eq!((ok.headers[0].name.as_str(), ok.headers[0].required), ("X-Rate-Limit", true));
+        assert_eq!(ok.content[0].schema["properties"]["name"], json!({ "type": "string" }));
+        assert!(not_found.content.is_empty());
+
+        assert_eq!(put.id, "put_users_id");
+        assert_eq!(put.security, None);
+        let body = put.request_body.as_ref().unwrap();
+        assert_eq!(body.schema["properties"]["name"], json!({ "type": "string" }));
+        // The recursive reference is cut instead of expanded forever
+        assert_eq!(body.schema["properties"]["manager"], json!({}));
+    }
+
+    #[test]
+    fn test_parse_swagger() {
+        let swagger = r##"{
+          "swagger": "2.0",
+          "info": { "title": "Pets" },
+          "host": "petstore.io",
+          "basePath": "/api",
+          "schemes": ["http"],
+          "securityDefinitions": { "key": { "type": "apiKey", "in": "header", "name": "X-Api-Key" } },
+          "paths": {
+            "/pets": {
+              "post": {
+                "security": [{ "key": [] }],
+                "parameters": [
+                  { "name": "body", "in": "body", "schema": { "$ref": "#/definitions/Pet" } },
+                  { "name": "X-Trace", "in": "header", "type": "string", "required": true }
+                ],
+                "produces": ["application/json", "application/xml"],
+                "responses": { "201": { "schema": { "$ref": "#/definitions/Pet" }, "headers": { "Location": { "type": "string" } } } }
+              },
+              "put": {
+                "consumes": ["application/x-www-form-urlencoded"],
+                "parameters": [{ "name": "name", "in": "formData", "type": "string", "default": "Rex" }]
+              }
+            }
+          },
+          "definitions": { "Pet": { "type": "object", "properties": { "name": { "type": "string" } } } }
+        }"##;
+        let spec = parse(&serde_json::from_str(swagger).unwrap()).unwrap();
+        assert_eq!(spec.base_url.as_deref(), Some("http://petstore.io/api"));
+
+        let post = spec.operations.iter().find(|o| o.method == "POST").unwrap();
+        assert_eq!(post.security, Some(SecurityScheme::ApiKey { location: ApiKeyLocation::Header, name: "X-Api-Key".to_string() }));
+        assert_eq!(post.request_body.as_ref().unwrap().content_type, "application/json");
+        assert_eq!(post.parameters[0].location, ParameterLocation::Header);
+        let created = &post.responses[0];
+        assert_eq!(created.content.iter().map(|m| m.content_type.as_str()).collect::<Vec<_>>(), vec!["application/json", "application/xml"]);
+        assert_eq!(created.headers[0].schema["type"], "string");
+
+        let put = spec.operations.iter().find(|o| o.method == "PUT").unwrap();
+        let request = put.to_request();
+        assert_eq!(request.body, Some(crate::domain::body::Body::Form(vec![("name".to_string(), "Rex".to_string())])));
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        assert!(parse(&json!({ "swagger": "1.2" })).is_err());
+        assert!(parse(&json!({ "openapi": "4.0.0" })).is_err());
+    }
+}
@@ -1,11 +1,29 @@
-use crate::domain::request::Request;
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    body::{Body, Part, PartContent},
+    request::Request,
+    websocket::MESSAGE_SEPARATOR,
+};
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 
 pub fn parse_http_file(content: &str) -> Result<Request> {
     let mut lines = content.lines();
+    let mut directives = Vec::new();
 
-    // 1. Parse Method and URL
-    let first_line = lines.next().context("Empty file")?;
+    // 1. Parse Method and URL (skipping leading comments and directives)
+    let first_line = loop {
+        let line = lines.next().context("Empty file")?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(comment) = comment_text(trimmed) {
+            directives.extend(parse_directive(comment));
+            continue;
+        }
+        break line;
+    };
     let mut parts = first_line.split_whitespace();
     let method = parts.next().context("Missing method")?;
     let url = parts.next().context("Missing URL")?;
@@ -27,6 +45,11 @@ pub fn parse_http_file(content: &str) -> Result<Request> {
             continue;
         }
 
+        if let Some(comment) = comment_text(line.trim()) {
+            directives.extend(parse_directive(comment));
+            continue;
+        }
+
         if let Some((key, value)) = line.split_once(':') {
             request.headers.insert(key.trim().to_string(), value.trim().to_string());
         }
@@ -34,12 +57,422 @@ pub fn parse_http_file(content: &str) -> Result<Request> {
 
     // 3. Set Body
     if !body_lines.is_empty() {
-        request.body = Some(body_lines.join("\n"));
+        let content_type = request
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.as_str());
+        request.body = Some(parse_body(&body_lines, content_type)?);
+    }
+
+    // 4. Apply Directives
+    // REST Client marks GraphQL requests with a header instead of a directive
+    let graphql_header = request.headers.keys().find(|k| k.eq_ignore_ascii_case("x-request-type")).cloned();
+    let mut is_graphql = match graphql_header {
+        Some(key) if request.headers[&key].eq_ignore_ascii_case("graphql") => {
+            request.headers.remove(&key);
+            true
+        }
+        _ => false,
+    };
+
+    for (name, args) in directives {
+        match name {
+            "auth" => request.auth = Some(parse_auth(args)?),
+            "graphql" => is_graphql = true,
+            "sign" if !args.is_empty() => request.signer = Some(args.to_string()),
+            "sign" => anyhow::bail!("@sign expects a signer name"),
+            "stream" => request.stream = true,
+            "diff-ignore" => request.diff_ignore.extend(parse_paths(args)),
+            "snapshot-redact" => request.snapshot_redact.extend(parse_paths(args)),
+            _ => {} // Unknown directives are plain comments
+        }
+    }
+
+    if request.is_websocket() {
+        if let Some(Body::Text(text)) = &request.body {
+            request.body = Some(parse_messages(text));
+        }
+    }
+
+    if is_graphql {
+        request.body = match request.body.take() {
+            Some(Body::Text(text)) => Some(parse_graphql(&text)?),
+            None => anyhow::bail!("GraphQL request has no query"),
+            Some(_) => anyhow::bail!("GraphQL requests need a plain query body"),
+        };
     }
 
     Ok(request)
 }
 
+/// Parses the body according to its content type.
+fn parse_body(lines: &[&str], content_type: Option<&str>) -> Result<Body> {
+    let original = content_type.unwrap_or("");
+    let content_type = original.to_lowercase();
+
+    if content_type.starts_with("multipart/form-data") {
+        // The boundary is case sensitive, so take it from the original value
+        let boundary = original
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("boundary="))
+            .map(|b| b.trim_matches('"'))
+            .context("multipart/form-data requires a boundary")?;
+        return parse_multipart(lines, boundary);
+    }
+
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        // Fields are separated by lines or `&`, and decoded so they are encoded once when sent
+        let fields = lines
+            .iter()
+            .flat_map(|line| line.split('&'))
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .filter_map(|field| url::form_urlencoded::parse(field.as_bytes()).next())
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        return Ok(Body::Form(fields));
+    }
+
+    let text = lines.join("\n");
+    match file_reference(text.trim()) {
+        Some(path) => Ok(Body::File(path.into())),
+        None => Ok(Body::Text(text)),
+    }
+}
+
+/// Splits the body of a WebSocket request into its predefined messages.
+fn parse_messages(text: &str) -> Body {
+    let mut messages = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if line.trim() == MESSAGE_SEPARATOR {
+            messages.push(current.join("\n"));
+            current.clear();
+        } else {
+            current.push(line);
+        }
+    }
+    messages.push(current.join("\n"));
+    Body::Messages(messages.into_iter().map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).collect())
+}
+
+/// Splits a GraphQL body into the query and an optional trailing JSON variables block.
+fn parse_graphql(text: &str) -> Result<Body> {
+    let lines: Vec<&str> = text.lines().collect();
+    let split = lines.iter().rposition(|l| l.trim().is_empty());
+
+    if let Some(split) = split {
+        let query = lines[..split].join("\n");
+        let variables = lines[split + 1..].join("\n");
+        // A selection set also starts with '{', so only a JSON object counts as variables
+        let is_json_object = serde_json::from_str::<serde_json::Value>(&variables).is_ok_and(|v| v.is_object());
+        if is_json_object && !query.trim().is_empty() {
+            return Ok(Body::GraphQL {
+                query: query.trim_end().to_string(),
+                variables: Some(variables.trim().to_string()),
+            });
+        }
+    }
+
+    Ok(Body::GraphQL { query: text.trim_end().to_string(), variables: None })
+}
+
+/// Parses the parts between `--boundary` delimiter lines.
+fn parse_multipart(lines: &[&str], boundary: &str) -> Result<Body> {
+    let delimiter = format!("--{}", boundary);
+    let closing = format!("--{}--", boundary);
+
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    for line in lines {
+        let trimmed = line.trim_end();
+        if trimmed == closing {
+            break;
+        }
+        if trimmed == delimiter {
+            sections.push(Vec::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+
+    let mut parts = Vec::new();
+    for section in sections {
+        let split = section.iter().position(|l| l.trim().is_empty()).unwrap_or(section.len());
+        let (headers, content) = section.split_at(split);
+        let content = content.iter().skip(1).copied().collect::<Vec<_>>().join("\n");
+
+        let mut name = None;
+        let mut filename = None;
+        let mut content_type = None;
+        for header in headers {
+            let Some((key, value)) = header.split_once(':') else { continue };
+            if key.trim().eq_ignore_ascii_case("content-disposition") {
+                for param in value.split(';').skip(1) {
+                    if let Some((k, v)) = param.split_once('=') {
+                        let v = v.trim().trim_matches('"').to_string();
+                        match k.trim() {
+                            "name" => name = Some(v),
+                            "filename" => filename = Some(v),
+                            _ => {}
+                        }
+                    }
+                }
+            } else if key.trim().eq_ignore_ascii_case("content-type") {
+                content_type = Some(value.trim().to_string());
+            }
+        }
+
+        let content = match file_reference(content.trim()) {
+            Some(path) => PartContent::File(path.into()),
+            None => PartContent::Text(content.trim_end_matches('\n').to_string()),
+        };
+        parts.push(Part {
+            name: name.context("Multipart part is missing a name")?,
+            filename,
+            content_type,
+            content,
+        });
+    }
+
+    Ok(Body::Multipart { boundary: boundary.to_string(), parts })
+}
+
+/// Returns the path of a `< ./path` file reference.
+fn file_reference(text: &str) -> Option<&str> {
+    if text.contains('\n') {
+        return None;
+    }
+    text.strip_prefix("< ").map(str::trim).filter(|path| !path.is_empty())
+}
+
+/// Percent-encodes what `parse_body` would otherwise read as separators, keeping the rest readable.
+fn escape_form(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' | '&' | '+' | '\r' | '\n' => escaped.push_str(&format!("%{:02X}", c as u8)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats a request as a `.http` file, the inverse of `parse_http_file`.
+pub fn format_http_file(request: &Request) -> String {
+    let mut content = String::new();
+    if let Some(auth) = &request.auth {
+        content.push_str(&format_auth(auth));
+        content.push('\n');
+    }
+    if let Some(Body::GraphQL { .. }) = &request.body {
+        content.push_str("# @graphql\n");
+    }
+    if let Some(signer) = &request.signer {
+        content.push_str(&format!("# @sign {}\n", signer));
+    }
+    if request.stream {
+        content.push_str("# @stream\n");
+    }
+    if !request.diff_ignore.is_empty() {
+        content.push_str(&format!("# @diff-ignore {}\n", request.diff_ignore.join(" ")));
+    }
+    if !request.snapshot_redact.is_empty() {
+        content.push_str(&format!("# @snapshot-redact {}\n", request.snapshot_redact.join(" ")));
+    }
+    content.push_str(&format!("{} {}\n", request.method, request.url));
+    for (key, value) in &request.headers {
+        content.push_str(&format!("{}: {}\n", key, value));
+    }
+    content.push('\n');
+    if let Some(body) = &request.body {
+        content.push_str(&format_body(body));
+    }
+    content
+}
+
+/// Formats a body as written in a `.http` file, the inverse of `parse_body`.
+pub fn format_body(body: &Body) -> String {
+    match body {
+        Body::Text(text) => text.clone(),
+        Body::File(path) => format!("< {}", path.display()),
+        Body::Form(fields) => fields
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape_form(k).replace('=', "%3D"), escape_form(v)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Body::Multipart { boundary, parts } => {
+            let mut content = String::new();
+            for part in parts {
+                content.push_str(&format!("--{}\n", boundary));
+                content.push_str(&format!("Content-Disposition: form-data; name=\"{}\"", part.name));
+                if let Some(filename) = &part.filename {
+                    content.push_str(&format!("; filename=\"{}\"", filename));
+                }
+                content.push('\n');
+                if let Some(content_type) = &part.content_type {
+                    content.push_str(&format!("Content-Type: {}\n", content_type));
+                }
+                content.push('\n');
+                match &part.content {
+                    PartContent::Text(text) => content.push_str(text),
+                    PartContent::File(path) => content.push_str(&format!("< {}", path.display())),
+                }
+                content.push('\n');
+            }
+            content.push_str(&format!("--{}--", boundary));
+            content
+        }
+        Body::Messages(messages) => messages.join(&format!("\n{}\n", MESSAGE_SEPARATOR)),
+        Body::GraphQL { query, variables } => match variables {
+            Some(variables) => format!("{}\n\n{}", query, variables),
+            None => query.clone(),
+        },
+    }
+}
+
+/// Returns the text of a `#` or `//` comment line.
+fn comment_text(line: &str) -> Option<&str> {
+    line.strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))
+        .map(str::trim)
+}
+
+/// Splits a `@name args` directive found in a comment.
+fn parse_directive(comment: &str) -> Option<(&str, &str)> {
+    let directive = comment.strip_prefix('@')?;
+    match directive.split_once(char::is_whitespace) {
+        Some((name, args)) => Some((name, args.trim())),
+        None => Some((directive, "")),
+    }
+}
+
+/// Splits the field paths of `@diff-ignore` and `@snapshot-redact` (comma or space separated).
+fn parse_paths(args: &str) -> impl Iterator<Item = String> + '_ {
+    args.split(|c: char| c == ',' || c.is_whitespace()).filter(|p| !p.is_empty()).map(str::to_string)
+}
+
+/// Parses the arguments of an `@auth` directive.
+fn parse_auth(args: &str) -> Result<Auth> {
+    let (scheme, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let rest = rest.trim();
+    let user_and_password = || -> Result<(String, String)> {
+        let (user, password) = rest.split_once(char::is_whitespace).context("@auth expects a user and a password")?;
+        Ok((user.to_string(), password.trim().to_string()))
+    };
+
+    let auth = match scheme.to_lowercase().as_str() {
+        "basic" => {
+            let (username, password) = user_and_password()?;
+            Auth::Basic { username, password }
+        }
+        "digest" => {
+            let (username, password) = user_and_password()?;
+            Auth::Digest { username, password }
+        }
+        "bearer" => {
+            if rest.is_empty() {
+                anyhow::bail!("@auth bearer expects a token");
+            }
+            Auth::Bearer { token: rest.to_string() }
+        }
+        "apikey" => {
+            let mut parts = rest.splitn(3, char::is_whitespace);
+            let location = match parts.next().map(str::to_lowercase).as_deref() {
+                Some("header") => ApiKeyLocation::Header,
+                Some("query") => ApiKeyLocation::Query,
+                _ => anyhow::bail!("@auth apikey expects 'header' or 'query'"),
+            };
+            let name = parts.next().context("@auth apikey expects a name")?;
+            let value = parts.next().context("@auth apikey expects a value")?;
+            Auth::ApiKey { location, name: name.to_string(), value: value.trim().to_string() }
+        }
+        other => anyhow::bail!("Unknown @auth scheme: {}", other),
+    };
+    Ok(auth)
+}
+
+/// Formats an `@auth` directive line, the inverse of `parse_auth`.
+pub fn format_auth(auth: &Auth) -> String {
+    match auth {
+        Auth::Basic { username, password } => format!("# @auth basic {} {}", username, password),
+        Auth::Bearer { token } => format!("# @auth bearer {}", token),
+        Auth::Digest { username, password } => format!("# @auth digest {} {}", username, password),
+        Auth::ApiKey { location, name, value } => {
+            let location = match location {
+                ApiKeyLocation::Header => "header",
+                ApiKeyLocation::Query => "query",
+            };
+            format!("# @auth apikey {} {} {}", location, name, value)
+        }
+    }
+}
+
+/// Parses a dotenv style file (`KEY=value` lines, `#` comments, optional quotes).
+/// Double-quoted values may escape `\"`, `\\`, `\n`, `\r` and `\t`; single-quoted ones are literal.
+pub fn parse_env_file(content: &str) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                Some(quoted) => unescape_env_value(quoted),
+                None => value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')).unwrap_or(value).to_string(),
+            };
+            variables.insert(key.trim().to_string(), value);
+        }
+    }
+
+    variables
+}
+
+/// Formats a value for a dotenv file: double-quoted, so that surrounding spaces and `#` survive,
+/// with quotes, backslashes and line breaks escaped. The inverse of `parse_env_file`.
+pub fn format_env_value(value: &str) -> String {
+    let mut formatted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => formatted.push_str("\\\""),
+            '\\' => formatted.push_str("\\\\"),
+            '\n' => formatted.push_str("\\n"),
+            '\r' => formatted.push_str("\\r"),
+            '\t' => formatted.push_str("\\t"),
+            c => formatted.push(c),
+        }
+    }
+    formatted.push('"');
+    formatted
+}
+
+/// Unknown escapes keep their backslash, e.g. in a Windows path.
+fn unescape_env_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let escaped = match (c, chars.peek()) {
+            ('\\', Some('"')) => '"',
+            ('\\', Some('\\')) => '\\',
+            ('\\', Some('n')) => '\n',
+            ('\\', Some('r')) => '\r',
+            ('\\', Some('t')) => '\t',
+            _ => {
+                unescaped.push(c);
+                continue;
+            }
+        };
+        chars.next();
+        unescaped.push(escaped);
+    }
+    unescaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +498,176 @@ Authorization: Bearer token
         let req = parse_http_file(content).unwrap();
         assert_eq!(req.method, "POST");
         assert_eq!(req.headers.get("Content-Type").unwrap(), "application/json");
-        assert_eq!(req.body.unwrap(), "{\n    \"name\": \"foo\"\n}");
+        assert_eq!(req.body.unwrap(), Body::Text("{\n    \"name\": \"foo\"\n}".to_string()));
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = "# comment\nhost=api.example.com\nexport token=\"abc def\"\n\nempty=";
+        let vars = parse_env_file(content);
+        assert_eq!(vars.get("host").unwrap(), "api.example.com");
+        assert_eq!(vars.get("token").unwrap(), "abc def");
+        assert_eq!(vars.get("empty").unwrap(), "");
+        assert_eq!(vars.len(), 3);
+    }
+
+    #[test]
+    fn test_env_value_round_trip() {
+        let value = "say \"hi\"\n\tC:\\dir # not a comment";
+        assert_eq!(format_env_value(value), r#""say \"hi\"\n\tC:\\dir # not a comment""#);
+        assert_eq!(parse_env_file(&format!("v={}", format_env_value(value)))["v"], value);
+        // Unknown escapes and single quotes are kept as written
+        let vars = parse_env_file("path=\"C:\\dir\"\nraw='a\\nb'");
+        assert_eq!((vars["path"].as_str(), vars["raw"].as_str()), ("C:\\dir", "a\\nb"));
+    }
+
+    #[test]
+    fn test_parse_auth_directives() {
+        let content = "# @auth bearer {{token}}\nGET https://api.com/me";
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.auth, Some(Auth::Bearer { token: "{{token}}".to_string() }));
+
+        let content = "GET https://api.com/me\n# @auth digest Mufasa Circle Of Life\nAccept: */*";
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(req.headers.len(), 1);
+        assert_eq!(
+            req.auth,
+            Some(Auth::Digest { username: "Mufasa".to_string(), password: "Circle Of Life".to_string() })
+        );
+
+        let content = "// @auth apikey query api_key abc\nGET https://api.com/me";
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(
+            req.auth,
+            Some(Auth::ApiKey { location: ApiKeyLocation::Query, name: "api_key".to_string(), value: "abc".to_string() })
+        );
+
+        assert!(parse_http_file("# @auth ntlm x y\nGET https://api.com").is_err());
+
+        let req = parse_http_file("# @sign sigv4\nGET https://api.com").unwrap();
+        assert_eq!(req.signer.as_deref(), Some("sigv4"));
+        assert!(!req.stream);
+
+        let req = parse_http_file("# @stream\nPOST https://api.com/chat").unwrap();
+        assert!(req.stream);
+
+        let req = parse_http_file("# @diff-ignore $.meta.requestId, items[*].updatedAt\n# @diff-ignore date\nGET https://api.com").unwrap();
+        assert_eq!(req.diff_ignore, vec!["$.meta.requestId", "items[*].updatedAt", "date"]);
+
+        let req = parse_http_file("# @snapshot-redact $.id\nGET https://api.com").unwrap();
+        assert_eq!(req.snapshot_redact, vec!["$.id"]);
+    }
+
+    #[test]
+    fn test_parse_file_form_and_multipart_bodies() {
+        let req = parse_http_file("POST https://api.com/upload\n\n< ./data/payload.json").unwrap();
+        assert_eq!(req.body, Some(Body::File("./data/payload.json".into())));
+
+        let content = "POST https://api.com/login\nContent-Type: application/x-www-form-urlencoded\n\nuser=foo\n&password=bar baz";
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(
+            req.body,
+            Some(Body::Form(vec![
+                ("user".to_string(), "foo".to_string()),
+                ("password".to_string(), "bar baz".to_string()),
+            ]))
+        );
+
+        // `&`-joined fields, with values already encoded
+        let content = "POST https://api.com/login\nContent-Type: application/x-www-form-urlencoded\n\nuser=foo&redirect=%2Fhome%3Fa%3D1&q=a+b\n&rate=100%25";
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(
+            req.body,
+            Some(Body::Form(vec![
+                ("user".to_string(), "foo".to_string()),
+                ("redirect".to_string(), "/home?a=1".to_string()),
+                ("q".to_string(), "a b".to_string()),
+                ("rate".to_string(), "100%".to_string()),
+            ]))
+        );
+
+        // Formatting round-trips values with separators in them
+        let body = Body::Form(vec![("a=b".to_string(), "x&y+z 100%".to_string())]);
+        assert_eq!(format_body(&body), "a%3Db=x%26y%2Bz 100%25");
+        let formatted = format!("POST https://api.com/login\nContent-Type: application/x-www-form-urlencoded\n\n{}", format_body(&body));
+        assert_eq!(parse_http_file(&formatted).unwrap().body, Some(body));
+
+        let content = r#"POST https://api.com/upload
+Content-Type: multipart/form-data; boundary=XYZ
+
+--XYZ
+Content-Disposition: form-data; name="title"
+
+Hello
+--XYZ
+Content-Disposition: form-data; name="image"; filename="a.png"
+Content-Type: image/png
+
+< ./a.png
+--XYZ--"#;
+        let req = parse_http_file(content).unwrap();
+        let body = req.body.unwrap();
+        let Body::Multipart { boundary, parts } = &body else { panic!("expected multipart") };
+        assert_eq!(boundary, "XYZ");
+        assert_eq!(parts[0].content, PartContent::Text("Hello".to_string()));
+        assert_eq!(parts[1].filename.as_deref(), Some("a.png"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("image/png"));
+        assert_eq!(parts[1].content, PartContent::File("./a.png".into()));
+
+        // Formatting round-trips
+        let formatted = format!("POST https://api.com/upload\nContent-Type: multipart/form-data; boundary=XYZ\n\n{}", format_body(&body));
+        assert_eq!(parse_http_file(&formatted).unwrap().body, Some(body));
+    }
+
+    #[test]
+    fn test_parse_graphql() {
+        let content = r#"# @graphql
+POST https://api.com/graphql
+Content-Type: application/json
+
+query GetUser($id: ID!) {
+  user(id: $id) { name }
+}
+
+{
+  "id": "1"
+}"#;
+        let req = parse_http_file(content).unwrap();
+        assert_eq!(
+            req.body,
+            Some(Body::GraphQL {
+                query: "query GetUser($id: ID!) {\n  user(id: $id) { name }\n}".to_string(),
+                variables: Some("{\n  \"id\": \"1\"\n}".to_string()),
+            })
+        );
+
+        // A shorthand query is not mistaken for variables
+        let content = "POST https://api.com/graphql\nX-Request-Type: GraphQL\n\n{\n  users { id }\n}";
+        let req = parse_http_file(content).unwrap();
+        assert!(req.headers.is_empty());
+        assert_eq!(req.body, Some(Body::GraphQL { query: "{\n  users { id }\n}".to_string(), variables: None }));
+    }
+
+    #[test]
+    fn test_parse_websocket_messages() {
+        let content = "WS wss://echo.example.com/socket\nSec-WebSocket-Protocol: chat\n\n{\"type\": \"subscribe\"}\n===\nping\n===\n";
+        let req = parse_http_file(content).unwrap();
+        assert!(req.is_websocket());
+        assert_eq!(
+            req.body,
+            Some(Body::Messages(vec![r#"{"type": "subscribe"}"#.to_string(), "ping".to_string()]))
+        );
+        assert_eq!(format_body(req.body.as_ref().unwrap()), "{\"type\": \"subscribe\"}\n===\nping");
+    }
+
+    #[test]
+    fn test_parse_grpc_request() {
+        let content = "GRPC localhost:50051/helloworld.Greeter/SayHello\nx-user: alice\n\n{\"name\": \"htup\"}";
+        let req = parse_http_file(content).unwrap();
+        assert!(req.is_grpc());
+        assert!(!req.is_websocket());
+        assert_eq!(req.url, "localhost:50051/helloworld.Greeter/SayHello");
+        assert_eq!(req.body, Some(Body::Text("{\"name\": \"htup\"}".to_string())));
     }
 }
@@ -0,0 +1,351 @@
+//! Postman Collection v2.1 and environment exports.
+
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    body::{Body, Part, PartContent},
+    collection::{Collection, CollectionItem},
+    request::Request,
+};
+use crate::infra::fs_collection_repository::{add_variable, set_default_header, text, MULTIPART_BOUNDARY};
+use anyhow::Result;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub fn is_postman(value: &Value) -> bool {
+    value["info"]["schema"].as_str().is_some_and(|s| s.contains("getpostman.com"))
+        || value["_postman_variable_scope"].is_string()
+}
+
+pub fn parse(root: &Value) -> Result<Collection> {
+    if root["info"]["schema"].as_str().is_some_and(|s| s.contains("/v1.")) {
+        anyhow::bail!("Postman collection v1 is not supported, export it as v2.1");
+    }
+    let mut collection = Collection::default();
+
+    // An environment export: variables only
+    if let Some(values) = root["values"].as_array() {
+        collection.name = text(&root["name"]);
+        for value in values.iter().filter(|v| v["enabled"] != Value::Bool(false)) {
+            let target = if value["type"] == "secret" { &mut collection.secrets } else { &mut collection.variables };
+            add_variable(target, &text(&value["key"]), text(&value["value"]));
+        }
+        return Ok(collection);
+    }
+
+    collection.name = text(&root["info"]["name"]);
+    for variable in root["variable"].as_array().into_iter().flatten().filter(|v| v["disabled"] != Value::Bool(true)) {
+        add_variable(&mut collection.variables, &text(&variable["key"]), text(&variable["value"]));
+    }
+    let auth = auth(&root["auth"], "collection", &mut collection).unwrap_or_default();
+    scripts(root, "", &mut collection.warnings);
+    collection.items = items(&root["item"], &auth, "", &mut collection);
+    Ok(collection)
+}
+
+/// Authentication of a request, inherited from the enclosing folders.
+#[derive(Debug, Clone, Default)]
+struct AuthSetting {
+    auth: Option<Auth>,
+    signer: Option<String>,
+}
+
+fn items(values: &Value, inherited: &AuthSetting, parent: &str, collection: &mut Collection) -> Vec<CollectionItem> {
+    let mut result = Vec::new();
+    for item in values.as_array().into_iter().flatten() {
+        let name = text(&item["name"]);
+        let path = if parent.is_empty() { name.clone() } else { format!("{} / {}", parent, name) };
+        scripts(item, &path, &mut collection.warnings);
+
+        if item["item"].is_array() {
+            let auth = auth(&item["auth"], &path, collection).unwrap_or_else(|| inherited.clone());
+            let children = items(&item["item"], &auth, &path, collection);
+            result.push(CollectionItem::Folder { name, items: children });
+        } else {
+            let auth = auth(&item["request"]["auth"], &path, collection).unwrap_or_else(|| inherited.clone());
+            let request = request(&item["request"], auth, &path, collection);
+            result.push(CollectionItem::Request { name, request: Box::new(request) });
+        }
+    }
+    result
+}
+
+fn request(value: &Value, auth: AuthSetting, path: &str, collection: &mut Collection) -> Request {
+    // A request may be written as its URL alone
+    let url = match value {
+        Value::String(url) => url.clone(),
+        _ => url(&value["url"]),
+    };
+    // Path variables (`:id`) keep their example value as a default
+    for variable in value["url"]["variable"].as_array().into_iter().flatten() {
+        add_variable(&mut collection.variables, &text(&variable["key"]), text(&variable["value"]));
+    }
+    let method = value["method"].as_str().unwrap_or("GET");
+    let mut request = Request::new(method, url);
+    request.auth = auth.auth;
+    request.signer = auth.signer;
+
+    for header in value["header"].as_array().into_iter().flatten().filter(|h| h["disabled"] != Value::Bool(true)) {
+        request.headers.insert(text(&header["key"]), text(&header["value"]));
+    }
+
+    let body = &value["body"];
+    if body["disabled"] == Value::Bool(true) {
+        return request;
+    }
+    let enabled = |fields: &Value| -> Vec<Value> {
+        fields.as_array().into_iter().flatten().filter(|f| f["disabled"] != Value::Bool(true)).cloned().collect()
+    };
+    match body["mode"].as_str() {
+        Some("raw") => {
+            let raw = text(&body["raw"]);
+            if !raw.is_empty() {
+                if body["options"]["raw"]["language"] == "json" {
+                    set_default_header(&mut request, "Content-Type", "application/json");
+                }
+                request.body = Some(Body::Text(raw));
+            }
+        }
+        Some("urlencoded") => {
+            let fields = enabled(&body["urlencoded"]).iter().map(|f| (text(&f["key"]), text(&f["value"]))).collect();
+            set_default_header(&mut request, "Content-Type", "application/x-www-form-urlencoded");
+            request.body = Some(Body::Form(fields));
+        }
+        Some("formdata") => {
+            let parts = enabled(&body["formdata"])
+                .iter()
+                .map(|f| {
+                    let content = match f["type"].as_str() {
+                        Some("file") => PartContent::File(PathBuf::from(file_src(&f["src"]))),
+                        _ => PartContent::Text(text(&f["value"])),
+                    };
+                    let content_type = f["contentType"].as_str().map(str::to_string);
+                    Part { name: text(&f["key"]), filename: None, content_type, content }
+                })
+                .collect();
+            request.headers.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+            request.headers.insert("Content-Type".to_string(), format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY));
+            request.body = Some(Body::Multipart { boundary: MULTIPART_BOUNDARY.to_string(), parts });
+        }
+        Some("file") => request.body = Some(Body::File(PathBuf::from(file_src(&body["file"]["src"])))),
+        Some("graphql") => {
+            let variables = text(&body["graphql"]["variables"]);
+            request.body = Some(Body::GraphQL {
+                query: text(&body["graphql"]["query"]),
+                variables: (!variables.trim().is_empty()).then_some(variables),
+            });
+        }
+        Some(other) => collection.warnings.push(format!("{}: {} body is not supported", path, other)),
+        None => {}
+    }
+    request
+}
+
+/// The raw URL, with `:name` path variables turned into `{{name}}` placeholders.
+fn url(value: &Value) -> String {
+    let raw = match value {
+        Value::String(url) => url.clone(),
+        _ => text(&value["raw"]),
+    };
+    let (base, query) = match raw.split_once('?') {
+        Some((base, query)) => (base.to_string(), Some(query)),
+        None => (raw.clone(), None),
+    };
+    let base = base
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) if !name.is_empty() => format!("{{{{{}}}}}", name),
+            _ => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    match query {
+        Some(query) => format!("{}?{}", base, query),
+        None => base,
+    }
+}
+
+/// A file of a form-data part or body; Postman allows several per part, of which the first is used.
+fn file_src(src: &Value) -> String {
+    match src {
+        Value::Array(files) => files.first().map(text).unwrap_or_default(),
+        other => text(other),
+    }
+}
+
+/// `None` when the item inherits the authentication of its parent.
+fn auth(value: &Value, path: &str, collection: &mut Collection) -> Option<AuthSetting> {
+    let kind = value["type"].as_str()?;
+    // Postman 2.1 lists parameters as `[{"key": .., "value": ..}]`
+    let param = |name: &str| -> String {
+        value[kind]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|p| p["key"] == name)
+            .map(|p| text(&p["value"]))
+            .unwrap_or_default()
+    };
+    let auth = match kind {
+        "inherit" => return None,
+        "noauth" => None,
+        "basic" => Some(Auth::Basic { username: param("username"), password: param("password") }),
+        "digest" => Some(Auth::Digest { username: param("username"), password: param("password") }),
+        "bearer" => Some(Auth::Bearer { token: param("token") }),
+        "apikey" => Some(Auth::ApiKey {
+            location: if param("in") == "query" { ApiKeyLocation::Query } else { ApiKeyLocation::Header },
+            name: param("key"),
+            value: param("value"),
+        }),
+        "awsv4" => {
+            for (variable, key) in [("aws_region", "region"), ("aws_service", "service")] {
+                add_variable(&mut collection.variables, variable, param(key));
+            }
+            for (variable, key) in
+                [("aws_access_key_id", "accessKey"), ("aws_secret_access_key", "secretKey"), ("aws_session_token", "sessionToken")]
+            {
+                if !param(key).is_empty() {
+                    add_variable(&mut collection.secrets, variable, param(key));
+                }
+            }
+            return Some(AuthSetting { auth: None, signer: Some("sigv4".to_string()) });
+        }
+        "oauth2" if !param("accessToken").is_empty() => {
+            collection
+                .warnings
+                .push(format!("{}: OAuth2 imported as its current access token, set oauth2_* variables to fetch new ones", path));
+            Some(Auth::Bearer { token: param("accessToken") })
+        }
+        other => {
+            collection.warnings.push(format!("{}: {} authentication is not supported", path, other));
+            None
+        }
+    };
+    Some(AuthSetting { auth, signer: None })
+}
+
+fn scripts(item: &Value, path: &str, warnings: &mut Vec<String>) {
+    for event in item["event"].as_array().into_iter().flatten() {
+        let script = match &event["script"]["exec"] {
+            Value::Array(lines) => lines.iter().map(text).collect::<Vec<_>>().join("\n"),
+            other => text(other),
+        };
+        if script.trim().is_empty() {
+            continue;
+        }
+        let kind = match event["listen"].as_str() {
+            Some("prerequest") => "pre-request script",
+            Some("test") => "test script",
+            _ => "script",
+        };
+        let path = if path.is_empty() { "collection" } else { path };
+        warnings.push(format!("{}: {} not imported", path, kind));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+      "info": { "name": "Shop API", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+      "variable": [{ "key": "base_url", "value": "https://api.shop.com" }],
+      "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }] },
+      "item": [
+        {
+          "name": "Users",
+          "item": [
+            {
+              "name": "Get user",
+              "event": [{ "listen": "prerequest", "script": { "exec": ["pm.environment.set('x', 1)"] } }],
+              "request": {
+                "method": "GET",
+                "header": [{ "key": "Accept", "value": "application/json" }, { "key": "X-Debug", "value": "1", "disabled": true }],
+                "url": { "raw": "{{base_url}}/users/:id?expand=true", "host": ["{{base_url}}"], "path": ["users", ":id"], "variable": [{ "key": "id", "value": "42" }] }
+              }
+            },
+            {
+              "name": "Create user",
+              "request": {
+                "auth": { "type": "basic", "basic": [{ "key": "username", "value": "admin" }, { "key": "password", "value": "{{password}}" }] },
+                "method": "POST",
+                "body": { "mode": "raw", "raw": "{\"name\": \"htup\"}", "options": { "raw": { "language": "json" } } },
+                "url": "{{base_url}}/users"
+              }
+            }
+          ]
+        },
+        {
+          "name": "Upload",
+          "request": {
+            "auth": { "type": "noauth" },
+            "method": "POST",
+            "body": { "mode": "formdata", "formdata": [{ "key": "file", "type": "file", "src": "/tmp/a.png" }, { "key": "note", "value": "hi", "type": "text" }] },
+            "url": "{{base_url}}/upload"
+          }
+        },
+        {
+          "name": "Signed",
+          "request": {
+            "auth": { "type": "awsv4", "awsv4": [{ "key": "region", "value": "eu-west-1" }, { "key": "secretKey", "value": "s3cr3t" }] },
+            "method": "GET",
+            "url": "https://s3.amazonaws.com/"
+          }
+        },
+        { "name": "Legacy", "request": { "auth": { "type": "hawk" }, "method": "GET", "url": "https://old.shop.com" } }
+      ]
+    }"#;
+
+    #[test]
+    fn test_parse_collection() {
+        let collection = parse(&serde_json::from_str(COLLECTION).unwrap()).unwrap();
+        assert_eq!(collection.name, "Shop API");
+        assert_eq!(collection.variables, vec![
+            ("base_url".to_string(), "https://api.shop.com".to_string()),
+            ("id".to_string(), "42".to_string()),
+            ("aws_region".to_string(), "eu-west-1".to_string()),
+            ("aws_service".to_string(), String::new()),
+        ]);
+        assert_eq!(collection.secrets, vec![("aws_secret_access_key".to_string(), "s3cr3t".to_string())]);
+        assert_eq!(collection.warnings, vec![
+            "Users / Get user: pre-request script not imported".to_string(),
+            "Legacy: hawk authentication is not supported".to_string(),
+        ]);
+
+        let [CollectionItem::Folder { name, items }, upload, signed, _] = collection.items.as_slice() else { panic!("unexpected items") };
+        assert_eq!(name, "Users");
+        let [CollectionItem::Request { request: get, .. }, CollectionItem::Request { request: create, .. }] = items.as_slice() else {
+            panic!("unexpected folder items")
+        };
+        assert_eq!(get.url, "{{base_url}}/users/{{id}}?expand=true");
+        assert_eq!(get.auth, Some(Auth::Bearer { token: "{{token}}".to_string() }));
+        assert_eq!(get.headers.len(), 1);
+        assert_eq!(create.auth, Some(Auth::Basic { username: "admin".to_string(), password: "{{password}}".to_string() }));
+        assert_eq!(create.headers["Content-Type"], "application/json");
+
+        let CollectionItem::Request { request: upload, .. } = upload else { panic!("expected a request") };
+        assert_eq!(upload.auth, None);
+        let Some(Body::Multipart { parts, .. }) = &upload.body else { panic!("expected multipart") };
+        assert_eq!(parts[0].content, PartContent::File(PathBuf::from("/tmp/a.png")));
+        let CollectionItem::Request { request: signed, .. } = signed else { panic!("expected a request") };
+        assert_eq!((signed.auth.clone(), signed.signer.as_deref()), (None, Some("sigv4")));
+    }
+
+    #[test]
+    fn test_parse_environment() {
+        let environment = r#"{
+          "name": "Production",
+          "values": [
+            { "key": "base_url", "value": "https://api.shop.com", "type": "default", "enabled": true },
+            { "key": "token", "value": "abc", "type": "secret", "enabled": true },
+            { "key": "old", "value": "x", "enabled": false }
+          ],
+          "_postman_variable_scope": "environment"
+        }"#;
+        let value = serde_json::from_str(environment).unwrap();
+        assert!(is_postman(&value));
+        let collection = parse(&value).unwrap();
+        assert_eq!(collection.variables, vec![("base_url".to_string(), "https://api.shop.com".to_string())]);
+        assert_eq!(collection.secrets, vec![("token".to_string(), "abc".to_string())]);
+        assert!(collection.items.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Loads `.proto` files into descriptors for `prost-reflect`, with the pure Rust parser of `protobuf-parse`.
+
+use anyhow::{Context, Result};
+use prost::Message as _;
+use prost_types::{FileDescriptorProto, FileDescriptorSet};
+use protobuf::Message as _;
+use std::path::{Path, PathBuf};
+
+/// Parses `files` and everything they import, dependencies first.
+/// Imports are resolved against `include_dir`; `google/protobuf/*` imports come with the parser.
+pub fn load_proto_files(include_dir: &Path, files: &[PathBuf]) -> Result<Vec<FileDescriptorProto>> {
+    let set = protobuf_parse::Parser::new()
+        .pure()
+        .include(include_dir)
+        .inputs(files)
+        .file_descriptor_set()
+        .with_context(|| format!("Failed to parse proto files in {:?}", include_dir))?;
+    // The parser has its own descriptor types; both are the protobuf encoding of `FileDescriptorSet`
+    let bytes = set.write_to_bytes().context("Failed to encode proto descriptors")?;
+    Ok(FileDescriptorSet::decode(bytes.as_slice()).context("Failed to decode proto descriptors")?.file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use prost_reflect::{DescriptorPool, Kind};
+
+    const PROTO: &str = r#"
+syntax = "proto3";
+package demo.v1;
+
+import "google/protobuf/timestamp.proto";
+option go_package = "example.com/demo"; // ignored
+
+/* A greeter */
+service Greeter {
+  rpc SayHello (HelloRequest) returns (HelloReply);
+  rpc Watch (HelloRequest) returns (stream HelloReply) { option deprecated = true; }
+}
+
+enum Mood { MOOD_UNSPECIFIED = 0; HAPPY = 1 [deprecated = true]; }
+
+message HelloRequest {
+  string name = 1 [json_name = "userName"];
+  repeated Mood moods = 2;
+  map<string, int32> counts = 3;
+  optional string locale = 4;
+  oneof target { string email = 5; Inner inner = 6; }
+  message Inner { google.protobuf.Timestamp at = 1; }
+  reserved 7, 8;
+}
+
+message HelloReply { string message = 1; HelloRequest.Inner inner = 2; }
+"#;
+
+    #[test]
+    fn test_descriptors_load_into_pool() {
+        let dir = std::env::temp_dir().join(format!("htup-proto-pool-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("demo.proto"), PROTO).unwrap();
+        let files = load_proto_files(&dir, &[dir.join("demo.proto")]).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut pool = DescriptorPool::global();
+        let files: Vec<_> = files.into_iter().filter(|f| pool.get_file_by_name(f.name()).is_none()).collect();
+        pool.add_file_descriptor_protos(files).unwrap();
+
+        let service = pool.get_service_by_name("demo.v1.Greeter").unwrap();
+        let methods: Vec<_> = service.methods().map(|m| (m.name().to_string(), m.is_server_streaming())).collect();
+        assert_eq!(methods, vec![("SayHello".to_string(), false), ("Watch".to_string(), true)]);
+
+        let request = pool.get_message_by_name("demo.v1.HelloRequest").unwrap();
+        assert_eq!(request.get_field_by_name("name").unwrap().json_name(), "userName");
+        assert!(matches!(request.get_field_by_name("moods").unwrap().kind(), Kind::Enum(_)));
+        assert!(request.get_field_by_name("counts").unwrap().is_map());
+        assert!(request.get_field_by_name("locale").unwrap().supports_presence());
+        let mut oneofs: Vec<_> = request.oneofs().map(|o| o.name().to_string()).collect();
+        oneofs.sort();
+        assert_eq!(oneofs, vec!["_locale", "target"]);
+
+        let reply = pool.get_message_by_name("demo.v1.HelloReply").unwrap();
+        let Kind::Message(inner) = reply.get_field_by_name("inner").unwrap().kind() else { panic!("expected a message") };
+        assert_eq!(inner.full_name(), "demo.v1.HelloRequest.Inner");
+    }
+
+    #[test]
+    fn test_imports_are_loaded_first() {
+        let dir = std::env::temp_dir().join(format!("htup-protos-{}", std::process::id()));
+        fs::create_dir_all(dir.join("common")).unwrap();
+        fs::write(dir.join("common/types.proto"), "syntax = \"proto3\"; package common; message Id { string value = 1; }").unwrap();
+        fs::write(dir.join("api.proto"), "syntax = \"proto3\"; import \"common/types.proto\"; message Req { common.Id id = 1; }").unwrap();
+
+        let files = load_proto_files(&dir, &[dir.join("api.proto"), dir.join("common/types.proto")]).unwrap();
+        let names: Vec<_> = files.iter().map(|f| f.name().to_string()).collect();
+        assert_eq!(names, vec!["common/types.proto", "api.proto"]);
+
+        let mut pool = DescriptorPool::new();
+        pool.add_file_descriptor_protos(files).unwrap();
+        assert!(pool.get_message_by_name("Req").is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
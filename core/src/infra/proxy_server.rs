@@ -0,0 +1,215 @@
+use crate::domain::{
+    body::Body as RequestBody,
+    capture::{CapturedCall, UNRECORDED_HEADERS},
+    repository::{CaptureSink, ProxyServer},
+    request::Request,
+    response::Response,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hyper::{header::HeaderMap, server::conn::Http, service::service_fn, Body, Method};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+
+/// Passes calls through to their upstream with reqwest.
+pub struct HyperProxyServer {
+    client: reqwest::Client,
+}
+
+impl HyperProxyServer {
+    pub fn new() -> Self {
+        // Redirects belong to the proxied app, so they are passed back instead of followed.
+        // `HTTP_PROXY` may point at this proxy itself, so it is ignored.
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+}
+
+impl Default for HyperProxyServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ProxyServer for HyperProxyServer {
+    async fn serve(&self, address: SocketAddr, upstream: Option<String>, sink: Arc<dyn CaptureSink>) -> Result<()> {
+        let upstream: Option<Arc<str>> = upstream.map(|u| u.trim_end_matches('/').into());
+        let listener = TcpListener::bind(address).await.with_context(|| format!("Failed to listen on {}", address))?;
+        sink.listening(listener.local_addr()?);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let (client, upstream, sink) = (self.client.clone(), upstream.clone(), sink.clone());
+            tokio::spawn(async move {
+                let service = service_fn(move |request| forward(client.clone(), upstream.clone(), sink.clone(), request));
+                let _ = Http::new().http1_only(true).serve_connection(stream, service).await;
+            });
+        }
+    }
+}
+
+async fn forward(
+    client: reqwest::Client,
+    upstream: Option<Arc<str>>,
+    sink: Arc<dyn CaptureSink>,
+    request: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, Infallible> {
+    if request.method() == Method::CONNECT {
+        return Ok(error(501, "HTTPS tunnelling is not supported, point the app at the proxy with --upstream instead".to_string()));
+    }
+    // `HTTP_PROXY` clients send absolute URLs, everything else is relative to the upstream
+    let url = match (request.uri().scheme(), &upstream) {
+        (Some(_), _) => request.uri().to_string(),
+        (None, Some(upstream)) => format!("{}{}", upstream, request.uri().path_and_query().map_or("/", |p| p.as_str())),
+        (None, None) => return Ok(error(502, "No upstream: start the proxy with --upstream <url>".to_string())),
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => return Ok(error(400, format!("Failed to read request body: {}", e))),
+    };
+    let mut headers = passed_headers(&parts.headers);
+    // Ask for an uncompressed body, so that it can be recorded as text
+    headers.remove(hyper::header::ACCEPT_ENCODING);
+
+    let start = Instant::now();
+    let sent = client.request(parts.method.clone(), &url).headers(headers.clone()).body(body.clone()).send().await;
+    let upstream_response = match sent {
+        Ok(response) => response,
+        Err(e) => return Ok(error(502, format!("Upstream request failed: {}", e))),
+    };
+    let status = upstream_response.status();
+    let response_headers = passed_headers(upstream_response.headers());
+    let response_body = match upstream_response.bytes().await {
+        Ok(bytes) => bytes,
+        Err(e) => return Ok(error(502, format!("Failed to read upstream response: {}", e))),
+    };
+    let latency = start.elapsed();
+
+    let mut captured_request = Request::new(parts.method.as_str(), url);
+    captured_request.headers = recorded_headers(&headers);
+    if !body.is_empty() {
+        captured_request.body = Some(RequestBody::Text(String::from_utf8_lossy(&body).to_string()));
+    }
+    let mut captured_response = Response::new(
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default().to_string(),
+        String::from_utf8_lossy(&response_body).to_string(),
+        latency,
+    );
+    captured_response.headers = recorded_headers(&response_headers);
+    sink.captured(CapturedCall { request: captured_request, response: captured_response });
+
+    let mut response = hyper::Response::new(Body::from(response_body));
+    *response.status_mut() = status;
+    *response.headers_mut() = response_headers;
+    Ok(response)
+}
+
+/// Drops the headers describing the connection to the proxy rather than the call.
+fn passed_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut passed = headers.clone();
+    for name in UNRECORDED_HEADERS {
+        passed.remove(name);
+    }
+    passed
+}
+
+/// Joins repeated headers, the way they are written in a `.http` file.
+fn recorded_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut recorded: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        recorded
+            .entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    recorded
+}
+
+fn error(status: u16, message: String) -> hyper::Response<Body> {
+    let mut response = hyper::Response::new(Body::from(message));
+    *response.status_mut() = hyper::StatusCode::from_u16(status).unwrap_or(hyper::StatusCode::BAD_GATEWAY);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::mock::{MockOptions, MockResponse, MockRoute, MockRouter};
+    use crate::domain::repository::MockServer;
+    use crate::infra::mock_server::HyperMockServer;
+    use crate::infra::test_server::{listening, reported, ChannelSink};
+
+    async fn spawn_upstream() -> String {
+        let response = MockResponse {
+            status: 201,
+            headers: HashMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body: r#"{"id": "{{id}}"}"#.to_string(),
+            delay: None,
+        };
+        let route = MockRoute { request_id: "create".to_string(), method: "PUT".to_string(), path: "/users/:id".to_string(), query: None, body: None, response: Some(response) };
+        let router = Arc::new(MockRouter::new(vec![route], MockOptions::default()));
+        let (sink, mut events) = ChannelSink::new();
+        tokio::spawn(async move { HyperMockServer::new().serve("127.0.0.1:0".parse().unwrap(), router, sink).await });
+        let url = listening(&mut events).await;
+        // Keeps the sink's receiver alive while the upstream serves
+        tokio::spawn(async move { while events.recv().await.is_some() {} });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_passes_through_and_captures() {
+        let upstream = spawn_upstream().await;
+        let (sink, mut events) = ChannelSink::new();
+        let proxied = upstream.clone();
+        tokio::spawn(async move { HyperProxyServer::new().serve("127.0.0.1:0".parse().unwrap(), Some(proxied), sink).await });
+        let proxy_url = listening(&mut events).await;
+
+        let response = reqwest::Client::new()
+            .put(format!("{}/users/9?notify=1", proxy_url))
+            .header("X-Trace", "abc")
+            .body("{\"name\": \"htup\"}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.text().await.unwrap(), r#"{"id": "9"}"#);
+
+        let call: CapturedCall = reported(&mut events).await;
+        assert_eq!(call.request.method, "PUT");
+        assert_eq!(call.request.url, format!("{}/users/9?notify=1", upstream));
+        assert_eq!(call.request.headers["x-trace"], "abc");
+        assert!(!call.request.headers.contains_key("host"));
+        assert_eq!(call.request.body, Some(RequestBody::Text("{\"name\": \"htup\"}".to_string())));
+        assert_eq!((call.response.status, call.response.headers["content-type"].as_str()), (201, "application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_absolute_form_and_missing_upstream() {
+        let upstream = spawn_upstream().await;
+        let (sink, mut events) = ChannelSink::new();
+        tokio::spawn(async move { HyperProxyServer::new().serve("127.0.0.1:0".parse().unwrap(), None, sink).await });
+        let proxy_url = listening(&mut events).await;
+
+        let client = reqwest::Client::builder().proxy(reqwest::Proxy::http(&proxy_url).unwrap()).build().unwrap();
+        let response = client.put(format!("{}/users/3", upstream)).send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), r#"{"id": "3"}"#);
+
+        let response = reqwest::Client::new().get(format!("{}/users/3", proxy_url)).send().await.unwrap();
+        assert_eq!(response.status(), 502);
+    }
+}
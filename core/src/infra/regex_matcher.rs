@@ -0,0 +1,48 @@
+use crate::domain::{repository::PatternCompiler, search::Matcher};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::ops::Range;
+
+/// A search query compiled to a regular expression.
+pub struct RegexMatcher(Regex);
+
+impl Matcher for RegexMatcher {
+    fn find(&self, line: &str) -> Vec<Range<usize>> {
+        self.0.find_iter(line).map(|m| m.range()).collect()
+    }
+}
+
+/// Compiles search queries with the `regex` crate.
+pub struct RegexPatternCompiler;
+
+impl PatternCompiler for RegexPatternCompiler {
+    fn compile(&self, query: &str, regex: bool) -> Result<Box<dyn Matcher>> {
+        let pattern = if regex {
+            Regex::new(query).with_context(|| format!("Invalid regex: {}", query))?
+        } else {
+            Regex::new(&format!("(?i){}", regex::escape(query))).context("Invalid query")?
+        };
+        Ok(Box::new(RegexMatcher(pattern)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_ignores_case() {
+        let matcher = RegexPatternCompiler.compile("ORDERS", false).unwrap();
+        assert_eq!(matcher.find("GET https://api.shop.com/v2/orders?page=1"), vec![Range { start: 28, end: 34 }]);
+        assert_eq!(RegexPatternCompiler.compile("v2/orders?", false).unwrap().find("/v2/orders?page=1").len(), 1);
+        assert!(RegexPatternCompiler.compile("v2/orders?", false).unwrap().find("/v2/orders").is_empty());
+    }
+
+    #[test]
+    fn test_regex() {
+        let matcher = RegexPatternCompiler.compile(r"^X-[\w-]+:", true).unwrap();
+        assert_eq!(matcher.find("X-Api-Version: 2"), vec![Range { start: 0, end: 14 }]);
+        assert!(matcher.find("Accept: X-Api:").is_empty());
+        assert_eq!(RegexPatternCompiler.compile("(", true).err().unwrap().to_string(), "Invalid regex: (");
+    }
+}
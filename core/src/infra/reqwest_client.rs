@@ -1,8 +1,19 @@
-use crate::domain::{repository::HttpClient, request::Request, response::Response};
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    body::{Body, PartContent},
+    repository::HttpClient,
+    request::Request,
+    response::Response,
+    stream::{ResponseStream, StreamEvent, StreamEvents},
+};
+use crate::infra::body::{encode_form, graphql_payload};
+use crate::infra::digest_auth::DigestChallenge;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use rand::Rng;
 use std::str::FromStr;
 use std::time::Instant;
+use tokio::sync::mpsc;
 
 pub struct ReqwestHttpClient {
     client: reqwest::Client,
@@ -16,35 +27,406 @@ impl ReqwestHttpClient {
     }
 }
 
-#[async_trait]
-impl HttpClient for ReqwestHttpClient {
-    async fn send(&self, request: &Request) -> Result<Response> {
-        let method = reqwest::Method::from_str(&request.method)
-            .with_context(|| format!("Invalid HTTP method: {}", request.method))?;
-
-        let mut builder = self.client.request(method, &request.url);
+impl ReqwestHttpClient {
+    async fn build(&self, method: &reqwest::Method, request: &Request) -> Result<reqwest::RequestBuilder> {
+        let mut builder = self.client.request(method.clone(), &request.url);
+        let is_multipart = matches!(request.body, Some(Body::Multipart { .. }));
 
         for (k, v) in &request.headers {
+            // reqwest sets the multipart Content-Type with its own boundary
+            if is_multipart && k.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
             builder = builder.header(k, v);
         }
 
-        if let Some(body) = &request.body {
-            builder = builder.body(body.clone());
+        builder = match &request.body {
+            // WebSocket messages are not an HTTP body, see `TungsteniteWebSocketClient`
+            None | Some(Body::Messages(_)) => builder,
+            Some(Body::Text(text)) => builder.body(text.clone()),
+            Some(Body::Form(fields)) => {
+                if !request.headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+                    builder = builder.header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+                }
+                builder.body(encode_form(fields))
+            }
+            Some(Body::File(path)) => builder.body(open_file(path).await?.0),
+            Some(Body::GraphQL { query, variables }) => {
+                if !request.headers.keys().any(|k| k.eq_ignore_ascii_case("content-type")) {
+                    builder = builder.header(reqwest::header::CONTENT_TYPE, "application/json");
+                }
+                builder.body(graphql_payload(query, variables.as_deref())?)
+            }
+            Some(Body::Multipart { parts, .. }) => {
+                let mut form = reqwest::multipart::Form::new();
+                for part in parts {
+                    let mut form_part = match &part.content {
+                        PartContent::Text(text) => reqwest::multipart::Part::text(text.clone()),
+                        PartContent::File(path) => {
+                            let (body, len) = open_file(path).await?;
+                            let file_name = part.filename.clone().or_else(|| {
+                                path.file_name().map(|n| n.to_string_lossy().to_string())
+                            });
+                            let form_part = reqwest::multipart::Part::stream_with_length(body, len);
+                            match file_name {
+                                Some(name) => form_part.file_name(name),
+                                None => form_part,
+                            }
+                        }
+                    };
+                    if let (Some(filename), PartContent::Text(_)) = (&part.filename, &part.content) {
+                        form_part = form_part.file_name(filename.clone());
+                    }
+                    if let Some(content_type) = &part.content_type {
+                        form_part = form_part
+                            .mime_str(content_type)
+                            .with_context(|| format!("Invalid part Content-Type: {}", content_type))?;
+                    }
+                    form = form.part(part.name.clone(), form_part);
+                }
+                builder.multipart(form)
+            }
+        };
+
+        Ok(match &request.auth {
+            Some(Auth::Basic { username, password }) => builder.basic_auth(username, Some(password)),
+            Some(Auth::Bearer { token }) => builder.bearer_auth(token),
+            Some(Auth::ApiKey { location: ApiKeyLocation::Header, name, value }) => builder.header(name, value),
+            Some(Auth::ApiKey { location: ApiKeyLocation::Query, name, value }) => builder.query(&[(name, value)]),
+            // Digest needs the server's challenge first, see `send`
+            Some(Auth::Digest { .. }) | None => builder,
+        })
+    }
+
+    /// Sends the request, answering a digest challenge if needed.
+    async fn execute(&self, request: &Request) -> Result<reqwest::Response> {
+        let method = reqwest::Method::from_str(&request.method)
+            .with_context(|| format!("Invalid HTTP method: {}", request.method))?;
+
+        let mut resp = self.build(&method, request).await?.send().await?;
+
+        // Digest: retry once with the answer to the server's challenge
+        if let Some(Auth::Digest { username, password }) = &request.auth {
+            if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                if let Some(authorization) = Self::digest_authorization(&resp, &method, username, password) {
+                    resp = self
+                        .build(&method, request)
+                        .await?
+                        .header(reqwest::header::AUTHORIZATION, authorization)
+                        .send()
+                        .await?;
+                }
+            }
         }
+        Ok(resp)
+    }
+
+    /// Answers a digest challenge from `resp`, if there is one.
+    fn digest_authorization(resp: &reqwest::Response, method: &reqwest::Method, username: &str, password: &str) -> Option<String> {
+        let challenge = resp
+            .headers()
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find_map(DigestChallenge::parse)?;
+
+        let url = resp.url();
+        let uri = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        Some(challenge.respond(username, password, method.as_str(), &uri, &cnonce(), 1))
+    }
+}
+
+/// A client nonce for digest auth; RFC 7616 wants it unpredictable, so it comes from random bytes.
+fn cnonce() -> String {
+    rand::thread_rng().gen::<[u8; 16]>().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Extracts `errors[].message` from a GraphQL response body.
+fn graphql_errors(body: &str) -> Vec<String> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        return Vec::new();
+    };
+    json.get("errors")
+        .and_then(|e| e.as_array())
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|e| match e.get("message").and_then(|m| m.as_str()) {
+                    Some(message) => message.to_string(),
+                    None => e.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Takes the longest valid UTF-8 prefix, keeping a character split across chunks for later.
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(text) => text.len(),
+        // Only an incomplete sequence at the very end is worth waiting for
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => return String::from_utf8_lossy(&std::mem::take(pending)).to_string(),
+    };
+    let rest = pending.split_off(valid);
+    String::from_utf8(std::mem::replace(pending, rest)).expect("prefix is valid UTF-8")
+}
+
+/// Opens a file as a streamed body, without reading it into memory.
+async fn open_file(path: &std::path::Path) -> Result<(reqwest::Body, u64)> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open body file: {:?}", path))?;
+    let len = file.metadata().await?.len();
+    Ok((reqwest::Body::from(file), len))
+}
+
+impl Default for ReqwestHttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+#[async_trait]
+impl HttpClient for ReqwestHttpClient {
+    async fn send(&self, request: &Request) -> Result<Response> {
         let start = Instant::now();
-        let resp = builder.send().await?;
+        let resp = self.execute(request).await?;
         let latency = start.elapsed();
 
         let status = resp.status();
         let status_code = status.as_u16();
         let status_text = status.canonical_reason().unwrap_or("").to_string();
-        
+        let headers = resp
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).to_string()))
+            .collect();
+
         let body_text = resp.text().await?;
 
-        // Note: Headers are not copied yet for simplicity, can be added if needed by Response struct
-        let response = Response::new(status_code, status_text, body_text, latency);
+        let mut response = Response::new(status_code, status_text, body_text, latency);
+        response.headers = headers;
+        if let Some(Body::GraphQL { .. }) = &request.body {
+            response.graphql_errors = graphql_errors(&response.body);
+        }
 
         Ok(response)
     }
+
+    async fn send_stream(&self, request: &Request) -> Result<ResponseStream> {
+        let start = Instant::now();
+        let mut resp = self.execute(request).await?;
+
+        let status = resp.status();
+        let headers = resp
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).to_string()))
+            .collect();
+        let (tx, events) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut pending = Vec::new();
+            loop {
+                // Waiting on the receiver too, so that cancelling an idle stream closes its connection
+                let chunk = tokio::select! {
+                    chunk = resp.chunk() => chunk,
+                    _ = tx.closed() => return,
+                };
+                let event = match chunk {
+                    Ok(Some(bytes)) => {
+                        pending.extend_from_slice(&bytes);
+                        let text = take_utf8(&mut pending);
+                        if text.is_empty() {
+                            continue;
+                        }
+                        StreamEvent::Chunk { text, elapsed: start.elapsed() }
+                    }
+                    Ok(None) => StreamEvent::End { elapsed: start.elapsed() },
+                    Err(e) => StreamEvent::Error(e.to_string()),
+                };
+                let done = !matches!(event, StreamEvent::Chunk { .. });
+                // A closed receiver means the stream was cancelled; dropping `resp` aborts the download
+                if tx.send(event).is_err() || done {
+                    return;
+                }
+            }
+        });
+
+        Ok(ResponseStream {
+            status: status.as_u16(),
+            status_text: status.canonical_reason().unwrap_or("").to_string(),
+            headers,
+            events: Box::new(ChannelEvents(events)),
+        })
+    }
+}
+
+/// Events sent by the task reading the body.
+struct ChannelEvents(mpsc::UnboundedReceiver<StreamEvent>);
+
+impl StreamEvents for ChannelEvents {
+    fn try_next(&mut self) -> Option<StreamEvent> {
+        self.0.try_recv().ok()
+    }
+
+    fn close(&mut self) {
+        self.0.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::body::Part;
+    use crate::infra::test_server::{response, serve};
+
+    #[test]
+    fn test_cnonce_is_random() {
+        let (a, b) = (cnonce(), cnonce());
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[tokio::test]
+    async fn test_digest_round_trip() {
+        let (base_url, server) = serve(vec![
+            response("401 Unauthorized", &[("WWW-Authenticate", r#"Digest realm="test", qop="auth", nonce="abc""#)], ""),
+            response("200 OK", &[], "ok"),
+        ])
+        .await;
+
+        let mut request = Request::new("GET", format!("{}/dir/index.html?x=1", base_url));
+        request.auth = Some(Auth::Digest { username: "Mufasa".to_string(), password: "Circle Of Life".to_string() });
+
+        let response = ReqwestHttpClient::new().send(&request).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "ok");
+
+        let received = server.await.unwrap();
+        assert!(!received[0].to_lowercase().contains("authorization:"));
+        assert!(received[1].contains(r#"Digest username="Mufasa", realm="test", nonce="abc", uri="/dir/index.html?x=1""#));
+    }
+
+    #[tokio::test]
+    async fn test_form_and_multipart_bodies() {
+        let (base_url, server) = serve(vec![response("200 OK", &[], ""), response("200 OK", &[], "")]).await;
+
+        let mut form = Request::new("POST", format!("{}/login", base_url));
+        form.body = Some(Body::Form(vec![("user".to_string(), "a b&c".to_string())]));
+        ReqwestHttpClient::new().send(&form).await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("htup-upload-{}.txt", std::process::id()));
+        std::fs::write(&path, "file contents").unwrap();
+        let mut multipart = Request::new("POST", format!("{}/upload", base_url));
+        multipart.headers.insert("Content-Type".to_string(), "multipart/form-data; boundary=XYZ".to_string());
+        multipart.body = Some(Body::Multipart {
+            boundary: "XYZ".to_string(),
+            parts: vec![Part {
+                name: "doc".to_string(),
+                filename: None,
+                content_type: Some("text/plain".to_string()),
+                content: PartContent::File(path.clone()),
+            }],
+        });
+        ReqwestHttpClient::new().send(&multipart).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let received = server.await.unwrap();
+        assert!(received[0].contains("application/x-www-form-urlencoded"));
+        assert!(received[0].ends_with("user=a+b%26c"));
+        assert_eq!(received[1].to_lowercase().matches("content-type: multipart/form-data").count(), 1);
+        assert!(received[1].contains("name=\"doc\"; filename=\"htup-upload-"));
+        assert!(received[1].contains("Content-Type: text/plain\r\n\r\nfile contents\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_graphql_post_and_errors() {
+        let body = r#"{"data":null,"errors":[{"message":"Cannot query field \"email\""}]}"#;
+        let (base_url, server) = serve(vec![response("200 OK", &[("Content-Type", "application/json")], body)]).await;
+
+        let mut request = Request::new("POST", format!("{}/graphql", base_url));
+        request.body = Some(Body::GraphQL {
+            query: "{ user { email } }".to_string(),
+            variables: Some(r#"{"id": 1}"#.to_string()),
+        });
+        let response = ReqwestHttpClient::new().send(&request).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.graphql_errors, vec![r#"Cannot query field "email""#]);
+        assert_eq!(response.headers.get("content-type").map(String::as_str), Some("application/json"));
+
+        let received = server.await.unwrap();
+        assert!(received[0].contains("content-type: application/json"));
+        assert!(received[0].ends_with(r#"{"query":"{ user { email } }","variables":{"id":1}}"#));
+    }
+
+    #[tokio::test]
+    async fn test_stream_delivers_chunks_as_they_arrive() {
+        use crate::infra::test_server::read_request;
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/events", listener.local_addr().unwrap());
+        let (next_tx, next_rx) = tokio::sync::oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n";
+            stream.write_all(head.as_bytes()).await.unwrap();
+            // "é" is split across the two chunks
+            stream.write_all(b"8\r\ndata: a\xc3\r\n").await.unwrap();
+            next_rx.await.unwrap();
+            stream.write_all(b"3\r\n\xa9\n\n\r\n0\r\n\r\n").await.unwrap();
+        });
+
+        let mut response = ReqwestHttpClient::new().send_stream(&Request::new("GET", url)).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert!(response.is_event_stream());
+
+        // The first chunk arrives while the server is still holding back the rest
+        let StreamEvent::Chunk { text, .. } = next(&mut response).await else { panic!("expected a chunk") };
+        assert_eq!(text, "data: a");
+        next_tx.send(()).unwrap();
+
+        let StreamEvent::Chunk { text, .. } = next(&mut response).await else { panic!("expected a chunk") };
+        assert_eq!(text, "é\n\n");
+        assert!(matches!(next(&mut response).await, StreamEvent::End { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_an_idle_stream_closes_the_connection() {
+        use crate::infra::test_server::read_request;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/events", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n";
+            stream.write_all(head.as_bytes()).await.unwrap();
+            // Sends nothing more; the read ends once the client hangs up
+            let mut rest = Vec::new();
+            stream.read_to_end(&mut rest).await
+        });
+
+        let mut response = ReqwestHttpClient::new().send_stream(&Request::new("GET", url)).await.unwrap();
+        response.events.close();
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(5), server).await;
+        assert!(closed.is_ok(), "the connection stayed open after cancelling");
+    }
+
+    async fn next(response: &mut ResponseStream) -> StreamEvent {
+        loop {
+            if let Some(event) = response.events.try_next() {
+                return event;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    }
 }
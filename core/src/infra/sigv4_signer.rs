@@ -0,0 +1,214 @@
+//! AWS Signature Version 4.
+//!
+//! Reads `aws_region`, `aws_service`, `aws_access_key_id`, `aws_secret_access_key`
+//! and the optional `aws_session_token` from the environment.
+
+use crate::domain::{
+    environment::Environment,
+    repository::{Clock, RequestSigner},
+    request::Request,
+};
+use crate::infra::body::payload_sha256;
+use crate::infra::datetime::Civil;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+pub struct SigV4Signer {
+    clock: Arc<dyn Clock>,
+}
+
+impl SigV4Signer {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+}
+
+impl RequestSigner for SigV4Signer {
+    fn name(&self) -> &str {
+        "sigv4"
+    }
+
+    fn sign(&self, request: &mut Request, environment: &Environment) -> Result<()> {
+        let require = |name: &str| environment.get(name).with_context(|| format!("SigV4 signing requires '{}'", name));
+        let region = require("aws_region")?;
+        let service = require("aws_service")?;
+        let access_key = require("aws_access_key_id")?;
+        let secret_key = require("aws_secret_access_key")?;
+
+        let url = reqwest::Url::parse(&request.url).with_context(|| format!("Invalid URL: {}", request.url))?;
+        let amz_date = format_amz_date(self.clock.now());
+        let date = &amz_date[..8];
+        let payload_hash = match payload_sha256(request.body.as_ref())? {
+            Some(hash) => hex(&hash),
+            // The multipart boundary is only known at send time
+            None => "UNSIGNED-PAYLOAD".to_string(),
+        };
+
+        // Headers added here are part of the signature
+        remove_header(request, "x-amz-date");
+        request.headers.insert("X-Amz-Date".to_string(), amz_date.clone());
+        if let Some(token) = environment.get("aws_session_token") {
+            remove_header(request, "x-amz-security-token");
+            request.headers.insert("X-Amz-Security-Token".to_string(), token);
+        }
+        if service == "s3" {
+            remove_header(request, "x-amz-content-sha256");
+            request.headers.insert("X-Amz-Content-Sha256".to_string(), payload_hash.clone());
+        }
+
+        let mut headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.to_lowercase(), v.split_whitespace().collect::<Vec<_>>().join(" ")))
+            .collect();
+        if !headers.iter().any(|(k, _)| k == "host") {
+            let host = url.host_str().context("URL has no host")?;
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            };
+            headers.push(("host".to_string(), host));
+        }
+        headers.sort();
+
+        let canonical_headers: String = headers.iter().map(|(k, v)| format!("{}:{}\n", k, v)).collect();
+        let signed_headers = headers.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>().join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method.to_uppercase(),
+            if url.path().is_empty() { "/" } else { url.path() },
+            canonical_query(&url),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+        let k_region = hmac_sha256(&k_date, region.as_bytes());
+        let k_service = hmac_sha256(&k_region, service.as_bytes());
+        let k_signing = hmac_sha256(&k_service, b"aws4_request");
+        let signature = hex(&hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+
+        remove_header(request, "authorization");
+        request.headers.insert(
+            "Authorization".to_string(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                access_key, scope, signed_headers, signature
+            ),
+        );
+        Ok(())
+    }
+}
+
+/// Sorted, RFC 3986 encoded query string.
+fn canonical_query(url: &reqwest::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (uri_encode(&k), uri_encode(&v)))
+        .collect();
+    pairs.sort();
+    pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&")
+}
+
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn remove_header(request: &mut Request, name: &str) {
+    request.headers.retain(|k, _| !k.eq_ignore_ascii_case(name));
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Formats a time as `YYYYMMDDTHHMMSSZ` (UTC).
+fn format_amz_date(time: SystemTime) -> String {
+    let c = Civil::from_time(time);
+    format!("{:04}{:02}{:02}T{:02}{:02}{:02}Z", c.year, c.month, c.day, c.hour, c.minute, c.second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::MockClock;
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // Credentials and date of the AWS SigV4 test suite (20150830T123600Z)
+    fn signer() -> SigV4Signer {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH + Duration::from_secs(1_440_938_160));
+        SigV4Signer::new(Arc::new(clock))
+    }
+
+    fn env(service: &str) -> Environment {
+        Environment::new(
+            HashMap::from([
+                ("aws_region".to_string(), "us-east-1".to_string()),
+                ("aws_service".to_string(), service.to_string()),
+                ("aws_access_key_id".to_string(), "AKIDEXAMPLE".to_string()),
+            ]),
+            HashMap::from([(
+                "aws_secret_access_key".to_string(),
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            )]),
+        )
+    }
+
+    #[test]
+    fn test_get_vanilla() {
+        let mut request = Request::new("GET", "https://example.amazonaws.com/");
+        signer().sign(&mut request, &env("service")).unwrap();
+
+        assert_eq!(request.headers["X-Amz-Date"], "20150830T123600Z");
+        assert_eq!(
+            request.headers["Authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_iam_list_users_example() {
+        let mut request = Request::new("GET", "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08");
+        request.headers.insert(
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+        );
+        signer().sign(&mut request, &env("iam")).unwrap();
+
+        assert_eq!(
+            request.headers["Authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use crate::domain::repository::Clock;
+use std::time::SystemTime;
+
+/// The clock of the operating system.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
@@ -0,0 +1,118 @@
+//! A minimal HTTP/1.1 stand-in server for infra tests.
+
+use crate::domain::{
+    capture::CapturedCall,
+    mock::MockHit,
+    repository::{CaptureSink, MockHitSink},
+};
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+/// Reads one full request (head and `Content-Length` body) from the stream.
+pub async fn read_request(stream: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(head_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..head_end]).to_lowercase();
+            let length = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if buf.len() >= head_end + 4 + length {
+                break;
+            }
+        }
+        let n = stream.read(&mut chunk).await.unwrap();
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+/// Builds a raw response with the given status line, extra headers and body.
+pub fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut raw = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+    for (k, v) in headers {
+        raw.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    raw.push_str("\r\n");
+    raw.push_str(body);
+    raw
+}
+
+/// Serves the given raw responses, one connection each, and returns the received requests.
+pub async fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = tokio::spawn(async move {
+        let mut received = Vec::new();
+        for raw in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            received.push(read_request(&mut stream).await);
+            stream.write_all(raw.as_bytes()).await.unwrap();
+        }
+        received
+    });
+
+    (base_url, handle)
+}
+
+/// What a server under test reported to its sink.
+pub enum Reported<T> {
+    Listening(SocketAddr),
+    Item(T),
+}
+
+/// A sink handing everything over to the test.
+pub struct ChannelSink<T>(UnboundedSender<Reported<T>>);
+
+impl<T> ChannelSink<T> {
+    pub fn new() -> (std::sync::Arc<Self>, UnboundedReceiver<Reported<T>>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (std::sync::Arc::new(Self(tx)), rx)
+    }
+}
+
+impl MockHitSink for ChannelSink<MockHit> {
+    fn listening(&self, address: SocketAddr) {
+        let _ = self.0.send(Reported::Listening(address));
+    }
+
+    fn hit(&self, hit: MockHit) {
+        let _ = self.0.send(Reported::Item(hit));
+    }
+}
+
+impl CaptureSink for ChannelSink<CapturedCall> {
+    fn listening(&self, address: SocketAddr) {
+        let _ = self.0.send(Reported::Listening(address));
+    }
+
+    fn captured(&self, call: CapturedCall) {
+        let _ = self.0.send(Reported::Item(call));
+    }
+}
+
+/// Waits for the server to bind, returning its base URL.
+pub async fn listening<T>(events: &mut UnboundedReceiver<Reported<T>>) -> String {
+    match events.recv().await {
+        Some(Reported::Listening(address)) => format!("http://{}", address),
+        _ => panic!("expected the server to listen"),
+    }
+}
+
+/// Waits for the next thing the server reported.
+pub async fn reported<T>(events: &mut UnboundedReceiver<Reported<T>>) -> T {
+    match events.recv().await {
+        Some(Reported::Item(item)) => item,
+        _ => panic!("expected a report"),
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::{
+    auth::{ApiKeyLocation, Auth},
+    repository::WebSocketClient,
+    request::Request,
+    websocket::{WebSocketConnection, WsEvent},
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue, Message};
+
+pub struct TungsteniteWebSocketClient;
+
+impl TungsteniteWebSocketClient {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TungsteniteWebSocketClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebSocketClient for TungsteniteWebSocketClient {
+    async fn connect(&self, request: &Request) -> Result<Box<dyn WebSocketConnection>> {
+        let mut url = reqwest::Url::parse(&websocket_url(request)).with_context(|| format!("Invalid URL: {}", request.url))?;
+        let mut headers = request.headers.clone();
+        match &request.auth {
+            Some(Auth::Basic { username, password }) => {
+                let credentials = STANDARD.encode(format!("{}:{}", username, password));
+                headers.insert("Authorization".to_string(), format!("Basic {}", credentials));
+            }
+            Some(Auth::Bearer { token }) => {
+                headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+            }
+            Some(Auth::ApiKey { location: ApiKeyLocation::Header, name, value }) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            Some(Auth::ApiKey { location: ApiKeyLocation::Query, name, value }) => {
+                url.query_pairs_mut().append_pair(name, value);
+            }
+            Some(Auth::Digest { .. }) => anyhow::bail!("Digest auth is not supported for WebSocket requests"),
+            None => {}
+        }
+
+        let mut handshake = url.as_str().into_client_request()?;
+        for (k, v) in &headers {
+            let name: tokio_tungstenite::tungstenite::http::HeaderName =
+                k.parse().with_context(|| format!("Invalid header name: {}", k))?;
+            let value = HeaderValue::from_str(v).with_context(|| format!("Invalid value for header {}", k))?;
+            handshake.headers_mut().insert(name, value);
+        }
+
+        let (socket, _) = tokio_tungstenite::connect_async(handshake)
+            .await
+            .with_context(|| format!("Failed to connect to {}", url))?;
+        let (mut sink, mut stream) = socket.split();
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(text) = outgoing_rx.recv().await {
+                if sink.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            // The session was dropped
+            let _ = sink.close().await;
+        });
+
+        tokio::spawn(async move {
+            let reason = loop {
+                let event = match stream.next().await {
+                    Some(Ok(Message::Text(text))) => WsEvent::Message(text),
+                    Some(Ok(Message::Binary(bytes))) => WsEvent::Message(String::from_utf8_lossy(&bytes).to_string()),
+                    Some(Ok(Message::Close(frame))) => {
+                        break frame.map(|f| format!("{} {}", f.code, f.reason)).unwrap_or_else(|| "Closed by server".to_string());
+                    }
+                    // Pings are answered by tungstenite itself
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => break e.to_string(),
+                    None => break "Connection closed".to_string(),
+                };
+                if incoming_tx.send(event).is_err() {
+                    return;
+                }
+            };
+            let _ = incoming_tx.send(WsEvent::Closed(reason));
+        });
+
+        Ok(Box::new(TungsteniteConnection { outgoing, incoming }))
+    }
+}
+
+/// Frames go through channels to the tasks owning the socket halves.
+struct TungsteniteConnection {
+    outgoing: mpsc::UnboundedSender<String>,
+    incoming: mpsc::UnboundedReceiver<WsEvent>,
+}
+
+impl WebSocketConnection for TungsteniteConnection {
+    fn send(&mut self, text: String) -> Result<()> {
+        self.outgoing.send(text).map_err(|_| anyhow::anyhow!("Connection closed"))
+    }
+
+    fn try_next(&mut self) -> Option<WsEvent> {
+        self.incoming.try_recv().ok()
+    }
+}
+
+/// Maps the request URL to a `ws://`/`wss://` URL, e.g. `WS https://host` -> `wss://host`.
+fn websocket_url(request: &Request) -> String {
+    let url = request.url.as_str();
+    let lower = url.to_lowercase();
+    if lower.starts_with("ws://") || lower.starts_with("wss://") {
+        url.to_string()
+    } else if lower.starts_with("https://") {
+        format!("wss://{}", &url["https://".len()..])
+    } else if lower.starts_with("http://") {
+        format!("ws://{}", &url["http://".len()..])
+    } else if request.method.eq_ignore_ascii_case("wss") {
+        format!("wss://{}", url)
+    } else {
+        format!("ws://{}", url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request as HandshakeRequest, Response as HandshakeResponse};
+
+    async fn next(connection: &mut dyn WebSocketConnection) -> WsEvent {
+        loop {
+            if let Some(event) = connection.try_next() {
+                return event;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_echo_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut authorization = None;
+            // The callback signature is fixed by tungstenite
+            #[allow(clippy::result_large_err)]
+            let callback = |req: &HandshakeRequest, resp: HandshakeResponse| {
+                authorization = req.headers().get("authorization").map(|v| v.to_str().unwrap().to_string());
+                Ok(resp)
+            };
+            let mut socket = tokio_tungstenite::accept_hdr_async(stream, callback).await.unwrap();
+            socket.send(Message::Text("welcome".to_string())).await.unwrap();
+            let echo = socket.next().await.unwrap().unwrap();
+            socket.send(echo).await.unwrap();
+            socket.close(None).await.unwrap();
+            authorization
+        });
+
+        let mut request = Request::new("WS", format!("http://{}/socket", addr));
+        request.auth = Some(Auth::Bearer { token: "t0ken".to_string() });
+        let mut connection = TungsteniteWebSocketClient::new().connect(&request).await.unwrap();
+
+        assert_eq!(next(connection.as_mut()).await, WsEvent::Message("welcome".to_string()));
+        connection.send(r#"{"op":"ping"}"#.to_string()).unwrap();
+        assert_eq!(next(connection.as_mut()).await, WsEvent::Message(r#"{"op":"ping"}"#.to_string()));
+        assert!(matches!(next(connection.as_mut()).await, WsEvent::Closed(_)));
+        assert_eq!(server.await.unwrap().as_deref(), Some("Bearer t0ken"));
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(websocket_url(&Request::new("WS", "https://host/a")), "wss://host/a");
+        assert_eq!(websocket_url(&Request::new("WSS", "host/a")), "wss://host/a");
+        assert_eq!(websocket_url(&Request::new("GET", "ws://host/a")), "ws://host/a");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::domain::{
+    oauth2::{OAuth2Config, OAuth2Token},
+    repository::{Clock, OAuth2Client, TokenCache},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Returns a valid OAuth2 token, reusing and refreshing cached tokens where possible.
+pub struct AcquireTokenUseCase {
+    client: Arc<dyn OAuth2Client>,
+    cache: Arc<dyn TokenCache>,
+    clock: Arc<dyn Clock>,
+}
+
+impl AcquireTokenUseCase {
+    pub fn new(client: Arc<dyn OAuth2Client>, cache: Arc<dyn TokenCache>, clock: Arc<dyn Clock>) -> Self {
+        Self { client, cache, clock }
+    }
+
+    pub async fn execute(&self, config: &OAuth2Config) -> Result<OAuth2Token> {
+        let key = config.cache_key();
+        let cached = self.cache.load(&key)?;
+
+        if let Some(token) = &cached {
+            if !token.is_expired(self.clock.now()) {
+                return Ok(token.clone());
+            }
+        }
+
+        // Prefer refreshing an expired token over a new (possibly interactive) grant
+        let refreshed = match cached.and_then(|t| t.refresh_token) {
+            Some(refresh_token) => self.client.refresh_token(config, &refresh_token).await.ok(),
+            None => None,
+        };
+        let token = match refreshed {
+            Some(token) => token,
+            None => self.client.fetch_token(config).await?,
+        };
+
+        self.cache.save(&key, &token)?;
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::oauth2::OAuth2Grant;
+    use crate::domain::repository::{MockClock, MockOAuth2Client, MockTokenCache};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    const NOW: u64 = 1_000_000;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn clock() -> Arc<MockClock> {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| at(NOW));
+        Arc::new(clock)
+    }
+
+    fn config() -> OAuth2Config {
+        OAuth2Config {
+            grant: OAuth2Grant::ClientCredentials,
+            token_url: "https://auth.example.com/token".to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+            scope: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returns_cached_token_while_valid() {
+        let mut cache = MockTokenCache::new();
+        cache
+            .expect_load()
+            .returning(|_| Ok(Some(OAuth2Token::new("cached", None, Some(3600), at(NOW - 60)))));
+        cache.expect_save().times(0);
+        let mut client = MockOAuth2Client::new();
+        client.expect_fetch_token().times(0);
+
+        let usecase = AcquireTokenUseCase::new(Arc::new(client), Arc::new(cache), clock());
+        let token = usecase.execute(&config()).await.unwrap();
+        assert_eq!(token.access_token, "cached");
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_expired_token() {
+        let mut cache = MockTokenCache::new();
+        cache
+            .expect_load()
+            .returning(|_| Ok(Some(OAuth2Token::new("old", Some("refresh".to_string()), Some(3600), at(NOW - 3600)))));
+        cache
+            .expect_save()
+            .withf(|_, token| token.access_token == "new")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut client = MockOAuth2Client::new();
+        client
+            .expect_refresh_token()
+            .withf(|_, refresh_token| refresh_token == "refresh")
+            .times(1)
+            .returning(|_, _| Ok(OAuth2Token::new("new", None, Some(3600), at(NOW))));
+        client.expect_fetch_token().times(0);
+
+        let usecase = AcquireTokenUseCase::new(Arc::new(client), Arc::new(cache), clock());
+        let token = usecase.execute(&config()).await.unwrap();
+        assert_eq!(token.access_token, "new");
+    }
+}
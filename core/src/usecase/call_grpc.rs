@@ -0,0 +1,69 @@
+use crate::domain::{
+    environment::Environment,
+    project::Project,
+    repository::{GrpcClient, ProtoRepository},
+    request::Request,
+    response::Response,
+};
+use crate::usecase::execute_request::ExecuteRequestUseCase;
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Calls a gRPC method, with the schema from the project's `.proto` files or server reflection.
+pub struct CallGrpcUseCase {
+    execute_request: Arc<ExecuteRequestUseCase>,
+    protos: Arc<dyn ProtoRepository>,
+    client: Arc<dyn GrpcClient>,
+}
+
+impl CallGrpcUseCase {
+    pub fn new(execute_request: Arc<ExecuteRequestUseCase>, protos: Arc<dyn ProtoRepository>, client: Arc<dyn GrpcClient>) -> Self {
+        Self { execute_request, protos, client }
+    }
+
+    pub async fn execute(&self, project: &Project, request: &Request, environment: &Environment) -> Result<Response> {
+        let request = self.execute_request.prepare(request, environment).await?;
+        let protos = self.protos.find(project, environment)?;
+        self.client.call(&request, protos).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::body::Body;
+    use crate::domain::grpc::ProtoSource;
+    use crate::domain::repository::{MockGrpcClient, MockHttpClient, MockProtoRepository};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_call_with_project_protos() {
+        let source = ProtoSource { include_dir: PathBuf::from("api/proto"), files: vec![PathBuf::from("api/proto/demo.proto")] };
+        let mut protos = MockProtoRepository::new();
+        let found = source.clone();
+        protos.expect_find().times(1).returning(move |_, _| Ok(Some(found.clone())));
+
+        let mut client = MockGrpcClient::new();
+        client
+            .expect_call()
+            .withf(move |req, protos| {
+                req.body == Some(Body::Text("{\"name\": \"htup\"}".to_string())) && protos.as_ref() == Some(&source)
+            })
+            .times(1)
+            .returning(|_, _| Ok(Response::new(0, "OK".to_string(), "{}".to_string(), Duration::ZERO)));
+
+        let usecase = CallGrpcUseCase::new(
+            Arc::new(ExecuteRequestUseCase::new(Arc::new(MockHttpClient::new()))),
+            Arc::new(protos),
+            Arc::new(client),
+        );
+        let mut request = Request::new("GRPC", "localhost:50051/demo.Greeter/SayHello");
+        request.body = Some(Body::Text("{\"name\": \"{{name}}\"}".to_string()));
+        let env = Environment::new(HashMap::from([("name".to_string(), "htup".to_string())]), HashMap::new());
+
+        let response = usecase.execute(&Project::new("api"), &request, &env).await.unwrap();
+        assert_eq!(response.status, 0);
+    }
+}
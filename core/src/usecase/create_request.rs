@@ -2,6 +2,7 @@ use crate::domain::{
     project::Project,
     repository::RequestRepository,
     request::Request,
+    body::{Body, Part, PartContent},
 };
 use anyhow::Result;
 use std::sync::Arc;
@@ -33,9 +34,46 @@ impl CreateRequestUseCase {
         // Construct Request object
         let mut request = Request::new(method, "https://example.com");
         
-        if body_type == "JSON" {
-            request.headers.insert("Content-Type".to_string(), "application/json".to_string());
-            request.body = Some("{\n    \n}".to_string());
+        match body_type {
+            "JSON" => {
+                request.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                request.body = Some(Body::Text("{\n    \n}".to_string()));
+            }
+            "Form" => {
+                request.headers.insert("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string());
+                request.body = Some(Body::Form(vec![("key".to_string(), "value".to_string())]));
+            }
+            "Multipart" => {
+                let boundary = "htup-boundary".to_string();
+                request.headers.insert("Content-Type".to_string(), format!("multipart/form-data; boundary={}", boundary));
+                request.body = Some(Body::Multipart {
+                    boundary,
+                    parts: vec![
+                        Part {
+                            name: "field".to_string(),
+                            filename: None,
+                            content_type: None,
+                            content: PartContent::Text("value".to_string()),
+                        },
+                        Part {
+                            name: "file".to_string(),
+                            filename: Some("file.txt".to_string()),
+                            content_type: Some("text/plain".to_string()),
+                            content: PartContent::File("./file.txt".into()),
+                        },
+                    ],
+                });
+            }
+            "GraphQL" => {
+                request.method = "POST".to_string();
+                request.url = "https://example.com/graphql".to_string();
+                request.headers.insert("Content-Type".to_string(), "application/json".to_string());
+                request.body = Some(Body::GraphQL {
+                    query: "query {\n    \n}".to_string(),
+                    variables: Some("{}".to_string()),
+                });
+            }
+            _ => {} // Empty
         }
 
         self.repo.save(project, request_id, &request)
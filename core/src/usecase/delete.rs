@@ -0,0 +1,74 @@
+use crate::domain::{
+    project::Project,
+    repository::{ProjectRepository, RequestRepository, TrashRepository},
+    trash::{TrashEntry, TRASH_RETENTION},
+};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Moves requests, folders and projects to the trash, and puts them back.
+pub struct DeleteUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    trash: Arc<dyn TrashRepository>,
+}
+
+impl DeleteUseCase {
+    pub fn new(projects: Arc<dyn ProjectRepository>, requests: Arc<dyn RequestRepository>, trash: Arc<dyn TrashRepository>) -> Self {
+        Self { projects, requests, trash }
+    }
+
+    pub fn delete_request(&self, project: &Project, request_id: &str) -> Result<TrashEntry> {
+        self.put(&format!("{}/{}", project.name, request_id), self.requests.paths(project, request_id))
+    }
+
+    pub fn delete_folder(&self, project: &Project, path: &str) -> Result<TrashEntry> {
+        self.put(&format!("{}/{}", project.name, path), self.projects.folder_paths(project, path))
+    }
+
+    pub fn delete_project(&self, project: &Project) -> Result<TrashEntry> {
+        self.put(&project.name, self.projects.project_paths(project))
+    }
+
+    /// Restores a deleted item; fails if something was created in its place since.
+    pub fn undo(&self, entry: &TrashEntry) -> Result<()> {
+        self.trash.restore(entry)
+    }
+
+    fn put(&self, label: &str, paths: Vec<PathBuf>) -> Result<TrashEntry> {
+        let entry = self.trash.put(label, &paths)?;
+        // Old entries only take up space, so failing to drop them does not fail the delete
+        let _ = self.trash.purge(TRASH_RETENTION);
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::{MockProjectRepository, MockRequestRepository, MockTrashRepository};
+    use mockall::predicate::*;
+
+    fn entry(label: &str) -> TrashEntry {
+        TrashEntry { id: "1700000000000".to_string(), label: label.to_string() }
+    }
+
+    #[test]
+    fn test_delete_and_undo() {
+        let mut requests = MockRequestRepository::new();
+        requests.expect_paths().with(always(), eq("users/get_user")).times(1).returning(|_, _| vec![PathBuf::from("shop/users/get_user.http")]);
+        let mut trash = MockTrashRepository::new();
+        trash
+            .expect_put()
+            .with(eq("shop/users/get_user"), eq(vec![PathBuf::from("shop/users/get_user.http")]))
+            .times(1)
+            .returning(|label, _| Ok(entry(label)));
+        trash.expect_purge().with(eq(TRASH_RETENTION)).times(1).returning(|_| Err(anyhow::anyhow!("read-only")));
+        trash.expect_restore().with(eq(entry("shop/users/get_user"))).times(1).returning(|_| Ok(()));
+        let usecase = DeleteUseCase::new(Arc::new(MockProjectRepository::new()), Arc::new(requests), Arc::new(trash));
+
+        let deleted = usecase.delete_request(&Project::new("shop"), "users/get_user").unwrap();
+        usecase.undo(&deleted).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+use crate::domain::{
+    diff::{diff_responses, ResponseDiff},
+    environment::Environment,
+    history::HistoryEntry,
+    project::Project,
+    repository::{Clock, HistoryRepository},
+    request::Request,
+    response::Response,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Records responses per request and compares them between runs or against a pinned baseline.
+pub struct DiffResponsesUseCase {
+    history: Arc<dyn HistoryRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl DiffResponsesUseCase {
+    pub fn new(history: Arc<dyn HistoryRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { history, clock }
+    }
+
+    /// Adds a response to the history of a request, along with the request as sent.
+    /// Secrets are masked before storing.
+    pub fn record(&self, project: &Project, request_id: &str, request: &Request, response: &Response, environment: &Environment) -> Result<()> {
+        let mut entry = self.entry(response, environment);
+        entry.request = Some(environment.redact_request(&environment.apply(request)));
+        self.history.append(project, request_id, &entry)
+    }
+
+    /// Pins a response as the baseline later responses are compared against.
+    pub fn pin_baseline(&self, project: &Project, request_id: &str, response: &Response, environment: &Environment) -> Result<()> {
+        self.history.save_baseline(project, request_id, &self.entry(response, environment))
+    }
+
+    pub fn history(&self, project: &Project, request_id: &str) -> Result<Vec<HistoryEntry>> {
+        self.history.list(project, request_id)
+    }
+
+    /// Compares the last two recorded responses, or `None` with fewer than two runs.
+    pub fn diff_previous(&self, project: &Project, request_id: &str, ignore: &[String]) -> Result<Option<ResponseDiff>> {
+        let entries = self.history.list(project, request_id)?;
+        let [.., old, new] = entries.as_slice() else {
+            return Ok(None);
+        };
+        Ok(Some(diff_responses(&old.response, &new.response, ignore)))
+    }
+
+    /// Compares a response against the pinned baseline, or `None` without one.
+    pub fn diff_baseline(
+        &self,
+        project: &Project,
+        request_id: &str,
+        response: &Response,
+        environment: &Environment,
+        ignore: &[String],
+    ) -> Result<Option<ResponseDiff>> {
+        let Some(baseline) = self.history.load_baseline(project, request_id)? else {
+            return Ok(None);
+        };
+        // The baseline was stored redacted, so compare like with like
+        let current = environment.redact_response(response);
+        Ok(Some(diff_responses(&baseline.response, &current, ignore)))
+    }
+
+    fn entry(&self, response: &Response, environment: &Environment) -> HistoryEntry {
+        HistoryEntry { timestamp: self.clock.now(), request: None, response: environment.redact_response(response) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::diff::JsonChange;
+    use crate::domain::repository::{MockClock, MockHistoryRepository};
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn clock() -> Arc<MockClock> {
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH + Duration::from_secs(42));
+        Arc::new(clock)
+    }
+
+    fn response(body: &str) -> Response {
+        Response::new(200, "OK".to_string(), body.to_string(), Duration::ZERO)
+    }
+
+    fn env() -> Environment {
+        Environment::new(HashMap::new(), HashMap::from([("token".to_string(), "s3cr3t".to_string())]))
+    }
+
+    #[test]
+    fn test_record_masks_secrets() {
+        let mut history = MockHistoryRepository::new();
+        history
+            .expect_append()
+            .withf(|_, id, entry| {
+                id == "login"
+                    && entry.response.body == r#"{"token": "******"}"#
+                    && entry.request.as_ref().is_some_and(|r| r.headers["Authorization"] == "Bearer ******")
+                    && entry.timestamp == UNIX_EPOCH + Duration::from_secs(42)
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let usecase = DiffResponsesUseCase::new(Arc::new(history), clock());
+        let mut request = Request::new("POST", "https://api.example.com/login");
+        request.headers.insert("Authorization".to_string(), "Bearer {{token}}".to_string());
+        usecase.record(&Project::new("api"), "login", &request, &response(r#"{"token": "s3cr3t"}"#), &env()).unwrap();
+    }
+
+    #[test]
+    fn test_diff_previous_run() {
+        let mut history = MockHistoryRepository::new();
+        history.expect_list().returning(|_, _| {
+            Ok(["{\"a\": 1}", "{\"a\": 2, \"at\": 1}", "{\"a\": 3, \"at\": 2}"]
+                .iter()
+                .map(|b| HistoryEntry { timestamp: UNIX_EPOCH, request: None, response: response(b) })
+                .collect())
+        });
+
+        let usecase = DiffResponsesUseCase::new(Arc::new(history), clock());
+        let diff = usecase.diff_previous(&Project::new("api"), "get", &["at".to_string()]).unwrap().unwrap();
+        assert_eq!(diff.changes, vec![JsonChange::Changed { path: "$.a".to_string(), old: 2.into(), new: 3.into() }]);
+    }
+
+    #[test]
+    fn test_diff_baseline() {
+        let mut history = MockHistoryRepository::new();
+        history.expect_load_baseline().times(1).returning(|_, _| Ok(None));
+        history
+            .expect_load_baseline()
+            .returning(|_, _| Ok(Some(HistoryEntry { timestamp: UNIX_EPOCH, request: None, response: response(r#"{"token": "******"}"#) })));
+
+        let usecase = DiffResponsesUseCase::new(Arc::new(history), clock());
+        let project = Project::new("api");
+        let current = response(r#"{"token": "s3cr3t"}"#);
+        assert_eq!(usecase.diff_baseline(&project, "login", &current, &env(), &[]).unwrap(), None);
+        let diff = usecase.diff_baseline(&project, "login", &current, &env(), &[]).unwrap().unwrap();
+        assert!(diff.is_empty());
+    }
+}
@@ -15,6 +15,11 @@ impl EditRequestUseCase {
     }
 
     pub fn execute(&self, project: &Project, request_id: &str) -> Result<()> {
-        self.editor.edit(project, request_id)
+        self.editor.edit(project, request_id, None)
+    }
+
+    /// Opens the request with the cursor on `line` (1-based).
+    pub fn execute_at(&self, project: &Project, request_id: &str, line: usize) -> Result<()> {
+        self.editor.edit(project, request_id, Some(line))
     }
 }
@@ -1,32 +1,84 @@
 use crate::domain::{
-    repository::HttpClient,
+    environment::Environment,
+    oauth2::{OAuth2Config, ACCESS_TOKEN_VARIABLE},
+    repository::{HttpClient, RequestSigner},
     request::Request,
     response::Response,
+    stream::ResponseStream,
 };
-use anyhow::Result;
+use crate::usecase::acquire_token::AcquireTokenUseCase;
+use anyhow::{Context, Result};
 use std::sync::Arc;
 
 pub struct ExecuteRequestUseCase {
     client: Arc<dyn HttpClient>,
+    acquire_token: Option<AcquireTokenUseCase>,
+    signers: Vec<Arc<dyn RequestSigner>>,
 }
 
 impl ExecuteRequestUseCase {
     pub fn new(client: Arc<dyn HttpClient>) -> Self {
-        Self { client }
+        Self { client, acquire_token: None, signers: Vec::new() }
     }
 
-    pub async fn execute(&self, request: &Request) -> Result<Response> {
-        // Here we could add logic to save history, substitute env vars, etc.
-        // For now, just pass through.
-        self.client.send(request).await
+    /// Acquires OAuth2 tokens for environments that configure `oauth2_*` variables.
+    pub fn with_oauth2(mut self, acquire_token: AcquireTokenUseCase) -> Self {
+        self.acquire_token = Some(acquire_token);
+        self
+    }
+
+    /// Registers a signer that requests can select with `# @sign <name>`.
+    pub fn with_signer(mut self, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    pub async fn execute(&self, request: &Request, environment: &Environment) -> Result<Response> {
+        let resolved = self.prepare(request, environment).await?;
+        self.client.send(&resolved).await
+    }
+
+    /// Like `execute`, but returns as soon as the response head arrives and streams the body.
+    pub async fn execute_stream(&self, request: &Request, environment: &Environment) -> Result<ResponseStream> {
+        let resolved = self.prepare(request, environment).await?;
+        self.client.send_stream(&resolved).await
+    }
+
+    /// Resolves a request into exactly what goes on the wire: token, variables and signature.
+    pub async fn prepare(&self, request: &Request, environment: &Environment) -> Result<Request> {
+        // Make sure a fresh token is available as {{oauth2_access_token}}
+        let environment = match (&self.acquire_token, OAuth2Config::from_environment(environment)?) {
+            (Some(acquire_token), Some(config)) => {
+                let token = acquire_token.execute(&config).await?;
+                environment.with_secret(ACCESS_TOKEN_VARIABLE, token.access_token)
+            }
+            _ => environment.clone(),
+        };
+
+        // Resolve {{variables}} (including secrets) right before sending
+        let mut resolved = environment.apply(request);
+
+        // Sign last, so the signature covers the final request
+        if let Some(name) = &resolved.signer {
+            let signer = self
+                .signers
+                .iter()
+                .find(|s| s.name() == name)
+                .with_context(|| format!("Unknown signer: {}", name))?;
+            signer.sign(&mut resolved, &environment)?;
+        }
+
+        Ok(resolved)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::repository::MockHttpClient;
-    use std::time::Duration;
+    use crate::domain::oauth2::OAuth2Token;
+    use crate::domain::repository::{MockClock, MockHttpClient, MockOAuth2Client, MockRequestSigner, MockTokenCache};
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
 
     #[tokio::test]
     async fn test_execute_request() {
@@ -41,9 +93,103 @@ mod tests {
             .returning(|_| Ok(Response::new(200, "OK".to_string(), "body".to_string(), Duration::from_millis(100))));
 
         let usecase = ExecuteRequestUseCase::new(Arc::new(mock_client));
-        let response = usecase.execute(&request).await.unwrap();
+        let response = usecase.execute(&request, &Environment::default()).await.unwrap();
 
         assert_eq!(response.status, 200);
         assert_eq!(response.body, "body");
     }
+
+    #[tokio::test]
+    async fn test_execute_request_substitutes_environment() {
+        let mut mock_client = MockHttpClient::new();
+        let mut request = Request::new("GET", "https://{{host}}/users");
+        request.headers.insert("Authorization".to_string(), "Bearer {{token}}".to_string());
+
+        let environment = Environment::new(
+            HashMap::from([("host".to_string(), "api.example.com".to_string())]),
+            HashMap::from([("token".to_string(), "s3cr3t".to_string())]),
+        );
+
+        mock_client
+            .expect_send()
+            .withf(|req| req.url == "https://api.example.com/users" && req.headers["Authorization"] == "Bearer s3cr3t")
+            .times(1)
+            .returning(|_| Ok(Response::new(200, "OK".to_string(), String::new(), Duration::from_millis(1))));
+
+        let usecase = ExecuteRequestUseCase::new(Arc::new(mock_client));
+        usecase.execute(&request, &environment).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_request_exposes_oauth2_token() {
+        let mut mock_client = MockHttpClient::new();
+        let mut request = Request::new("GET", "https://api.example.com/me");
+        request.headers.insert("Authorization".to_string(), "Bearer {{oauth2_access_token}}".to_string());
+
+        let environment = Environment::new(
+            HashMap::from([
+                ("oauth2_grant_type".to_string(), "client_credentials".to_string()),
+                ("oauth2_token_url".to_string(), "https://auth.example.com/token".to_string()),
+                ("oauth2_client_id".to_string(), "client".to_string()),
+            ]),
+            HashMap::new(),
+        );
+
+        let mut cache = MockTokenCache::new();
+        cache.expect_load().returning(|_| Ok(None));
+        cache.expect_save().times(1).returning(|_, _| Ok(()));
+        let mut oauth2 = MockOAuth2Client::new();
+        oauth2
+            .expect_fetch_token()
+            .times(1)
+            .returning(|_| Ok(OAuth2Token::new("t0ken", None, Some(3600), UNIX_EPOCH)));
+
+        mock_client
+            .expect_send()
+            .withf(|req| req.headers["Authorization"] == "Bearer t0ken")
+            .times(1)
+            .returning(|_| Ok(Response::new(200, "OK".to_string(), String::new(), Duration::from_millis(1))));
+
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH);
+
+        let usecase = ExecuteRequestUseCase::new(Arc::new(mock_client))
+            .with_oauth2(AcquireTokenUseCase::new(Arc::new(oauth2), Arc::new(cache), Arc::new(clock)));
+        usecase.execute(&request, &environment).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_request_signs_after_substitution() {
+        let mut mock_client = MockHttpClient::new();
+        let mut request = Request::new("GET", "https://{{host}}/");
+        request.signer = Some("test".to_string());
+        let environment = Environment::new(
+            HashMap::from([("host".to_string(), "api.example.com".to_string())]),
+            HashMap::new(),
+        );
+
+        let mut signer = MockRequestSigner::new();
+        signer.expect_name().return_const("test".to_string());
+        signer
+            .expect_sign()
+            .withf(|req, _| req.url == "https://api.example.com/")
+            .times(1)
+            .returning(|req, _| {
+                req.headers.insert("X-Signature".to_string(), "sig".to_string());
+                Ok(())
+            });
+
+        mock_client
+            .expect_send()
+            .withf(|req| req.headers["X-Signature"] == "sig")
+            .times(1)
+            .returning(|_| Ok(Response::new(200, "OK".to_string(), String::new(), Duration::from_millis(1))));
+
+        let usecase = ExecuteRequestUseCase::new(Arc::new(mock_client)).with_signer(Arc::new(signer));
+        usecase.execute(&request, &environment).await.unwrap();
+
+        let mut unknown = Request::new("GET", "https://api.example.com/");
+        unknown.signer = Some("missing".to_string());
+        assert!(usecase.execute(&unknown, &environment).await.is_err());
+    }
 }
@@ -0,0 +1,109 @@
+use crate::domain::{
+    capture::{ArchivedCall, CapturedCall},
+    environment::Environment,
+    project::Project,
+    repository::{HarRepository, HistoryRepository, ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Writes the recorded responses of a project as a HAR file, e.g. for Chrome devtools.
+pub struct ExportHarUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    history: Arc<dyn HistoryRepository>,
+    har: Arc<dyn HarRepository>,
+}
+
+impl ExportHarUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        history: Arc<dyn HistoryRepository>,
+        har: Arc<dyn HarRepository>,
+    ) -> Self {
+        Self { projects, requests, history, har }
+    }
+
+    /// Exports the history of `request_ids` (every request when empty) and returns the number of entries.
+    /// Each response is paired with the request recorded with it. Older entries, recorded without one,
+    /// fall back to the request file resolved against `environment`, with secrets masked so the file can be shared.
+    pub fn execute(&self, project: &Project, request_ids: &[String], environment: &Environment, path: &Path) -> Result<usize> {
+        let request_ids = if request_ids.is_empty() { self.projects.list_requests(project)? } else { request_ids.to_vec() };
+        let mut calls = Vec::new();
+        for request_id in request_ids {
+            let request = self.requests.load(project, &request_id)?;
+            // HAR describes HTTP exchanges only
+            if request.is_websocket() || request.is_grpc() {
+                continue;
+            }
+            let current = environment.redact_request(&environment.apply(&request));
+            for entry in self.history.list(project, &request_id)? {
+                let request = entry.request.unwrap_or_else(|| current.clone());
+                calls.push(ArchivedCall {
+                    started: entry.timestamp,
+                    call: CapturedCall { request, response: environment.redact_response(&entry.response) },
+                });
+            }
+        }
+        self.har.write(path, &calls)?;
+        Ok(calls.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::environment::REDACTED;
+    use crate::domain::history::HistoryEntry;
+    use crate::domain::repository::{MockHarRepository, MockHistoryRepository, MockProjectRepository, MockRequestRepository};
+    use crate::domain::request::Request;
+    use crate::domain::response::Response;
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_export_pairs_responses_with_their_requests() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_requests().returning(|_| Ok(vec!["me".to_string(), "events".to_string()]));
+        let mut requests = MockRequestRepository::new();
+        requests.expect_load().returning(|_, id| {
+            Ok(match id {
+                "me" => {
+                    let mut request = Request::new("GET", "{{base_url}}/me");
+                    request.headers.insert("Authorization".to_string(), "Bearer {{token}}".to_string());
+                    request
+                }
+                _ => Request::new("GET", "wss://example.com/events"),
+            })
+        });
+        let mut history = MockHistoryRepository::new();
+        history.expect_list().returning(|_, _| {
+            let response = Response::new(200, "OK".to_string(), "{}".to_string(), Duration::ZERO);
+            let sent = Request::new("GET", "https://old.example.com/me");
+            Ok(vec![
+                HistoryEntry { timestamp: SystemTime::now(), request: None, response: response.clone() },
+                HistoryEntry { timestamp: SystemTime::now(), request: Some(sent), response },
+            ])
+        });
+        let mut har = MockHarRepository::new();
+        har.expect_write()
+            .withf(|_, calls| {
+                calls.len() == 2
+                    && calls[0].call.request.url == "https://api.example.com/me"
+                    && calls[0].call.request.headers["Authorization"] == format!("Bearer {}", REDACTED)
+                    && calls[1].call.request.url == "https://old.example.com/me"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let usecase = ExportHarUseCase::new(Arc::new(projects), Arc::new(requests), Arc::new(history), Arc::new(har));
+        let environment = Environment::new(
+            HashMap::from([("base_url".to_string(), "https://api.example.com".to_string())]),
+            HashMap::from([("token".to_string(), "s3cr3t".to_string())]),
+        );
+        let count = usecase.execute(&Project::new("api"), &[], &environment, Path::new("out.har")).unwrap();
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::domain::{
+    finder::{self, FinderEntry, FinderHit},
+    repository::{ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Fuzzy finds projects and requests by name, and optionally by method and URL.
+pub struct FindRequestUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+}
+
+impl FindRequestUseCase {
+    pub fn new(projects: Arc<dyn ProjectRepository>, requests: Arc<dyn RequestRepository>) -> Self {
+        Self { projects, requests }
+    }
+
+    /// Every project followed by its requests; a request that fails to parse is still listed by name.
+    pub fn index(&self) -> Result<Vec<FinderEntry>> {
+        let mut entries = Vec::new();
+        for project in self.projects.list_projects()? {
+            let requests = self.projects.list_requests(&project)?;
+            entries.push(FinderEntry { project: project.clone(), request_id: None, detail: None });
+            for id in requests {
+                let detail = self.requests.load(&project, &id).ok().map(|r| format!("{} {}", r.method, r.url));
+                entries.push(FinderEntry { project: project.clone(), request_id: Some(id), detail });
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn search(&self, entries: &[FinderEntry], query: &str, with_details: bool) -> Vec<FinderHit> {
+        finder::search(entries, query, with_details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{project::Project, repository::{MockProjectRepository, MockRequestRepository}, request::Request};
+
+    #[test]
+    fn test_index_lists_projects_and_requests() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("shop")]));
+        projects.expect_list_requests().returning(|_| Ok(vec!["broken".to_string(), "users/get_user".to_string()]));
+        let mut requests = MockRequestRepository::new();
+        requests.expect_load().returning(|_, id| match id {
+            "broken" => Err(anyhow::anyhow!("Failed to parse")),
+            _ => Ok(Request::new("GET", "https://api.shop.com/users/1")),
+        });
+        let usecase = FindRequestUseCase::new(Arc::new(projects), Arc::new(requests));
+
+        let entries = usecase.index().unwrap();
+        let labels: Vec<String> = entries.iter().map(FinderEntry::label).collect();
+        assert_eq!(labels, vec!["shop", "shop/broken", "shop/users/get_user"]);
+        assert_eq!(entries[1].detail, None);
+        assert_eq!(entries[2].detail.as_deref(), Some("GET https://api.shop.com/users/1"));
+        assert_eq!(usecase.search(&entries, "api.shop", true)[0].index, 2);
+    }
+}
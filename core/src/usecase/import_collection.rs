@@ -0,0 +1,152 @@
+use crate::domain::{
+    collection::{file_name, Collection, CollectionItem},
+    project::Project,
+    repository::{CollectionRepository, EnvironmentRepository, ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// What an import created, and what it had to leave out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Requests written, as project and request id (`folder/request` inside folders).
+    pub requests: Vec<(Project, String)>,
+    /// How many of them replaced an existing request.
+    pub updated: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Recreates a Postman or Insomnia collection as `.http` files and `.env` variables.
+pub struct ImportCollectionUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    environments: Arc<dyn EnvironmentRepository>,
+    collections: Arc<dyn CollectionRepository>,
+}
+
+impl ImportCollectionUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        environments: Arc<dyn EnvironmentRepository>,
+        collections: Arc<dyn CollectionRepository>,
+    ) -> Self {
+        Self { projects, requests, environments, collections }
+    }
+
+    /// Imports into `project`, with folders as sub-directories. With `folders_as_projects`,
+    /// each top-level folder becomes a project of its own instead.
+    pub fn execute(&self, project: &Project, path: &Path, folders_as_projects: bool) -> Result<ImportReport> {
+        let collection = self.collections.read(path)?;
+        let mut report = ImportReport { warnings: collection.warnings.clone(), ..Default::default() };
+
+        let mut targets = vec![(project.clone(), Vec::new())];
+        for item in &collection.items {
+            match item {
+                CollectionItem::Folder { name, items } if folders_as_projects => targets.push((Project::new(file_name(name)), items.clone())),
+                item => targets[0].1.push(item.clone()),
+            }
+        }
+        // Variables go to every project, an environment export has nothing else
+        for (target, items) in &targets {
+            self.import_into(target, items, &collection, &mut report)?;
+        }
+        Ok(report)
+    }
+
+    fn import_into(&self, project: &Project, items: &[CollectionItem], collection: &Collection, report: &mut ImportReport) -> Result<()> {
+        if !self.projects.list_projects()?.iter().any(|p| p.name == project.name) {
+            self.projects.create_project(&project.name)?;
+        }
+        self.environments.add_variables(project, &collection.variables, false)?;
+        self.environments.add_variables(project, &collection.secrets, true)?;
+
+        let mut used: HashSet<String> = self.projects.list_requests(project)?.into_iter().collect();
+        self.save_items(project, "", items, &mut used, report)
+    }
+
+    fn save_items(&self, project: &Project, dir: &str, items: &[CollectionItem], used: &mut HashSet<String>, report: &mut ImportReport) -> Result<()> {
+        for item in items {
+            match item {
+                CollectionItem::Folder { name, items } => {
+                    let dir = unique(format!("{}{}", dir, file_name(name)), used);
+                    self.save_items(project, &format!("{}/", dir), items, used, report)?;
+                }
+                CollectionItem::Request { name, request } => {
+                    let id = unique(format!("{}{}", dir, file_name(name)), used);
+                    self.requests.save(project, &id, request)?;
+                    report.requests.push((project.clone(), id));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Appends `_2`, `_3`, ... when two items of a folder share a name.
+fn unique(id: String, used: &mut HashSet<String>) -> String {
+    let id = if used.contains(&id) { (2..).map(|n| format!("{}_{}", id, n)).find(|c| !used.contains(c)).unwrap_or(id) } else { id };
+    used.insert(id.clone());
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::{MockCollectionRepository, MockEnvironmentRepository, MockProjectRepository, MockRequestRepository};
+    use crate::domain::request::Request;
+    use mockall::predicate::*;
+
+    fn collection() -> Collection {
+        let request = |name: &str| CollectionItem::Request { name: name.to_string(), request: Box::new(Request::new("GET", "{{base_url}}")) };
+        Collection {
+            name: "Shop".to_string(),
+            items: vec![
+                CollectionItem::Folder { name: "Users".to_string(), items: vec![request("Get user"), request("Get user"), request("List")] },
+                request("Health check"),
+            ],
+            variables: vec![("base_url".to_string(), "https://api.shop.com".to_string())],
+            secrets: vec![],
+            warnings: vec!["Users / Get user: pre-request script not imported".to_string()],
+        }
+    }
+
+    fn usecase(projects: MockProjectRepository, expected_ids: Vec<&'static str>) -> ImportCollectionUseCase {
+        let mut collections = MockCollectionRepository::new();
+        collections.expect_read().returning(|_| Ok(collection()));
+        let mut environments = MockEnvironmentRepository::new();
+        environments.expect_add_variables().returning(|_, _, _| Ok(()));
+        let mut requests = MockRequestRepository::new();
+        requests.expect_save().times(expected_ids.len()).withf(move |_, id, _| expected_ids.contains(&id)).returning(|_, _, _| Ok(()));
+        ImportCollectionUseCase::new(Arc::new(projects), Arc::new(requests), Arc::new(environments), Arc::new(collections))
+    }
+
+    #[test]
+    fn test_import_folders_as_directories() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![]));
+        projects.expect_create_project().with(eq("shop")).times(1).returning(|_| Ok(()));
+        projects.expect_list_requests().returning(|_| Ok(vec!["health_check".to_string()]));
+
+        let usecase = usecase(projects, vec!["users/get_user", "users/get_user_2", "users/list", "health_check_2"]);
+        let report = usecase.execute(&Project::new("shop"), Path::new("shop.json"), false).unwrap();
+        let ids: Vec<&str> = report.requests.iter().map(|(_, id)| id.as_str()).collect();
+        assert_eq!(ids, vec!["users/get_user", "users/get_user_2", "users/list", "health_check_2"]);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_import_folders_as_projects() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("shop")]));
+        projects.expect_create_project().with(eq("users")).times(1).returning(|_| Ok(()));
+        projects.expect_list_requests().returning(|_| Ok(vec![]));
+
+        let usecase = usecase(projects, vec!["get_user", "get_user_2", "list", "health_check"]);
+        let report = usecase.execute(&Project::new("shop"), Path::new("shop.json"), true).unwrap();
+        let projects: Vec<&str> = report.requests.iter().map(|(p, _)| p.name.as_str()).collect();
+        assert_eq!(projects, vec!["shop", "users", "users", "users"]);
+    }
+}
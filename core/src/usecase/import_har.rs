@@ -0,0 +1,111 @@
+use crate::domain::{
+    capture::{extract_credentials, request_id, CaptureFilter},
+    project::Project,
+    repository::{EnvironmentRepository, HarRepository, ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Turns the entries of a HAR file into requests of a project.
+pub struct ImportHarUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    environments: Arc<dyn EnvironmentRepository>,
+    har: Arc<dyn HarRepository>,
+}
+
+impl ImportHarUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        environments: Arc<dyn EnvironmentRepository>,
+        har: Arc<dyn HarRepository>,
+    ) -> Self {
+        Self { projects, requests, environments, har }
+    }
+
+    /// Saves each entry matching `filter` as a `.http` file and returns the new request ids.
+    /// Cookies and credentials of the browser session are saved as secret variables.
+    pub fn execute(&self, project: &Project, path: &Path, filter: &CaptureFilter) -> Result<Vec<String>> {
+        let calls = self.har.read(path)?;
+        if !self.projects.list_projects()?.iter().any(|p| p.name == project.name) {
+            self.projects.create_project(&project.name)?;
+        }
+        let mut existing = self.projects.list_requests(project)?;
+        let mut environment = self.environments.load(project)?;
+        let (mut imported, mut secrets) = (Vec::new(), Vec::new());
+        for archived in calls.iter().filter(|a| filter.matches(&a.call)) {
+            let mut request = archived.call.request.clone();
+            for (name, value) in extract_credentials(&mut request, &environment) {
+                environment = environment.with_secret(name.clone(), value.clone());
+                secrets.push((name, value));
+            }
+            let id = request_id(&request.method, &request.url, &existing);
+            self.requests.save(project, &id, &request)?;
+            existing.push(id.clone());
+            imported.push(id);
+        }
+        self.environments.add_variables(project, &secrets, true)?;
+        Ok(imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::capture::{ArchivedCall, CapturedCall};
+    use crate::domain::environment::Environment;
+    use crate::domain::repository::{MockEnvironmentRepository, MockHarRepository, MockProjectRepository, MockRequestRepository};
+    use crate::domain::request::Request;
+    use crate::domain::response::Response;
+    use std::time::{Duration, SystemTime};
+
+    fn archived(method: &str, url: &str) -> ArchivedCall {
+        let mut request = Request::new(method, url);
+        request.headers.insert("Cookie".to_string(), format!("sid={}", url.len()));
+        ArchivedCall {
+            started: SystemTime::now(),
+            call: CapturedCall {
+                request,
+                response: Response::new(200, "OK".to_string(), String::new(), Duration::ZERO),
+            },
+        }
+    }
+
+    #[test]
+    fn test_import_filters_and_names_entries() {
+        let mut har = MockHarRepository::new();
+        har.expect_read().returning(|_| {
+            Ok(vec![
+                archived("GET", "https://api.example.com/users"),
+                archived("GET", "https://cdn.example.com/app.js"),
+                archived("GET", "https://api.example.com/users"),
+                archived("DELETE", "https://api.example.com/users/1"),
+            ])
+        });
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("imported")]));
+        projects.expect_list_requests().returning(|_| Ok(vec![]));
+        let mut requests = MockRequestRepository::new();
+        requests
+            .expect_save()
+            .withf(|_, _, request| request.headers["Cookie"].starts_with("{{cookie"))
+            .times(3)
+            .returning(|_, _, _| Ok(()));
+        let mut environments = MockEnvironmentRepository::new();
+        environments.expect_load().returning(|_| Ok(Environment::default()));
+        environments
+            .expect_add_variables()
+            .withf(|_, variables, secret| {
+                *secret && variables == [("cookie".to_string(), "sid=29".to_string()), ("cookie_2".to_string(), "sid=31".to_string())]
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let usecase = ImportHarUseCase::new(Arc::new(projects), Arc::new(requests), Arc::new(environments), Arc::new(har));
+        let filter = CaptureFilter { hosts: vec!["api.example.com".to_string()], ..Default::default() };
+        let ids = usecase.execute(&Project::new("imported"), Path::new("session.har"), &filter).unwrap();
+        assert_eq!(ids, vec!["get_users", "get_users_2", "delete_users_1"]);
+    }
+}
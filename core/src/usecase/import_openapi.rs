@@ -0,0 +1,123 @@
+use crate::domain::{
+    openapi::BASE_URL_VARIABLE,
+    project::Project,
+    repository::{ApiSpecRepository, EnvironmentRepository, ProjectRepository, RequestRepository},
+};
+use crate::usecase::{create_project::CreateProjectUseCase, import_collection::ImportReport};
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Scaffolds one request per operation of an OpenAPI document.
+pub struct ImportOpenApiUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    create_project: CreateProjectUseCase,
+    requests: Arc<dyn RequestRepository>,
+    environments: Arc<dyn EnvironmentRepository>,
+    specs: Arc<dyn ApiSpecRepository>,
+}
+
+impl ImportOpenApiUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        environments: Arc<dyn EnvironmentRepository>,
+        specs: Arc<dyn ApiSpecRepository>,
+    ) -> Self {
+        let create_project = CreateProjectUseCase::new(projects.clone());
+        Self { projects, create_project, requests, environments, specs }
+    }
+
+    /// Whether `path` is a document this use case imports.
+    pub fn accepts(&self, path: &Path) -> bool {
+        self.specs.is_spec(path)
+    }
+
+    /// Writes the requests of every operation into `project`, keeping a copy of the document.
+    /// Requests are named after their operation, so importing again updates them in place.
+    pub fn execute(&self, project: &Project, path: &Path) -> Result<ImportReport> {
+        let spec = self.specs.read(path)?;
+        if !self.projects.list_projects()?.iter().any(|p| p.name == project.name) {
+            self.create_project.execute(&project.name)?;
+        }
+        self.specs.save(project, path)?;
+
+        let existing = self.projects.list_requests(project)?;
+        let mut variables = vec![(BASE_URL_VARIABLE.to_string(), spec.base_url.clone().unwrap_or_default())];
+        let mut secrets = Vec::new();
+        let mut report = ImportReport::default();
+        for operation in &spec.operations {
+            self.requests.save(project, &operation.id, &operation.to_request())?;
+            if existing.contains(&operation.id) {
+                report.updated += 1;
+            }
+            report.requests.push((project.clone(), operation.id.clone()));
+            variables.extend(operation.variables());
+            secrets.extend(operation.secrets());
+        }
+        if spec.base_url.is_none() {
+            report.warnings.push(format!("No server declared, set {} in the .env file", BASE_URL_VARIABLE));
+        }
+        // Variables the user already set keep their value
+        self.environments.add_variables(project, &variables, false)?;
+        self.environments.add_variables(project, &secrets, true)?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::openapi::{ApiSpec, Operation, Parameter, ParameterLocation};
+    use crate::domain::repository::{MockApiSpecRepository, MockEnvironmentRepository, MockProjectRepository, MockRequestRepository};
+    use mockall::predicate::*;
+    use serde_json::json;
+
+    fn operation(id: &str, path: &str) -> Operation {
+        Operation { id: id.to_string(), method: "GET".to_string(), path: path.to_string(), parameters: vec![], request_body: None, security: None, responses: vec![] }
+    }
+
+    #[test]
+    fn test_import_updates_existing_requests() {
+        let mut specs = MockApiSpecRepository::new();
+        specs.expect_read().returning(|_| {
+            let mut get_user = operation("get_user", "/users/{id}");
+            get_user.parameters.push(Parameter {
+                name: "id".to_string(),
+                location: ParameterLocation::Path,
+                required: true,
+                schema: json!({ "type": "integer" }),
+                example: Some(json!(1)),
+            });
+            Ok(ApiSpec { title: "Shop".to_string(), base_url: Some("https://api.shop.com".to_string()), operations: vec![operation("list_users", "/users"), get_user] })
+        });
+        specs.expect_save().times(1).returning(|_, _| Ok(()));
+
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("shop")]));
+        projects.expect_create_project().never();
+        projects.expect_list_requests().returning(|_| Ok(vec!["list_users".to_string()]));
+
+        let mut requests = MockRequestRepository::new();
+        requests.expect_save().with(always(), eq("list_users"), always()).times(1).returning(|_, _, _| Ok(()));
+        requests
+            .expect_save()
+            .withf(|_, id, request| id == "get_user" && request.url == "{{baseUrl}}/users/{{id}}")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut environments = MockEnvironmentRepository::new();
+        environments
+            .expect_add_variables()
+            .with(always(), eq(vec![("baseUrl".to_string(), "https://api.shop.com".to_string()), ("id".to_string(), "1".to_string())]), eq(false))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        environments.expect_add_variables().with(always(), always(), eq(true)).times(1).returning(|_, _, _| Ok(()));
+
+        let usecase = ImportOpenApiUseCase::new(Arc::new(projects), Arc::new(requests), Arc::new(environments), Arc::new(specs));
+        let report = usecase.execute(&Project::new("shop"), Path::new("openapi.yaml")).unwrap();
+        assert_eq!(report.requests.len(), 2);
+        assert_eq!(report.updated, 1);
+        assert!(report.warnings.is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::domain::{
+    body::Body,
+    environment::Environment,
+    graphql::{GraphQLSchema, INTROSPECTION_QUERY},
+    project::Project,
+    repository::SchemaRepository,
+    request::Request,
+};
+use crate::usecase::execute_request::ExecuteRequestUseCase;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+/// Fetches the GraphQL schema of an endpoint and caches it per project.
+pub struct IntrospectSchemaUseCase {
+    execute_request: Arc<ExecuteRequestUseCase>,
+    repo: Arc<dyn SchemaRepository>,
+}
+
+impl IntrospectSchemaUseCase {
+    pub fn new(execute_request: Arc<ExecuteRequestUseCase>, repo: Arc<dyn SchemaRepository>) -> Self {
+        Self { execute_request, repo }
+    }
+
+    /// Sends the introspection query to the endpoint of `request`, reusing its headers and auth.
+    pub async fn execute(&self, project: &Project, request: &Request, environment: &Environment) -> Result<GraphQLSchema> {
+        let mut introspection = request.clone();
+        introspection.method = "POST".to_string();
+        introspection.body = Some(Body::GraphQL { query: INTROSPECTION_QUERY.to_string(), variables: None });
+
+        let response = self.execute_request.execute(&introspection, environment).await?;
+        if !(200..300).contains(&response.status) {
+            anyhow::bail!("Introspection failed: {} {}", response.status, response.status_text);
+        }
+        if let Some(error) = response.graphql_errors.first() {
+            anyhow::bail!("Introspection failed: {}", error);
+        }
+
+        // A body that is not a schema must not replace a working cache
+        let schema = GraphQLSchema::from_introspection(&response.body).context("Introspection failed")?;
+        self.repo.save(project, &response.body)?;
+        Ok(schema)
+    }
+
+    /// Returns the cached schema of a project, if any.
+    pub fn load(&self, project: &Project) -> Result<Option<GraphQLSchema>> {
+        self.repo.load(project)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::{MockHttpClient, MockSchemaRepository};
+    use crate::domain::response::Response;
+    use std::time::Duration;
+
+    const INTROSPECTION: &str = r#"{"data":{"__schema":{"queryType":{"name":"Query"},"types":[]}}}"#;
+
+    fn client(body: &'static str) -> MockHttpClient {
+        let mut client = MockHttpClient::new();
+        client
+            .expect_send()
+            .withf(|req| {
+                req.method == "POST"
+                    && req.headers.get("Authorization").map(String::as_str) == Some("Bearer t")
+                    && matches!(&req.body, Some(Body::GraphQL { query, .. }) if query == INTROSPECTION_QUERY)
+            })
+            .times(1)
+            .returning(move |_| Ok(Response::new(200, "OK".to_string(), body.to_string(), Duration::ZERO)));
+        client
+    }
+
+    fn request() -> Request {
+        let mut request = Request::new("GET", "https://api.example.com/graphql");
+        request.headers.insert("Authorization".to_string(), "Bearer t".to_string());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_introspection_is_cached() {
+        let mut repo = MockSchemaRepository::new();
+        repo.expect_save().withf(|_, body| body == INTROSPECTION).times(1).returning(|_, _| Ok(()));
+        let usecase = IntrospectSchemaUseCase::new(Arc::new(ExecuteRequestUseCase::new(Arc::new(client(INTROSPECTION)))), Arc::new(repo));
+
+        let schema = usecase.execute(&Project::new("api"), &request(), &Environment::default()).await.unwrap();
+        assert_eq!(schema, GraphQLSchema { query_type: "Query".to_string(), ..Default::default() });
+    }
+
+    #[tokio::test]
+    async fn test_bad_introspection_keeps_the_cache() {
+        for body in ["<html>Sign in</html>", r#"{"data":null}"#] {
+            let mut repo = MockSchemaRepository::new();
+            repo.expect_save().times(0);
+            let usecase = IntrospectSchemaUseCase::new(Arc::new(ExecuteRequestUseCase::new(Arc::new(client(body)))), Arc::new(repo));
+
+            let error = usecase.execute(&Project::new("api"), &request(), &Environment::default()).await.unwrap_err();
+            assert!(format!("{:#}", error).starts_with("Introspection failed: Introspection response"), "{:#}", error);
+        }
+    }
+}
@@ -21,4 +21,8 @@ impl ListProjectsUseCase {
     pub fn list_requests(&self, project: &Project) -> Result<Vec<String>> {
         self.repo.list_requests(project)
     }
+
+    pub fn list_folders(&self, project: &Project) -> Result<Vec<String>> {
+        self.repo.list_folders(project)
+    }
 }
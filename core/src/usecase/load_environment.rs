@@ -0,0 +1,21 @@
+use crate::domain::{
+    environment::Environment,
+    project::Project,
+    repository::EnvironmentRepository,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+pub struct LoadEnvironmentUseCase {
+    repo: Arc<dyn EnvironmentRepository>,
+}
+
+impl LoadEnvironmentUseCase {
+    pub fn new(repo: Arc<dyn EnvironmentRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub fn execute(&self, project: &Project) -> Result<Environment> {
+        self.repo.load(project)
+    }
+}
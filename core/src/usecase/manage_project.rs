@@ -0,0 +1,61 @@
+use crate::domain::{project::Project, repository::ProjectRepository};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Renames and duplicates projects.
+pub struct ManageProjectUseCase {
+    projects: Arc<dyn ProjectRepository>,
+}
+
+impl ManageProjectUseCase {
+    pub fn new(projects: Arc<dyn ProjectRepository>) -> Self {
+        Self { projects }
+    }
+
+    pub fn rename(&self, project: &Project, to: &str) -> Result<Project> {
+        let to = self.check_name(to)?;
+        self.projects.rename_project(project, to)?;
+        Ok(Project::new(to))
+    }
+
+    /// Copies a project with its requests and environment under a new name.
+    pub fn duplicate(&self, project: &Project, to: &str) -> Result<Project> {
+        let to = self.check_name(to)?;
+        self.projects.duplicate_project(project, to)?;
+        Ok(Project::new(to))
+    }
+
+    fn check_name<'a>(&self, name: &'a str) -> Result<&'a str> {
+        let name = name.trim();
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            anyhow::bail!("Invalid project name '{}'", name);
+        }
+        if self.projects.list_projects()?.iter().any(|p| p.name == name) {
+            anyhow::bail!("Project '{}' already exists", name);
+        }
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::MockProjectRepository;
+    use mockall::predicate::*;
+
+    #[test]
+    fn test_rename_and_duplicate() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("shop"), Project::new("blog")]));
+        projects.expect_rename_project().with(eq(Project::new("shop")), eq("store")).times(1).returning(|_, _| Ok(()));
+        projects.expect_duplicate_project().with(eq(Project::new("shop")), eq("shop-v2")).times(1).returning(|_, _| Ok(()));
+        let usecase = ManageProjectUseCase::new(Arc::new(projects));
+        let shop = Project::new("shop");
+
+        assert_eq!(usecase.rename(&shop, " store ").unwrap(), Project::new("store"));
+        assert_eq!(usecase.duplicate(&shop, "shop-v2").unwrap(), Project::new("shop-v2"));
+        assert_eq!(usecase.rename(&shop, "blog").unwrap_err().to_string(), "Project 'blog' already exists");
+        assert!(usecase.duplicate(&shop, "a/b").is_err());
+        assert!(usecase.duplicate(&shop, ".trash").is_err());
+    }
+}
@@ -3,3 +3,24 @@ pub mod list_projects;
 pub mod create_project;
 pub mod create_request;
 pub mod edit_request;
+pub mod load_environment;
+pub mod acquire_token;
+pub mod introspect_schema;
+pub mod websocket_session;
+pub mod stream_response;
+pub mod call_grpc;
+pub mod diff_responses;
+pub mod snapshot_response;
+pub mod serve_mock;
+pub mod record_traffic;
+pub mod import_har;
+pub mod export_har;
+pub mod import_collection;
+pub mod import_openapi;
+pub mod validate_response;
+pub mod move_request;
+pub mod manage_project;
+pub mod delete;
+pub mod find_request;
+pub mod search_requests;
+pub mod watch_projects;
@@ -0,0 +1,138 @@
+use crate::domain::{
+    project::Project,
+    repository::{ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Creates folders and renames, moves or duplicates requests and folders within a project.
+pub struct MoveRequestUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+}
+
+impl MoveRequestUseCase {
+    pub fn new(projects: Arc<dyn ProjectRepository>, requests: Arc<dyn RequestRepository>) -> Self {
+        Self { projects, requests }
+    }
+
+    pub fn create_folder(&self, project: &Project, path: &str) -> Result<()> {
+        let path = validate_path(path)?;
+        if self.projects.list_folders(project)?.iter().any(|f| f == path) {
+            anyhow::bail!("Folder '{}' already exists", path);
+        }
+        self.projects.create_folder(project, path)
+    }
+
+    /// Renames a request; a path with another folder (`admin/get_user`) moves it there.
+    pub fn move_request(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let to = validate_path(to)?;
+        if from == to {
+            return Ok(());
+        }
+        self.check_request_path(project, to)?;
+        self.requests.rename(project, from, to)
+    }
+
+    /// Copies a request to a new path, e.g. `users/get_user` to `users/get_user_copy`.
+    pub fn duplicate_request(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let to = validate_path(to)?;
+        self.check_request_path(project, to)?;
+        self.requests.duplicate(project, from, to)
+    }
+
+    fn check_request_path(&self, project: &Project, path: &str) -> Result<()> {
+        if self.projects.list_requests(project)?.iter().any(|r| r == path) {
+            anyhow::bail!("Request '{}' already exists", path);
+        }
+        if self.projects.list_folders(project)?.iter().any(|f| f == path) {
+            anyhow::bail!("'{}' is a folder", path);
+        }
+        Ok(())
+    }
+
+    /// Renames or moves a folder with the requests inside it.
+    pub fn move_folder(&self, project: &Project, from: &str, to: &str) -> Result<()> {
+        let to = validate_path(to)?;
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(&format!("{}/", from)) {
+            anyhow::bail!("Cannot move '{}' into itself", from);
+        }
+        if self.projects.list_folders(project)?.iter().any(|f| f == to) {
+            anyhow::bail!("Folder '{}' already exists", to);
+        }
+        if self.projects.list_requests(project)?.iter().any(|r| r == to) {
+            anyhow::bail!("'{}' is a request", to);
+        }
+        self.projects.rename_folder(project, from, to)
+    }
+}
+
+/// A relative path inside the project: no empty, `..` or hidden segments.
+fn validate_path(path: &str) -> Result<&str> {
+    let path = path.trim().trim_matches('/');
+    if path.is_empty() {
+        anyhow::bail!("Name must not be empty");
+    }
+    if let Some(segment) = path.split('/').find(|s| s.is_empty() || s.starts_with('.') || s.contains('\\')) {
+        anyhow::bail!("Invalid name '{}' in '{}'", segment, path);
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::{MockProjectRepository, MockRequestRepository};
+    use mockall::predicate::*;
+
+    fn projects() -> MockProjectRepository {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_requests().returning(|_| Ok(vec!["health".to_string(), "users/get_user".to_string()]));
+        projects.expect_list_folders().returning(|_| Ok(vec!["admin".to_string(), "users".to_string()]));
+        projects
+    }
+
+    #[test]
+    fn test_move_request() {
+        let mut requests = MockRequestRepository::new();
+        requests.expect_rename().with(always(), eq("users/get_user"), eq("admin/get_user")).times(1).returning(|_, _, _| Ok(()));
+        let usecase = MoveRequestUseCase::new(Arc::new(projects()), Arc::new(requests));
+        let project = Project::new("shop");
+
+        usecase.move_request(&project, "users/get_user", "/admin/get_user ").unwrap();
+        let conflict = usecase.move_request(&project, "users/get_user", "health").unwrap_err();
+        assert_eq!(conflict.to_string(), "Request 'health' already exists");
+        assert!(usecase.move_request(&project, "health", "users").is_err());
+        assert!(usecase.move_request(&project, "health", "../outside").is_err());
+    }
+
+    #[test]
+    fn test_duplicate_request() {
+        let mut requests = MockRequestRepository::new();
+        requests.expect_duplicate().with(always(), eq("health"), eq("users/health")).times(1).returning(|_, _, _| Ok(()));
+        let usecase = MoveRequestUseCase::new(Arc::new(projects()), Arc::new(requests));
+        let project = Project::new("shop");
+
+        usecase.duplicate_request(&project, "health", "users/health").unwrap();
+        assert_eq!(usecase.duplicate_request(&project, "health", "health").unwrap_err().to_string(), "Request 'health' already exists");
+        assert_eq!(usecase.duplicate_request(&project, "health", "admin").unwrap_err().to_string(), "'admin' is a folder");
+    }
+
+    #[test]
+    fn test_move_folder() {
+        let mut projects = projects();
+        projects.expect_rename_folder().with(always(), eq("users"), eq("admin/users")).times(1).returning(|_, _, _| Ok(()));
+        projects.expect_create_folder().with(always(), eq("orders")).times(1).returning(|_, _| Ok(()));
+        let usecase = MoveRequestUseCase::new(Arc::new(projects), Arc::new(MockRequestRepository::new()));
+        let project = Project::new("shop");
+
+        usecase.move_folder(&project, "users", "admin/users").unwrap();
+        assert!(usecase.move_folder(&project, "users", "admin").is_err());
+        assert_eq!(usecase.move_folder(&project, "users", "users/old").unwrap_err().to_string(), "Cannot move 'users' into itself");
+        usecase.create_folder(&project, "orders").unwrap();
+        assert!(usecase.create_folder(&project, "users").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::domain::{
+    capture::{extract_credentials, request_id, CaptureFilter, CapturedCall, UNRECORDED_RESPONSE_HEADERS},
+    mock::MockResponse,
+    project::Project,
+    repository::{CaptureSink, EnvironmentRepository, MockRepository, ProjectRepository, ProxyServer, RequestRepository},
+};
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Records the calls passing through a local proxy as requests of a project.
+pub struct RecordTrafficUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    mocks: Arc<dyn MockRepository>,
+    environments: Arc<dyn EnvironmentRepository>,
+    server: Arc<dyn ProxyServer>,
+}
+
+impl RecordTrafficUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        mocks: Arc<dyn MockRepository>,
+        environments: Arc<dyn EnvironmentRepository>,
+        server: Arc<dyn ProxyServer>,
+    ) -> Self {
+        Self { projects, requests, mocks, environments, server }
+    }
+
+    /// Proxies calls on `address` until accepting fails, reporting each one to `sink`.
+    pub async fn serve(&self, address: SocketAddr, upstream: Option<String>, sink: Arc<dyn CaptureSink>) -> Result<()> {
+        self.server.serve(address, upstream, sink).await
+    }
+
+    /// Saves a call as a new `.http` file, and its response as a `.mock` file for replay when
+    /// `record_responses` is set. Returns the new request id, or `None` when `filter` skips the call.
+    /// Credentials are saved as secret variables, and cookies set by the response are dropped.
+    pub fn record(&self, project: &Project, call: &CapturedCall, filter: &CaptureFilter, record_responses: bool) -> Result<Option<String>> {
+        if !filter.matches(call) {
+            return Ok(None);
+        }
+        if !self.projects.list_projects()?.iter().any(|p| p.name == project.name) {
+            self.projects.create_project(&project.name)?;
+        }
+        let existing = self.projects.list_requests(project)?;
+        let id = request_id(&call.request.method, &call.request.url, &existing);
+        let mut request = call.request.clone();
+        let secrets = extract_credentials(&mut request, &self.environments.load(project)?);
+        self.environments.add_variables(project, &secrets, true)?;
+        self.requests.save(project, &id, &request)?;
+        if record_responses {
+            let response = MockResponse {
+                status: call.response.status,
+                headers: call
+                    .response
+                    .headers
+                    .iter()
+                    .filter(|(k, _)| !UNRECORDED_RESPONSE_HEADERS.contains(&k.to_lowercase().as_str()))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                body: call.response.body.clone(),
+                delay: None,
+            };
+            self.mocks.save(project, &id, &response)?;
+        }
+        Ok(Some(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::environment::Environment;
+    use crate::domain::repository::{
+        MockEnvironmentRepository, MockMockRepository, MockProjectRepository, MockProxyServer, MockRequestRepository,
+    };
+    use crate::domain::request::Request;
+    use crate::domain::response::Response;
+    use mockall::predicate::*;
+    use std::time::Duration;
+
+    fn call(url: &str) -> CapturedCall {
+        let mut request = Request::new("GET", url);
+        request.headers.insert("authorization".to_string(), "Bearer abc".to_string());
+        let mut response = Response::new(200, "OK".to_string(), "[]".to_string(), Duration::ZERO);
+        response.headers.insert("set-cookie".to_string(), "sid=1".to_string());
+        CapturedCall { request, response }
+    }
+
+    #[test]
+    fn test_record_names_requests_and_saves_responses() {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![]));
+        projects.expect_create_project().with(eq("captured")).times(1).returning(|_| Ok(()));
+        projects.expect_list_requests().returning(|_| Ok(vec!["get_users".to_string()]));
+        let mut requests = MockRequestRepository::new();
+        requests
+            .expect_save()
+            .withf(|_, id, request| {
+                id == "get_users_2" && request.url == "https://api.example.com/users" && request.headers["authorization"] == "{{authorization}}"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let mut mocks = MockMockRepository::new();
+        mocks
+            .expect_save()
+            .withf(|_, id, mock| id == "get_users_2" && mock.body == "[]" && mock.headers.is_empty())
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        let mut environments = MockEnvironmentRepository::new();
+        environments.expect_load().returning(|_| Ok(Environment::default()));
+        environments
+            .expect_add_variables()
+            .with(always(), eq(vec![("authorization".to_string(), "Bearer abc".to_string())]), eq(true))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let usecase = RecordTrafficUseCase::new(
+            Arc::new(projects),
+            Arc::new(requests),
+            Arc::new(mocks),
+            Arc::new(environments),
+            Arc::new(MockProxyServer::new()),
+        );
+        let filter = CaptureFilter { hosts: vec!["api.example.com".to_string()], ..Default::default() };
+        let project = Project::new("captured");
+
+        let id = usecase.record(&project, &call("https://api.example.com/users"), &filter, true).unwrap();
+        assert_eq!(id.as_deref(), Some("get_users_2"));
+        assert_eq!(usecase.record(&project, &call("https://cdn.example.com/app.js"), &filter, true).unwrap(), None);
+    }
+}
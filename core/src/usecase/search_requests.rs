@@ -0,0 +1,94 @@
+use crate::domain::{
+    repository::{PatternCompiler, ProjectRepository, RequestRepository},
+    search::{self, SearchHit},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Searches the text of every request file: method, URL, headers, body and directives.
+pub struct SearchRequestsUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    compiler: Arc<dyn PatternCompiler>,
+}
+
+impl SearchRequestsUseCase {
+    pub fn new(projects: Arc<dyn ProjectRepository>, requests: Arc<dyn RequestRepository>, compiler: Arc<dyn PatternCompiler>) -> Self {
+        Self { projects, requests, compiler }
+    }
+
+    /// One hit per matching line, by project, request and line. Plain text ignores case.
+    pub fn execute(&self, query: &str, regex: bool) -> Result<Vec<SearchHit>> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let matcher = self.compiler.compile(query, regex)?;
+        let mut hits = Vec::new();
+        for project in self.projects.list_projects()? {
+            for request_id in self.projects.list_requests(&project)? {
+                // A file removed since the listing is skipped
+                let Ok(source) = self.requests.read_source(&project, &request_id) else { continue };
+                for (line, text, ranges) in search::matching_lines(matcher.as_ref(), &source) {
+                    hits.push(SearchHit { project: project.clone(), request_id: request_id.clone(), line, text: text.to_string(), ranges });
+                }
+            }
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{project::Project, repository::{MockPatternCompiler, MockProjectRepository, MockRequestRepository}, search::Matcher};
+    use std::ops::Range;
+
+    struct Text(String);
+
+    impl Matcher for Text {
+        fn find(&self, line: &str) -> Vec<Range<usize>> {
+            line.match_indices(self.0.as_str()).map(|(i, m)| i..i + m.len()).collect()
+        }
+    }
+
+    fn usecase() -> SearchRequestsUseCase {
+        let mut projects = MockProjectRepository::new();
+        projects.expect_list_projects().returning(|| Ok(vec![Project::new("blog"), Project::new("shop")]));
+        projects.expect_list_requests().returning(|p| match p.name.as_str() {
+            "shop" => Ok(vec!["list_orders".to_string(), "users/get_user".to_string()]),
+            _ => Ok(vec!["posts".to_string()]),
+        });
+        let mut requests = MockRequestRepository::new();
+        requests.expect_read_source().returning(|_, id| match id {
+            "list_orders" => Ok("GET {{baseUrl}}/v2/orders\nAccept: application/json".to_string()),
+            "users/get_user" => Ok("GET {{baseUrl}}/v1/users/1\nAccept: application/json".to_string()),
+            _ => Ok("POST https://blog.dev/posts\nContent-Type: application/json\n\n{\"order\": 1}".to_string()),
+        });
+        let mut compiler = MockPatternCompiler::new();
+        compiler.expect_compile().returning(|query, regex| match regex {
+            true => Err(anyhow::anyhow!("Invalid regex: {}", query)),
+            false => Ok(Box::new(Text(query.to_string()))),
+        });
+        SearchRequestsUseCase::new(Arc::new(projects), Arc::new(requests), Arc::new(compiler))
+    }
+
+    #[test]
+    fn test_search_every_request() {
+        let usecase = usecase();
+
+        let hits = usecase.execute("/v2/orders", false).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].request_id.as_str(), hits[0].line, hits[0].ranges.clone()), ("list_orders", 1, vec![Range { start: 15, end: 25 }]));
+
+        let hits = usecase.execute("application/json", false).unwrap();
+        let found: Vec<(&str, usize)> = hits.iter().map(|h| (h.request_id.as_str(), h.line)).collect();
+        assert_eq!(found, vec![("posts", 2), ("list_orders", 2), ("users/get_user", 2)]);
+
+        assert!(usecase.execute("", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_query() {
+        assert_eq!(usecase().execute("[", true).unwrap_err().to_string(), "Invalid regex: [");
+    }
+}
@@ -0,0 +1,135 @@
+use crate::domain::{
+    body::Body,
+    mock::{form_body, url_path, url_query, MockOptions, MockResponse, MockRoute, MockRouter},
+    project::Project,
+    repository::{HistoryRepository, MockHitSink, MockRepository, MockServer, ProjectRepository, RequestRepository},
+};
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Serves the requests of a project from a local HTTP server, answering with example responses.
+pub struct ServeMockUseCase {
+    projects: Arc<dyn ProjectRepository>,
+    requests: Arc<dyn RequestRepository>,
+    mocks: Arc<dyn MockRepository>,
+    history: Arc<dyn HistoryRepository>,
+    server: Arc<dyn MockServer>,
+}
+
+impl ServeMockUseCase {
+    pub fn new(
+        projects: Arc<dyn ProjectRepository>,
+        requests: Arc<dyn RequestRepository>,
+        mocks: Arc<dyn MockRepository>,
+        history: Arc<dyn HistoryRepository>,
+        server: Arc<dyn MockServer>,
+    ) -> Self {
+        Self { projects, requests, mocks, history, server }
+    }
+
+    /// Builds the routes of a project. A `.mock` file wins over the latest recorded response.
+    /// With `exact`, a literal query or body in the request file must be matched too, so that recorded calls replay exactly.
+    pub fn router(&self, project: &Project, options: MockOptions) -> Result<MockRouter> {
+        let mut routes = Vec::new();
+        for request_id in self.projects.list_requests(project)? {
+            let request = self.requests.load(project, &request_id)?;
+            if request.is_websocket() || request.is_grpc() {
+                continue;
+            }
+            let response = match self.mocks.load(project, &request_id)? {
+                Some(mock) => Some(mock),
+                None => self.history.list(project, &request_id)?.pop().map(|entry| MockResponse {
+                    status: entry.response.status,
+                    headers: entry.response.headers,
+                    body: entry.response.body,
+                    delay: None,
+                }),
+            };
+            let body = match &request.body {
+                _ if !options.exact => None,
+                Some(Body::Text(text)) if !text.contains("{{") => Some(text.clone()),
+                Some(Body::Form(fields)) if !fields.iter().any(|(k, v)| k.contains("{{") || v.contains("{{")) => Some(form_body(fields)),
+                _ => None,
+            };
+            let path = url_path(&request.url);
+            let query = url_query(&request.url).filter(|_| options.exact);
+            routes.push(MockRoute { request_id, method: request.method, path, query, body, response });
+        }
+        Ok(MockRouter::new(routes, options))
+    }
+
+    /// Serves `router` on `address` until accepting fails, reporting every answered call to `sink`.
+    pub async fn serve(&self, address: SocketAddr, router: MockRouter, sink: Arc<dyn MockHitSink>) -> Result<()> {
+        self.server.serve(address, Arc::new(router), sink).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::history::HistoryEntry;
+    use crate::domain::mock::MockCall;
+    use crate::domain::repository::{
+        MockHistoryRepository, MockMockRepository, MockMockServer, MockProjectRepository, MockRequestRepository,
+    };
+    use crate::domain::request::Request;
+    use crate::domain::response::Response;
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_router_prefers_mock_files_over_history() {
+        let mut projects = MockProjectRepository::new();
+        projects
+            .expect_list_requests()
+            .returning(|_| Ok(vec!["get_user".to_string(), "list_users".to_string(), "events".to_string()]));
+        let mut requests = MockRequestRepository::new();
+        requests.expect_load().returning(|_, id| {
+            Ok(match id {
+                "get_user" => Request::new("GET", "{{base_url}}/users/{{id}}"),
+                "list_users" => Request::new("GET", "{{base_url}}/users?page=1"),
+                _ => Request::new("GET", "wss://example.com/events"),
+            })
+        });
+        let mut mocks = MockMockRepository::new();
+        mocks.expect_load().returning(|_, id| {
+            Ok((id == "get_user").then(|| MockResponse {
+                status: 200,
+                headers: HashMap::new(),
+                body: r#"{"id": "{{id}}"}"#.to_string(),
+                delay: Some(Duration::from_millis(5)),
+            }))
+        });
+        let mut history = MockHistoryRepository::new();
+        history.expect_list().returning(|_, _| {
+            let entry = |body: &str| HistoryEntry {
+                timestamp: SystemTime::now(),
+                request: None,
+                response: Response::new(200, "OK".to_string(), body.to_string(), Duration::ZERO),
+            };
+            Ok(vec![entry("old"), entry("[]")])
+        });
+
+        let usecase = ServeMockUseCase::new(
+            Arc::new(projects),
+            Arc::new(requests),
+            Arc::new(mocks),
+            Arc::new(history),
+            Arc::new(MockMockServer::new()),
+        );
+        let router = usecase.router(&Project::new("api"), MockOptions::default()).unwrap();
+
+        assert_eq!(router.routes().len(), 2);
+        let call = |path: &str, query: &str| MockCall { method: "GET".to_string(), path: path.to_string(), query: query.to_string(), ..Default::default() };
+        assert_eq!(router.respond(&call("/users/3", "")).body, r#"{"id": "3"}"#);
+        assert_eq!(router.respond(&call("/users", "page=1")).body, "[]");
+        // Mocking matches the method and path only
+        assert_eq!(router.respond(&call("/users", "page=2")).body, "[]");
+
+        // Replaying also wants the recorded query
+        let router = usecase.router(&Project::new("api"), MockOptions { exact: true, ..Default::default() }).unwrap();
+        assert_eq!(router.respond(&call("/users", "page=1")).body, "[]");
+        assert_eq!(router.respond(&call("/users", "page=2")).status, 404);
+    }
+}
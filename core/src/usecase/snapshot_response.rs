@@ -0,0 +1,68 @@
+use crate::domain::{
+    environment::Environment,
+    project::Project,
+    repository::SnapshotRepository,
+    request::Request,
+    response::Response,
+    snapshot::{Snapshot, SnapshotResult},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Compares responses with the approved snapshot of their request, and approves new ones.
+pub struct SnapshotResponseUseCase {
+    repo: Arc<dyn SnapshotRepository>,
+}
+
+impl SnapshotResponseUseCase {
+    pub fn new(repo: Arc<dyn SnapshotRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub fn check(&self, project: &Project, request_id: &str, request: &Request, response: &Response, environment: &Environment) -> Result<SnapshotResult> {
+        let actual = capture(request, response, environment);
+        Ok(SnapshotResult::compare(self.repo.load(project, request_id)?, actual))
+    }
+
+    /// Approves a response as the new snapshot of its request.
+    pub fn accept(&self, project: &Project, request_id: &str, request: &Request, response: &Response, environment: &Environment) -> Result<Snapshot> {
+        let snapshot = capture(request, response, environment);
+        self.repo.save(project, request_id, &snapshot)?;
+        Ok(snapshot)
+    }
+}
+
+/// Snapshots are committed, so secrets are masked along with the volatile fields.
+fn capture(request: &Request, response: &Response, environment: &Environment) -> Snapshot {
+    Snapshot::capture(&environment.redact_response(response), &request.snapshot_redact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::MockSnapshotRepository;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[test]
+    fn test_accept_then_check() {
+        let env = Environment::new(HashMap::new(), HashMap::from([("token".to_string(), "s3cr3t".to_string())]));
+        let mut request = Request::new("POST", "https://api.com/login");
+        request.snapshot_redact = vec!["$.requestId".to_string()];
+        let response = Response::new(200, "OK".to_string(), r#"{"token": "s3cr3t", "requestId": 1}"#.to_string(), Duration::ZERO);
+
+        let expected = "{\n  \"requestId\": \"[redacted]\",\n  \"token\": \"******\"\n}";
+        let mut repo = MockSnapshotRepository::new();
+        repo.expect_save().withf(move |_, id, s| id == "login" && s.body == expected).times(1).returning(|_, _, _| Ok(()));
+        let saved = Snapshot { status: 200, content_type: None, body: expected.to_string() };
+        repo.expect_load().returning(move |_, _| Ok(Some(saved.clone())));
+
+        let usecase = SnapshotResponseUseCase::new(Arc::new(repo));
+        let project = Project::new("api");
+        usecase.accept(&project, "login", &request, &response, &env).unwrap();
+        assert_eq!(usecase.check(&project, "login", &request, &response, &env).unwrap(), SnapshotResult::Match);
+
+        let changed = Response::new(500, "Error".to_string(), "oops".to_string(), Duration::ZERO);
+        assert!(matches!(usecase.check(&project, "login", &request, &changed, &env).unwrap(), SnapshotResult::Mismatch { .. }));
+    }
+}
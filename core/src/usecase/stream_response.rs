@@ -0,0 +1,162 @@
+use crate::domain::{
+    response::Response,
+    stream::{ResponseStream, SseEvent, SseParser, StreamEvent},
+};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamState {
+    Streaming,
+    /// Complete, with the total time.
+    Finished(Duration),
+    Cancelled,
+    Failed(String),
+}
+
+/// A response body that is still arriving, collected chunk by chunk.
+pub struct StreamingResponse {
+    stream: ResponseStream,
+    /// Set for `text/event-stream` responses.
+    parser: Option<SseParser>,
+    /// Raw chunks with their arrival time.
+    pub chunks: Vec<(Duration, String)>,
+    pub body: String,
+    pub events: Vec<SseEvent>,
+    pub state: StreamState,
+}
+
+impl StreamingResponse {
+    pub fn new(stream: ResponseStream) -> Self {
+        let parser = stream.is_event_stream().then(SseParser::new);
+        Self { stream, parser, chunks: Vec::new(), body: String::new(), events: Vec::new(), state: StreamState::Streaming }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.stream.status
+    }
+
+    pub fn status_text(&self) -> &str {
+        &self.stream.status_text
+    }
+
+    pub fn is_event_stream(&self) -> bool {
+        self.parser.is_some()
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.state == StreamState::Streaming
+    }
+
+    /// Collects what arrived since the last call. Returns whether anything changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while self.is_streaming() {
+            let Some(event) = self.stream.events.try_next() else {
+                break;
+            };
+            changed = true;
+            match event {
+                StreamEvent::Chunk { text, elapsed } => {
+                    if let Some(parser) = &mut self.parser {
+                        self.events.extend(parser.feed(&text, elapsed));
+                    }
+                    self.body.push_str(&text);
+                    self.chunks.push((elapsed, text));
+                }
+                StreamEvent::End { elapsed } => self.state = StreamState::Finished(elapsed),
+                StreamEvent::Error(e) => self.state = StreamState::Failed(e),
+            }
+        }
+        changed
+    }
+
+    /// Stops the download. What arrived so far is kept.
+    pub fn cancel(&mut self) {
+        if self.is_streaming() {
+            self.stream.events.close();
+            self.state = StreamState::Cancelled;
+        }
+    }
+
+    /// The response as received so far.
+    pub fn to_response(&self) -> Response {
+        let latency = match &self.state {
+            StreamState::Finished(elapsed) => *elapsed,
+            _ => self.chunks.last().map(|(elapsed, _)| *elapsed).unwrap_or_default(),
+        };
+        let mut response = Response::new(self.stream.status, self.stream.status_text.clone(), self.body.clone(), latency);
+        response.headers = self.stream.headers.clone();
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::stream::StreamEvents;
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::{Arc, Mutex};
+
+    /// Events queued by the test; `None` once closed.
+    #[derive(Clone, Default)]
+    struct FakeEvents(Arc<Mutex<Option<VecDeque<StreamEvent>>>>);
+
+    impl FakeEvents {
+        fn send(&self, event: StreamEvent) -> Result<(), StreamEvent> {
+            let mut queue = self.0.lock().unwrap();
+            let Some(queue) = queue.as_mut() else {
+                return Err(event);
+            };
+            queue.push_back(event);
+            Ok(())
+        }
+    }
+
+    impl StreamEvents for FakeEvents {
+        fn try_next(&mut self) -> Option<StreamEvent> {
+            self.0.lock().unwrap().as_mut()?.pop_front()
+        }
+
+        fn close(&mut self) {
+            *self.0.lock().unwrap() = None;
+        }
+    }
+
+    fn stream(content_type: &str) -> (FakeEvents, StreamingResponse) {
+        let tx = FakeEvents(Arc::new(Mutex::new(Some(VecDeque::new()))));
+        let headers = HashMap::from([("content-type".to_string(), content_type.to_string())]);
+        let stream = ResponseStream { status: 200, status_text: "OK".to_string(), headers, events: Box::new(tx.clone()) };
+        (tx, StreamingResponse::new(stream))
+    }
+
+    #[test]
+    fn test_sse_events_and_completion() {
+        let (tx, mut response) = stream("text/event-stream; charset=utf-8");
+        let ms = Duration::from_millis;
+        tx.send(StreamEvent::Chunk { text: "event: token\ndata: Hel".to_string(), elapsed: ms(5) }).unwrap();
+        tx.send(StreamEvent::Chunk { text: "lo\n\n".to_string(), elapsed: ms(9) }).unwrap();
+        tx.send(StreamEvent::End { elapsed: ms(12) }).unwrap();
+
+        assert!(response.poll());
+        assert_eq!(response.events.len(), 1);
+        assert_eq!(response.events[0].event.as_deref(), Some("token"));
+        assert_eq!(response.events[0].data, "Hello");
+        assert_eq!(response.events[0].elapsed, ms(9));
+        assert_eq!(response.state, StreamState::Finished(ms(12)));
+        assert_eq!(response.to_response().body, "event: token\ndata: Hello\n\n");
+    }
+
+    #[test]
+    fn test_cancel_stops_the_stream() {
+        let (tx, mut response) = stream("text/plain");
+        tx.send(StreamEvent::Chunk { text: "partial".to_string(), elapsed: Duration::ZERO }).unwrap();
+        response.poll();
+        response.cancel();
+
+        assert_eq!(response.state, StreamState::Cancelled);
+        assert!(tx.send(StreamEvent::Chunk { text: "more".to_string(), elapsed: Duration::ZERO }).is_err());
+        assert!(!response.poll());
+        assert_eq!(response.body, "partial");
+        assert!(!response.is_event_stream());
+    }
+}
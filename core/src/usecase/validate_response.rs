@@ -0,0 +1,85 @@
+use crate::domain::{
+    environment::Environment,
+    project::Project,
+    repository::{ApiSpecRepository, SchemaValidator},
+    request::Request,
+    response::Response,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Checks responses against the OpenAPI document imported into their project.
+pub struct ValidateResponseUseCase {
+    specs: Arc<dyn ApiSpecRepository>,
+    validator: Arc<dyn SchemaValidator>,
+}
+
+impl ValidateResponseUseCase {
+    pub fn new(specs: Arc<dyn ApiSpecRepository>, validator: Arc<dyn SchemaValidator>) -> Self {
+        Self { specs, validator }
+    }
+
+    /// Returns the contract violations of a response, with secrets masked.
+    /// `None` when the project has no document or the request matches none of its operations.
+    pub fn execute(&self, project: &Project, request: &Request, response: &Response, environment: &Environment) -> Result<Option<Vec<String>>> {
+        let Some(spec) = self.specs.load(project)? else {
+            return Ok(None);
+        };
+        // Scaffolded URLs match as written; others once their variables are resolved
+        let operation = spec
+            .operation_for(&request.method, &request.url)
+            .or_else(|| spec.operation_for(&request.method, &environment.substitute(&request.url)));
+        Ok(operation.map(|operation| operation.validate_response(response, self.validator.as_ref()).iter().map(|v| environment.redact(v)).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::openapi::{ApiResponse, ApiSpec, Operation};
+    use crate::domain::repository::{MockApiSpecRepository, MockSchemaValidator};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn spec() -> ApiSpec {
+        let operation = Operation {
+            id: "get_user".to_string(),
+            method: "GET".to_string(),
+            path: "/users/{id}".to_string(),
+            parameters: vec![],
+            request_body: None,
+            security: None,
+            responses: vec![ApiResponse { status: "200".to_string(), headers: vec![], content: vec![] }],
+        };
+        ApiSpec { title: "Shop".to_string(), base_url: Some("https://api.shop.com".to_string()), operations: vec![operation] }
+    }
+
+    #[test]
+    fn test_validates_matching_requests() {
+        let mut specs = MockApiSpecRepository::new();
+        specs.expect_load().returning(|_| Ok(Some(spec())));
+        let usecase = ValidateResponseUseCase::new(Arc::new(specs), Arc::new(MockSchemaValidator::new()));
+        let environment = Environment::new(HashMap::from([("path".to_string(), "users/1".to_string())]), HashMap::new());
+        let response = |status| Response::new(status, String::new(), String::new(), Duration::ZERO);
+        let project = Project::new("shop");
+
+        let request = Request::new("GET", "https://api.shop.com/{{path}}");
+        assert_eq!(usecase.execute(&project, &request, &response(200), &environment).unwrap(), Some(vec![]));
+        assert_eq!(
+            usecase.execute(&project, &request, &response(500), &environment).unwrap(),
+            Some(vec!["status 500 is not declared (expected 200)".to_string()])
+        );
+        let unknown = Request::new("GET", "https://api.shop.com/orders");
+        assert_eq!(usecase.execute(&project, &unknown, &response(200), &environment).unwrap(), None);
+    }
+
+    #[test]
+    fn test_no_spec() {
+        let mut specs = MockApiSpecRepository::new();
+        specs.expect_load().returning(|_| Ok(None));
+        let usecase = ValidateResponseUseCase::new(Arc::new(specs), Arc::new(MockSchemaValidator::new()));
+        let response = Response::new(200, String::new(), String::new(), Duration::ZERO);
+        let result = usecase.execute(&Project::new("shop"), &Request::new("GET", "/"), &response, &Environment::default()).unwrap();
+        assert_eq!(result, None);
+    }
+}
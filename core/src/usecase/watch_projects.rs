@@ -0,0 +1,42 @@
+use crate::domain::repository::{ProjectChangeSink, ProjectWatcher};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Follows the changes made to the projects outside of htup.
+pub struct WatchProjectsUseCase {
+    watcher: Arc<dyn ProjectWatcher>,
+}
+
+impl WatchProjectsUseCase {
+    pub fn new(watcher: Arc<dyn ProjectWatcher>) -> Self {
+        Self { watcher }
+    }
+
+    /// Starts watching; the changes are reported to the sink.
+    pub fn execute(&self, sink: Arc<dyn ProjectChangeSink>) -> Result<()> {
+        self.watcher.watch(sink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        repository::{MockProjectChangeSink, MockProjectWatcher},
+        watch::ProjectChange,
+    };
+    use mockall::predicate::*;
+
+    #[test]
+    fn test_changes_reach_the_sink() {
+        let mut watcher = MockProjectWatcher::new();
+        watcher.expect_watch().times(1).returning(|sink| {
+            sink.changed(ProjectChange::Project("shop".to_string()));
+            Ok(())
+        });
+        let mut sink = MockProjectChangeSink::new();
+        sink.expect_changed().with(eq(ProjectChange::Project("shop".to_string()))).times(1).return_const(());
+
+        WatchProjectsUseCase::new(Arc::new(watcher)).execute(Arc::new(sink)).unwrap();
+    }
+}
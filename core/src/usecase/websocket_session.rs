@@ -0,0 +1,148 @@
+use crate::domain::{
+    body::Body,
+    environment::Environment,
+    repository::{Clock, WebSocketClient},
+    request::Request,
+    websocket::{Direction, WebSocketConnection, WsEvent, WsMessage},
+};
+use crate::usecase::execute_request::ExecuteRequestUseCase;
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Opens WebSocket sessions for `WS` requests.
+pub struct OpenWebSocketUseCase {
+    execute_request: Arc<ExecuteRequestUseCase>,
+    client: Arc<dyn WebSocketClient>,
+    clock: Arc<dyn Clock>,
+}
+
+impl OpenWebSocketUseCase {
+    pub fn new(execute_request: Arc<ExecuteRequestUseCase>, client: Arc<dyn WebSocketClient>, clock: Arc<dyn Clock>) -> Self {
+        Self { execute_request, client, clock }
+    }
+
+    /// Connects with the resolved request, so variables, OAuth2 and signing apply to the handshake.
+    pub async fn execute(&self, request: &Request, environment: &Environment) -> Result<WebSocketSession> {
+        let resolved = self.execute_request.prepare(request, environment).await?;
+        let predefined = match &resolved.body {
+            Some(Body::Messages(messages)) => messages.clone(),
+            _ => Vec::new(),
+        };
+        let connection = self.client.connect(&resolved).await?;
+        Ok(WebSocketSession { connection, clock: self.clock.clone(), log: Vec::new(), predefined, closed: None })
+    }
+}
+
+/// A live connection together with its message log.
+pub struct WebSocketSession {
+    connection: Box<dyn WebSocketConnection>,
+    clock: Arc<dyn Clock>,
+    pub log: Vec<WsMessage>,
+    /// Messages written in the request file, ready to send.
+    pub predefined: Vec<String>,
+    /// Why the connection ended, once it has.
+    pub closed: Option<String>,
+}
+
+impl WebSocketSession {
+    pub fn is_open(&self) -> bool {
+        self.closed.is_none()
+    }
+
+    /// Sends a text frame. JSON is sent as text, exactly as written.
+    pub fn send(&mut self, text: &str) -> Result<()> {
+        if let Some(reason) = &self.closed {
+            anyhow::bail!("Connection closed: {}", reason);
+        }
+        self.connection.send(text.to_string())?;
+        self.log.push(WsMessage { direction: Direction::Sent, text: text.to_string(), timestamp: self.clock.now() });
+        Ok(())
+    }
+
+    /// Moves frames that arrived since the last call into the log. Returns whether anything changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(event) = self.connection.try_next() {
+            changed = true;
+            match event {
+                WsEvent::Message(text) => {
+                    self.log.push(WsMessage { direction: Direction::Received, text, timestamp: self.clock.now() })
+                }
+                WsEvent::Closed(reason) => self.closed = Some(reason),
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::{MockClock, MockWebSocketClient};
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// A connection whose server side is played by the test.
+    struct FakeConnection {
+        sent: Arc<Mutex<Vec<String>>>,
+        events: Arc<Mutex<VecDeque<WsEvent>>>,
+    }
+
+    impl WebSocketConnection for FakeConnection {
+        fn send(&mut self, text: String) -> Result<()> {
+            self.sent.lock().unwrap().push(text);
+            Ok(())
+        }
+
+        fn try_next(&mut self) -> Option<WsEvent> {
+            self.events.lock().unwrap().pop_front()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_logs_both_directions() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let events = Arc::new(Mutex::new(VecDeque::new()));
+        let mut connection: Option<Box<dyn WebSocketConnection>> =
+            Some(Box::new(FakeConnection { sent: sent.clone(), events: events.clone() }));
+
+        let mut client = MockWebSocketClient::new();
+        client
+            .expect_connect()
+            .withf(|req| req.url == "wss://example.com/live")
+            .times(1)
+            .returning(move |_| Ok(connection.take().unwrap()));
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(|| UNIX_EPOCH + Duration::from_secs(42));
+
+        let usecase = OpenWebSocketUseCase::new(
+            Arc::new(ExecuteRequestUseCase::new(Arc::new(crate::domain::repository::MockHttpClient::new()))),
+            Arc::new(client),
+            Arc::new(clock),
+        );
+        let mut request = Request::new("WS", "wss://{{host}}/live");
+        request.body = Some(Body::Messages(vec![r#"{"channel": "{{channel}}"}"#.to_string()]));
+        let environment = Environment::new(
+            HashMap::from([("host".to_string(), "example.com".to_string()), ("channel".to_string(), "news".to_string())]),
+            HashMap::new(),
+        );
+
+        let mut session = usecase.execute(&request, &environment).await.unwrap();
+        assert_eq!(session.predefined, vec![r#"{"channel": "news"}"#]);
+
+        let first = session.predefined[0].clone();
+        session.send(&first).unwrap();
+        assert_eq!(*sent.lock().unwrap(), vec![r#"{"channel": "news"}"#]);
+
+        events.lock().unwrap().extend([WsEvent::Message("hello".to_string()), WsEvent::Closed("1000 bye".to_string())]);
+        assert!(session.poll());
+        assert_eq!(
+            session.log.iter().map(|m| (m.direction, m.text.as_str())).collect::<Vec<_>>(),
+            vec![(Direction::Sent, r#"{"channel": "news"}"#), (Direction::Received, "hello")]
+        );
+        assert!(session.log.iter().all(|m| m.timestamp == UNIX_EPOCH + Duration::from_secs(42)));
+        assert!(!session.is_open());
+        assert!(session.send("late").is_err());
+    }
+}
@@ -1,35 +1,80 @@
-use crate::state::{AppMode, AppState, FocusPane};
+use crate::keymap::Action;
+use crate::state::{AppMode, AppState, Finder, FocusPane, Help, Item, RequestRow, Search};
 use anyhow::Result;
 use htup_core::{
-    domain::{repository::ProjectRepository, request::Request},
+    domain::{diff::{DiffLine, ResponseDiff}, project::Project, request::Request, response::Response, search::SearchHit, snapshot::SnapshotResult},
     usecase::{
         execute_request::ExecuteRequestUseCase, 
         list_projects::ListProjectsUseCase,
         create_project::CreateProjectUseCase,
         create_request::CreateRequestUseCase,
         edit_request::EditRequestUseCase,
+        load_environment::LoadEnvironmentUseCase,
+        introspect_schema::IntrospectSchemaUseCase,
+        websocket_session::OpenWebSocketUseCase,
+        stream_response::{StreamState, StreamingResponse},
+        call_grpc::CallGrpcUseCase,
+        diff_responses::DiffResponsesUseCase,
+        snapshot_response::SnapshotResponseUseCase,
+        validate_response::ValidateResponseUseCase,
+        move_request::MoveRequestUseCase,
+        manage_project::ManageProjectUseCase,
+        delete::DeleteUseCase,
+        find_request::FindRequestUseCase,
+        search_requests::SearchRequestsUseCase,
+        watch_projects::WatchProjectsUseCase,
     },
 };
-use std::path::PathBuf;
+use htup_core::domain::{repository::ProjectChangeSink, watch::ProjectChange};
 use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
 pub struct App {
     pub state: AppState,
     list_projects_usecase: ListProjectsUseCase,
-    execute_request_usecase: ExecuteRequestUseCase,
+    execute_request_usecase: Arc<ExecuteRequestUseCase>,
     create_project_usecase: CreateProjectUseCase,
     create_request_usecase: CreateRequestUseCase,
     edit_request_usecase: EditRequestUseCase,
+    load_environment_usecase: LoadEnvironmentUseCase,
+    introspect_schema_usecase: IntrospectSchemaUseCase,
+    open_websocket_usecase: OpenWebSocketUseCase,
+    call_grpc_usecase: CallGrpcUseCase,
+    diff_responses_usecase: DiffResponsesUseCase,
+    snapshot_usecase: SnapshotResponseUseCase,
+    validate_response_usecase: ValidateResponseUseCase,
+    move_request_usecase: MoveRequestUseCase,
+    manage_project_usecase: ManageProjectUseCase,
+    delete_usecase: DeleteUseCase,
+    find_request_usecase: FindRequestUseCase,
+    search_requests_usecase: SearchRequestsUseCase,
+    watch_projects_usecase: WatchProjectsUseCase,
     request_repo: Arc<dyn htup_core::domain::repository::RequestRepository>,
+    changes: Option<UnboundedReceiver<ProjectChange>>, // Changes made on disk outside of htup
+    streamed: Option<(Project, String, Request)>, // What the running stream answers, recorded once it finishes
 }
 
 impl App {
+    #[allow(clippy::too_many_arguments)] // One parameter per use case
     pub fn new(
         list_projects_usecase: ListProjectsUseCase,
-        execute_request_usecase: ExecuteRequestUseCase,
+        execute_request_usecase: Arc<ExecuteRequestUseCase>,
         create_project_usecase: CreateProjectUseCase,
         create_request_usecase: CreateRequestUseCase,
         edit_request_usecase: EditRequestUseCase,
+        load_environment_usecase: LoadEnvironmentUseCase,
+        introspect_schema_usecase: IntrospectSchemaUseCase,
+        open_websocket_usecase: OpenWebSocketUseCase,
+        call_grpc_usecase: CallGrpcUseCase,
+        diff_responses_usecase: DiffResponsesUseCase,
+        snapshot_usecase: SnapshotResponseUseCase,
+        validate_response_usecase: ValidateResponseUseCase,
+        move_request_usecase: MoveRequestUseCase,
+        manage_project_usecase: ManageProjectUseCase,
+        delete_usecase: DeleteUseCase,
+        find_request_usecase: FindRequestUseCase,
+        search_requests_usecase: SearchRequestsUseCase,
+        watch_projects_usecase: WatchProjectsUseCase,
         request_repo: Arc<dyn htup_core::domain::repository::RequestRepository>,
     ) -> Self {
         Self {
@@ -39,35 +84,104 @@ impl App {
             create_project_usecase,
             create_request_usecase,
             edit_request_usecase,
+            load_environment_usecase,
+            introspect_schema_usecase,
+            open_websocket_usecase,
+            call_grpc_usecase,
+            diff_responses_usecase,
+            snapshot_usecase,
+            validate_response_usecase,
+            move_request_usecase,
+            manage_project_usecase,
+            delete_usecase,
+            find_request_usecase,
+            search_requests_usecase,
+            watch_projects_usecase,
             request_repo,
+            changes: None,
+            streamed: None,
         }
     }
 
     pub async fn init(&mut self) -> Result<()> {
         self.refresh_projects()?;
+        let (sender, changes) = mpsc::unbounded_channel();
+        match self.watch_projects_usecase.execute(Arc::new(ChangeQueue(sender))) {
+            Ok(()) => self.changes = Some(changes),
+            // Still usable, the lists just refresh after htup's own actions only
+            Err(e) => self.state.status_message = Some(format!("Auto-reload disabled: {:#}", e)),
+        }
         Ok(())
     }
 
     pub fn refresh_projects(&mut self) -> Result<()> {
         self.state.projects = self.list_projects_usecase.list_projects()?;
-        if !self.state.projects.is_empty() {
+        if self.state.selected_project_index >= self.state.projects.len() {
+            self.state.selected_project_index = self.state.projects.len().saturating_sub(1);
+        }
+        if self.state.projects.is_empty() {
+            // The last project was deleted
+            self.state.requests.clear();
+            self.state.folders.clear();
+            self.state.current_request = None;
+        } else {
             self.refresh_requests()?;
         }
         Ok(())
     }
 
     pub fn refresh_requests(&mut self) -> Result<()> {
-        if let Some(project) = self.state.selected_project() {
-            self.state.requests = self.list_projects_usecase.list_requests(project)?;
+        if let Some(project) = self.state.selected_project().cloned() {
+            self.state.requests = self.list_projects_usecase.list_requests(&project)?;
+            self.state.folders = self.list_projects_usecase.list_folders(&project)?;
+            self.state.environment = self.load_environment_usecase.execute(&project)?;
+            // A broken cache only disables the unknown field check
+            self.state.graphql_schema = self.introspect_schema_usecase.load(&project).ok().flatten();
             // Reset selection if out of bounds
-            if self.state.selected_request_index >= self.state.requests.len() {
+            if self.state.selected_request_index >= self.state.request_rows().len() {
                 self.state.selected_request_index = 0;
             }
         }
+        self.refresh_preview();
         Ok(())
     }
 
+    /// Reloads the lists after changes on disk, keeping the selected project and row when they still exist.
+    /// The preview is read again, so it never shows a request as it was before an edit.
+    fn reload(&mut self, projects: bool) -> Result<()> {
+        let project = self.state.selected_project().cloned();
+        let row = self.state.selected_row().map(|row| row.path().to_string());
+        if projects {
+            self.refresh_projects()?;
+            if let Some(project) = &project {
+                self.state.select_project(&project.name);
+            }
+        }
+        if self.state.selected_project() != project.as_ref() {
+            self.state.collapsed.clear();
+            self.state.selected_request_index = 0;
+        }
+        self.refresh_requests()?;
+        if let Some(path) = row.filter(|p| self.state.requests.contains(p) || self.state.folders.contains(p)) {
+            self.state.select_path(&path);
+            self.refresh_preview();
+        }
+        Ok(())
+    }
+
+    /// Reloads the preview of the selected request.
+    pub fn refresh_preview(&mut self) {
+        self.state.current_request = match (self.state.selected_project(), self.state.selected_request_id()) {
+            (Some(project), Some(req_id)) => self.request_repo.load(project, req_id).ok(),
+            _ => None,
+        };
+    }
+
     pub fn next(&mut self) {
+        if self.state.mode == AppMode::Diff {
+            self.state.diff_scroll = self.state.diff_scroll.saturating_add(1);
+            return;
+        }
         match self.state.focused_pane {
             FocusPane::Projects => self.next_project(),
             FocusPane::Requests => self.next_request(),
@@ -75,6 +189,10 @@ impl App {
     }
 
     pub fn previous(&mut self) {
+        if self.state.mode == AppMode::Diff {
+            self.state.diff_scroll = self.state.diff_scroll.saturating_sub(1);
+            return;
+        }
         match self.state.focused_pane {
             FocusPane::Projects => self.previous_project(),
             FocusPane::Requests => self.previous_request(),
@@ -84,6 +202,7 @@ impl App {
     fn next_project(&mut self) {
         if !self.state.projects.is_empty() {
             self.state.selected_project_index = (self.state.selected_project_index + 1) % self.state.projects.len();
+            self.state.collapsed.clear();
             self.refresh_requests().unwrap_or_default();
         }
     }
@@ -95,23 +214,28 @@ impl App {
             } else {
                 self.state.selected_project_index -= 1;
             }
+            self.state.collapsed.clear();
             self.refresh_requests().unwrap_or_default();
         }
     }
 
     fn next_request(&mut self) {
-        if !self.state.requests.is_empty() {
-            self.state.selected_request_index = (self.state.selected_request_index + 1) % self.state.requests.len();
+        let rows = self.state.request_rows().len();
+        if rows > 0 {
+            self.state.selected_request_index = (self.state.selected_request_index + 1) % rows;
+            self.refresh_preview();
         }
     }
 
     fn previous_request(&mut self) {
-        if !self.state.requests.is_empty() {
+        let rows = self.state.request_rows().len();
+        if rows > 0 {
             if self.state.selected_request_index == 0 {
-                self.state.selected_request_index = self.state.requests.len() - 1;
+                self.state.selected_request_index = rows - 1;
             } else {
                 self.state.selected_request_index -= 1;
             }
+            self.refresh_preview();
         }
     }
 
@@ -138,6 +262,9 @@ impl App {
                          // Enter on project list switches focus to requests (common pattern)
                          self.focus_requests();
                     }
+                    FocusPane::Requests if matches!(self.state.selected_row(), Some(RequestRow::Folder(_))) => {
+                        self.toggle_folder();
+                    }
                     FocusPane::Requests => {
                         // Execute Request
                         // Clone data first to avoid borrow conflicts
@@ -151,14 +278,59 @@ impl App {
                             // Now safe to mutate state
                             self.state.status_message = Some(format!("Executing {}...", req_id));
                             self.state.current_response = None; // Clear previous response
+                            self.state.streaming = None;
+                            self.streamed = None;
+                            self.state.snapshot = None;
+                            self.state.contract = None;
                             
                             // Load request (might fail, so handle error)
                             match self.request_repo.load(&project, &req_id) {
-                                Ok(request) => {
-                                    match self.execute_request_usecase.execute(&request).await {
+                                Ok(request) if request.is_grpc() => {
+                                    match self.call_grpc_usecase.execute(&project, &request, &self.state.environment).await {
                                         Ok(response) => {
+                                            self.state.status_message = Some(format!("Called '{}': {}", req_id, response.status_text));
+                                            self.after_response(&project, &req_id, &request, &response);
                                             self.state.current_response = Some(response);
+                                            self.state.mode = AppMode::ViewingResponse;
+                                        }
+                                        Err(e) => {
+                                            self.state.status_message = Some(format!("Error: {}", e));
+                                        }
+                                    }
+                                }
+                                Ok(request) if request.is_websocket() => {
+                                    match self.open_websocket_usecase.execute(&request, &self.state.environment).await {
+                                        Ok(session) => {
+                                            self.state.websocket = Some(session);
+                                            self.state.input_buffer.clear();
+                                            self.state.selection_index = 0;
+                                            self.state.status_message = Some(format!("Connected '{}'", req_id));
+                                            self.state.mode = AppMode::WebSocket;
+                                        }
+                                        Err(e) => {
+                                            self.state.status_message = Some(format!("Error: {}", e));
+                                        }
+                                    }
+                                }
+                                Ok(request) if request.is_streaming() => {
+                                    match self.execute_request_usecase.execute_stream(&request, &self.state.environment).await {
+                                        Ok(stream) => {
+                                            self.state.streaming = Some(StreamingResponse::new(stream));
+                                            self.streamed = Some((project.clone(), req_id.clone(), request.clone()));
+                                            self.state.status_message = Some(format!("Streaming '{}' (<{}> to cancel)", req_id, self.state.keymap.key_for(Action::Back)));
+                                            self.state.mode = AppMode::ViewingResponse;
+                                        }
+                                        Err(e) => {
+                                            self.state.status_message = Some(format!("Error: {}", e));
+                                        }
+                                    }
+                                }
+                                Ok(request) => {
+                                    match self.execute_request_usecase.execute(&request, &self.state.environment).await {
+                                        Ok(response) => {
                                             self.state.status_message = Some(format!("Executed '{}' successfully", req_id));
+                                            self.after_response(&project, &req_id, &request, &response);
+                                            self.state.current_response = Some(response);
                                             self.state.mode = AppMode::ViewingResponse;
                                         }
                                         Err(e) => {
@@ -188,7 +360,7 @@ impl App {
                 let name = self.state.input_buffer.clone();
                  if !name.is_empty() {
                      // Step 1 Complete: Move to Step 2 (Method)
-                     if let Some(mut pending) = self.state.pending_request.as_mut() {
+                     if let Some(pending) = self.state.pending_request.as_mut() {
                          pending.name = name;
                      }
                      self.state.mode = AppMode::CreatingRequestMethod;
@@ -197,8 +369,8 @@ impl App {
             }
             AppMode::CreatingRequestMethod => {
                 // Step 2 Complete: Move to Step 3 (Body)
-                let methods = vec!["GET", "POST", "PUT", "DELETE", "PATCH"];
-                if let Some(mut pending) = self.state.pending_request.as_mut() {
+                let methods = ["GET", "POST", "PUT", "DELETE", "PATCH"];
+                if let Some(pending) = self.state.pending_request.as_mut() {
                     if let Some(m) = methods.get(self.state.selection_index) {
                          pending.method = m.to_string();
                          self.state.mode = AppMode::CreatingRequestBody;
@@ -208,17 +380,19 @@ impl App {
             }
             AppMode::CreatingRequestBody => {
                 // Step 3 Complete: Finalize
-                let types = vec!["Empty", "JSON"];
+                let types = ["Empty", "JSON", "Form", "Multipart", "GraphQL"];
                  if let Some(project) = self.state.selected_project().cloned() {
-                    if let Some(pending) = self.state.pending_request.take() {
-                         let body_type = types.get(self.state.selection_index).unwrap_or(&"Empty");
+                    if let Some(mut pending) = self.state.pending_request.take() {
+                         pending.body_type = types.get(self.state.selection_index).unwrap_or(&"Empty").to_string();
                         
                         // Execute creation
-                        self.create_request_usecase.execute(&project, &pending.name, &pending.method, body_type)?;
+                        self.create_request_usecase.execute(&project, &pending.name, &pending.method, &pending.body_type)?;
                         
                         self.state.mode = AppMode::Normal;
                         self.state.input_buffer.clear();
                         self.refresh_requests()?;
+                        self.state.select_path(&pending.name);
+                        self.refresh_preview();
                         self.state.status_message = Some(format!("Request '{}' created in '{}'", pending.name, project.name));
                         
                         // Automatically open editor
@@ -226,29 +400,449 @@ impl App {
                     }
                  }
             }
+            AppMode::CreatingFolder => self.create_folder()?,
+            AppMode::Renaming => self.rename()?,
+            AppMode::Duplicating => self.duplicate()?,
+            AppMode::Finder => self.jump_to_hit()?,
+            AppMode::Searching => self.run_search(),
+            AppMode::SearchResults => self.open_search_hit()?,
+            AppMode::WebSocket => self.send_websocket_message(),
+            AppMode::Help => {
+                if let Some(help) = self.state.help.as_mut() {
+                    help.filtering = false;
+                }
+            }
             _ => {}
         }
         Ok(())
     }
 
+    /// Opens or closes the selected folder, keeping it selected.
+    fn toggle_folder(&mut self) {
+        if let Some(RequestRow::Folder(path)) = self.state.selected_row() {
+            let path = path.to_string();
+            if !self.state.collapsed.remove(&path) {
+                self.state.collapsed.insert(path);
+            }
+        }
+    }
+
+    fn create_folder(&mut self) -> Result<()> {
+        let Some(project) = self.state.selected_project().cloned() else {
+            return Ok(());
+        };
+        let path = self.state.input_buffer.trim().trim_matches('/').to_string();
+        match self.move_request_usecase.create_folder(&project, &path) {
+            Ok(()) => {
+                self.state.mode = AppMode::Normal;
+                self.state.input_buffer.clear();
+                self.refresh_requests()?;
+                self.state.select_path(&path);
+                self.refresh_preview();
+                self.state.status_message = Some(format!("Folder '{}' created in '{}'", path, project.name));
+            }
+            // Conflicts keep the popup open to pick another name
+            Err(e) => self.state.status_message = Some(format!("Error: {}", e)),
+        }
+        Ok(())
+    }
+
+    /// Renames the item picked by `start_rename`; another folder in a request or folder path moves it.
+    fn rename(&mut self) -> Result<()> {
+        let (Some(project), Some(target)) = (self.state.selected_project().cloned(), self.state.target.clone()) else {
+            return Ok(());
+        };
+        let to = self.state.input_buffer.trim().trim_matches('/').to_string();
+        let result = match &target {
+            Item::Project(_) => self.manage_project_usecase.rename(&project, &to).map(|_| ()),
+            Item::Folder(from) => self.move_request_usecase.move_folder(&project, from, &to),
+            Item::Request(from) => self.move_request_usecase.move_request(&project, from, &to),
+        };
+        self.after_input(result, &target, &to, "Renamed")
+    }
+
+    /// Copies the item picked by `start_duplicate` under the typed name.
+    fn duplicate(&mut self) -> Result<()> {
+        let (Some(project), Some(target)) = (self.state.selected_project().cloned(), self.state.target.clone()) else {
+            return Ok(());
+        };
+        let to = self.state.input_buffer.trim().trim_matches('/').to_string();
+        let result = match &target {
+            Item::Project(_) => self.manage_project_usecase.duplicate(&project, &to).map(|_| ()),
+            Item::Request(from) => self.move_request_usecase.duplicate_request(&project, from, &to),
+            Item::Folder(_) => Ok(()),
+        };
+        self.after_input(result, &target, &to, "Duplicated")
+    }
+
+    /// Closes the popup and selects `to`, or shows the error and keeps the popup open to fix the name.
+    fn after_input(&mut self, result: Result<()>, target: &Item, to: &str, done: &str) -> Result<()> {
+        if let Err(e) = result {
+            self.state.status_message = Some(format!("Error: {}", e));
+            return Ok(());
+        }
+        self.state.mode = AppMode::Normal;
+        self.state.input_buffer.clear();
+        self.state.target = None;
+        match target {
+            Item::Project(_) => {
+                self.refresh_projects()?;
+                self.state.select_project(to);
+                self.state.collapsed.clear();
+                self.refresh_requests()?;
+            }
+            Item::Folder(_) | Item::Request(_) => {
+                self.refresh_requests()?;
+                self.state.select_path(to);
+                self.refresh_preview();
+            }
+        }
+        self.state.status_message = Some(format!("{} {} '{}' to '{}'", done, target.kind(), target.path(), to));
+        Ok(())
+    }
+
+    /// Moves the item confirmed in `ConfirmingDelete` to the trash.
+    pub fn on_confirm_delete(&mut self) -> Result<()> {
+        let (Some(project), Some(target)) = (self.state.selected_project().cloned(), self.state.target.take()) else {
+            return Ok(());
+        };
+        self.state.mode = AppMode::Normal;
+        let result = match &target {
+            Item::Project(_) => self.delete_usecase.delete_project(&project),
+            Item::Folder(path) => self.delete_usecase.delete_folder(&project, path),
+            Item::Request(id) => self.delete_usecase.delete_request(&project, id),
+        };
+        match result {
+            Ok(entry) => {
+                self.state.status_message = Some(format!("Deleted {} '{}' (u to undo)", target.kind(), entry.label));
+                self.state.trash.push(entry);
+                if let Item::Project(_) = target {
+                    self.state.collapsed.clear();
+                    self.refresh_projects()?;
+                } else {
+                    self.refresh_requests()?;
+                }
+            }
+            Err(e) => self.state.status_message = Some(format!("Error: {}", e)),
+        }
+        Ok(())
+    }
+
+    /// Restores the last deletion of the session.
+    pub fn on_undo(&mut self) -> Result<()> {
+        let Some(entry) = self.state.trash.pop() else {
+            self.state.status_message = Some("Nothing to undo".to_string());
+            return Ok(());
+        };
+        match self.delete_usecase.undo(&entry) {
+            Ok(()) => {
+                self.refresh_projects()?;
+                self.state.status_message = Some(format!("Restored '{}'", entry.label));
+            }
+            Err(e) => {
+                self.state.status_message = Some(format!("Error: {}", e));
+                self.state.trash.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the typed message, or the selected predefined one when nothing is typed.
+    fn send_websocket_message(&mut self) {
+        let Some(session) = self.state.websocket.as_mut() else {
+            return;
+        };
+        let text = if self.state.input_buffer.is_empty() {
+            match session.predefined.get(self.state.selection_index) {
+                Some(message) => message.clone(),
+                None => return,
+            }
+        } else {
+            std::mem::take(&mut self.state.input_buffer)
+        };
+        if let Err(e) = session.send(&text) {
+            self.state.status_message = Some(format!("Send failed: {}", e));
+        }
+    }
+
+    /// Copies the selected predefined message into the input box for editing.
+    pub fn on_tab(&mut self) {
+        if self.state.mode == AppMode::Searching {
+            if let Some(search) = self.state.search.as_mut() {
+                search.regex = !search.regex;
+            }
+            return;
+        }
+        if let Some(finder) = self.state.finder.as_mut() {
+            finder.with_details = !finder.with_details;
+            self.update_finder();
+            return;
+        }
+        if let Some(message) = self.state.websocket.as_ref().and_then(|s| s.predefined.get(self.state.selection_index)) {
+            self.state.input_buffer = message.clone();
+        }
+    }
+
+    /// Collects what arrived on background connections since the last frame.
+    pub fn on_tick(&mut self) {
+        if let Some(streaming) = self.state.streaming.as_mut() {
+            if streaming.is_streaming() && streaming.poll() && !streaming.is_streaming() {
+                let response = streaming.to_response();
+                self.state.status_message = Some(match &streaming.state {
+                    StreamState::Failed(e) => format!("Stream failed: {}", e),
+                    _ => "Stream finished".to_string(),
+                });
+                // Only a complete body is worth keeping in history or checking against the snapshot and spec
+                if let (StreamState::Finished(_), Some((project, request_id, request))) = (&streaming.state, self.streamed.take()) {
+                    self.after_response(&project, &request_id, &request, &response);
+                }
+                self.state.current_response = Some(response);
+            }
+        }
+        if let Some(session) = self.state.websocket.as_mut() {
+            let was_open = session.is_open();
+            session.poll();
+            if let (true, Some(reason)) = (was_open, &session.closed) {
+                self.state.status_message = Some(format!("WebSocket closed: {}", reason));
+            }
+        }
+        self.apply_changes();
+    }
+
+    /// Reloads once for all the changes reported since the last frame; a save often reports several.
+    fn apply_changes(&mut self) {
+        let Some(changes) = self.changes.as_mut() else {
+            return;
+        };
+        let (mut projects, mut selected) = (false, false);
+        while let Ok(change) = changes.try_recv() {
+            match change {
+                ProjectChange::Projects => projects = true,
+                ProjectChange::Project(name) => selected |= self.state.selected_project().is_some_and(|p| p.name == name),
+            }
+        }
+        if projects || selected {
+            if let Err(e) = self.reload(projects) {
+                self.state.status_message = Some(format!("Reload failed: {}", e));
+            }
+        }
+    }
+
+    /// Fetches and caches the GraphQL schema from the endpoint of the selected request.
+    pub async fn on_introspect(&mut self) -> Result<()> {
+        let (Some(project), Some(request)) = (self.state.selected_project().cloned(), self.state.current_request.clone()) else {
+            self.state.status_message = Some("No request selected".to_string());
+            return Ok(());
+        };
+
+        self.state.status_message = Some(format!("Introspecting {}...", request.url));
+        match self.introspect_schema_usecase.execute(&project, &request, &self.state.environment).await {
+            Ok(schema) => {
+                self.state.status_message = Some(format!("Schema of '{}' cached ({} types)", project.name, schema.types.len()));
+                self.state.graphql_schema = Some(schema);
+            }
+            Err(e) => {
+                self.state.status_message = Some(format!("Introspection failed: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the response for later comparisons and checks it against the approved snapshot
+    /// and the OpenAPI document. Failing to do so must not hide the response.
+    fn after_response(&mut self, project: &Project, request_id: &str, request: &Request, response: &Response) {
+        if let Err(e) = self.diff_responses_usecase.record(project, request_id, request, response, &self.state.environment) {
+            self.state.status_message = Some(format!("Failed to record history: {}", e));
+        }
+        match self.snapshot_usecase.check(project, request_id, request, response, &self.state.environment) {
+            Ok(result) => {
+                if let SnapshotResult::Mismatch { .. } = result {
+                    self.state.status_message = Some(format!("'{}' does not match its snapshot (S: show diff, A: accept)", request_id));
+                }
+                self.state.snapshot = Some(result);
+            }
+            Err(e) => self.state.status_message = Some(format!("Snapshot check failed: {}", e)),
+        }
+        match self.validate_response_usecase.execute(project, request, response, &self.state.environment) {
+            Ok(Some(violations)) => {
+                if !violations.is_empty() {
+                    self.state.status_message = Some(format!("'{}' violates its OpenAPI contract ({} problems)", request_id, violations.len()));
+                }
+                self.state.contract = Some(violations);
+            }
+            Ok(None) => {}
+            Err(e) => self.state.status_message = Some(format!("OpenAPI validation failed: {}", e)),
+        }
+    }
+
+    /// Shows how the current response differs from the approved snapshot.
+    pub fn on_show_snapshot_diff(&mut self) {
+        if let Some(diff) = self.state.snapshot.as_ref().and_then(SnapshotResult::diff) {
+            let title = format!("'{}': snapshot vs current", self.state.selected_request_id().unwrap_or_default());
+            self.show_diff(title, diff);
+            return;
+        }
+        self.state.status_message = Some(match &self.state.snapshot {
+            Some(SnapshotResult::Match) => "Response matches its snapshot".to_string(),
+            Some(SnapshotResult::New(_)) => "No snapshot yet (A to accept this response)".to_string(),
+            _ => "Run the request first".to_string(),
+        });
+    }
+
+    /// Approves the current response as the snapshot of the selected request.
+    pub fn on_accept_snapshot(&mut self) {
+        let (Some(project), Some(req_id), Some(request), Some(response)) = (
+            self.state.selected_project(),
+            self.state.selected_request_id(),
+            self.state.current_request.as_ref(),
+            self.state.current_response.as_ref(),
+        ) else {
+            self.state.status_message = Some("No response to accept".to_string());
+            return;
+        };
+        match self.snapshot_usecase.accept(project, req_id, request, response, &self.state.environment) {
+            Ok(_) => {
+                self.state.status_message = Some(format!("Accepted snapshot of '{}'", req_id));
+                self.state.snapshot = Some(SnapshotResult::Match);
+            }
+            Err(e) => self.state.status_message = Some(format!("Failed to save snapshot: {}", e)),
+        }
+    }
+
+    /// Compares the last two runs of the selected request.
+    pub fn on_diff_previous(&mut self) {
+        let (Some(project), Some(req_id)) = (self.state.selected_project().cloned(), self.state.selected_request_id().map(str::to_string)) else {
+            return;
+        };
+        let ignore = self.state.current_request.as_ref().map(|r| r.diff_ignore.clone()).unwrap_or_default();
+        match self.diff_responses_usecase.diff_previous(&project, &req_id, &ignore) {
+            Ok(Some(diff)) => self.show_diff(format!("'{}': previous run vs last run", req_id), diff),
+            Ok(None) => self.state.status_message = Some(format!("'{}' needs two runs to compare", req_id)),
+            Err(e) => self.state.status_message = Some(format!("Diff failed: {}", e)),
+        }
+    }
+
+    /// Compares the current response against the pinned baseline.
+    pub fn on_diff_baseline(&mut self) {
+        let (Some(project), Some(req_id), Some(response)) = (
+            self.state.selected_project().cloned(),
+            self.state.selected_request_id().map(str::to_string),
+            self.state.current_response.as_ref(),
+        ) else {
+            self.state.status_message = Some("Run the request first to compare it with its baseline".to_string());
+            return;
+        };
+        let ignore = self.state.current_request.as_ref().map(|r| r.diff_ignore.clone()).unwrap_or_default();
+        match self.diff_responses_usecase.diff_baseline(&project, &req_id, response, &self.state.environment, &ignore) {
+            Ok(Some(diff)) => self.show_diff(format!("'{}': baseline vs current", req_id), diff),
+            Ok(None) => self.state.status_message = Some(format!("No baseline pinned for '{}' (P to pin)", req_id)),
+            Err(e) => self.state.status_message = Some(format!("Diff failed: {}", e)),
+        }
+    }
+
+    /// Pins the current response as the baseline of the selected request.
+    pub fn on_pin_baseline(&mut self) {
+        let (Some(project), Some(req_id), Some(response)) = (
+            self.state.selected_project(),
+            self.state.selected_request_id(),
+            self.state.current_response.as_ref(),
+        ) else {
+            self.state.status_message = Some("No response to pin".to_string());
+            return;
+        };
+        self.state.status_message = Some(match self.diff_responses_usecase.pin_baseline(project, req_id, response, &self.state.environment) {
+            Ok(()) => format!("Pinned baseline of '{}'", req_id),
+            Err(e) => format!("Failed to pin baseline: {}", e),
+        });
+    }
+
+    fn show_diff(&mut self, title: String, diff: ResponseDiff) {
+        let changed_lines = diff.lines.iter().filter(|l| !matches!(l, DiffLine::Same(_))).count();
+        self.state.status_message = Some(match (diff.is_empty(), diff.changes.len()) {
+            (true, _) => "No differences".to_string(),
+            (false, 0) => format!("{} changed lines (v: side-by-side / unified)", changed_lines),
+            (false, changes) => format!("{} changes (v: side-by-side / unified)", changes),
+        });
+        self.state.diff = Some((title, diff));
+        self.state.diff_scroll = 0;
+        self.state.mode = AppMode::Diff;
+    }
+
+    pub fn toggle_diff_layout(&mut self) {
+        if self.state.mode == AppMode::Diff {
+            self.state.diff_side_by_side = !self.state.diff_side_by_side;
+        }
+    }
+
     pub fn on_edit(&mut self) -> Result<()> {
+        // In the search results the editor opens at the matched line
+        if self.state.mode == AppMode::SearchResults {
+            if let Some(hit) = self.selected_search_hit() {
+                self.edit_request_usecase.execute_at(&hit.project, &hit.request_id, hit.line)?;
+                self.state.status_message = Some(format!("Edited {}", hit.request_id));
+                self.refresh_preview();
+            }
+            return Ok(());
+        }
         if let (Some(project), Some(req_id)) = (self.state.selected_project(), self.state.selected_request_id()) {
              self.edit_request_usecase.execute(project, req_id)?;
              self.state.status_message = Some(format!("Edited {}", req_id));
+             self.refresh_preview();
         }
         Ok(())
     }
 
     pub fn on_esc(&mut self) {
         match self.state.mode {
+            // The first <Esc> cancels a running stream, keeping what arrived
+            AppMode::ViewingResponse if self.state.streaming.as_ref().is_some_and(|s| s.is_streaming()) => {
+                if let Some(streaming) = self.state.streaming.as_mut() {
+                    streaming.cancel();
+                    self.state.current_response = Some(streaming.to_response());
+                }
+                self.state.status_message = Some("Stream cancelled".to_string());
+            }
             AppMode::ViewingResponse => {
                 self.state.mode = AppMode::Normal;
             }
+            AppMode::Diff => {
+                self.state.diff = None;
+                self.state.mode = if self.state.current_response.is_some() { AppMode::ViewingResponse } else { AppMode::Normal };
+            }
             AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingRequestMethod | AppMode::CreatingRequestBody => {
                 self.state.mode = AppMode::Normal;
                 self.state.input_buffer.clear();
                 self.state.pending_request = None;
             }
+            AppMode::Searching | AppMode::SearchResults => {
+                self.state.mode = AppMode::Normal;
+                self.state.input_buffer.clear();
+            }
+            AppMode::Finder => {
+                self.state.mode = AppMode::Normal;
+                self.state.input_buffer.clear();
+                self.state.finder = None;
+            }
+            AppMode::CreatingFolder | AppMode::Renaming | AppMode::Duplicating | AppMode::ConfirmingDelete => {
+                self.state.mode = AppMode::Normal;
+                self.state.input_buffer.clear();
+                self.state.target = None;
+            }
+            // The first <Esc> clears a filter being typed
+            AppMode::Help if self.state.help.as_ref().is_some_and(|h| h.filtering) => {
+                if let Some(help) = self.state.help.as_mut() {
+                    help.query.clear();
+                    help.filtering = false;
+                }
+            }
+            AppMode::Help => self.toggle_help(),
+            AppMode::WebSocket => {
+                // Dropping the session closes the connection
+                self.state.websocket = None;
+                self.state.input_buffer.clear();
+                self.state.mode = AppMode::Normal;
+                self.state.status_message = Some("WebSocket disconnected".to_string());
+            }
             _ => {
                 // Maybe quit?
             }
@@ -257,33 +851,46 @@ impl App {
 
     pub fn on_char(&mut self, c: char) {
         match self.state.mode {
-            AppMode::CreatingProject | AppMode::CreatingRequest => {
+            AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingFolder | AppMode::Renaming | AppMode::Duplicating | AppMode::Searching | AppMode::WebSocket => {
                 self.state.input_buffer.push(c);
             }
+            AppMode::Finder => {
+                self.state.input_buffer.push(c);
+                self.update_finder();
+            }
+            AppMode::Help => {
+                if let Some(help) = self.state.help.as_mut().filter(|h| h.filtering) {
+                    help.query.push(c);
+                    help.scroll = 0;
+                }
+            }
             _ => {}
         }
     }
 
     pub fn on_backspace(&mut self) {
          match self.state.mode {
-            AppMode::CreatingProject | AppMode::CreatingRequest => {
+            AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingFolder | AppMode::Renaming | AppMode::Duplicating | AppMode::Searching | AppMode::WebSocket => {
                 self.state.input_buffer.pop();
             }
+            AppMode::Finder => {
+                self.state.input_buffer.pop();
+                self.update_finder();
+            }
+            AppMode::Help => {
+                if let Some(help) = self.state.help.as_mut().filter(|h| h.filtering) {
+                    help.query.pop();
+                    help.scroll = 0;
+                }
+            }
             _ => {}
         }
     }
     
     pub fn on_up(&mut self) {
         match self.state.mode {
-            AppMode::CreatingRequestMethod => {
-                 if self.state.selection_index > 0 {
-                     self.state.selection_index -= 1;
-                 }
-            }
-             AppMode::CreatingRequestBody => {
-                 if self.state.selection_index > 0 {
-                     self.state.selection_index -= 1;
-                 }
+            AppMode::CreatingRequestMethod | AppMode::CreatingRequestBody | AppMode::WebSocket | AppMode::Finder if self.state.selection_index > 0 => {
+                self.state.selection_index -= 1;
             }
             _ => {}
         }
@@ -298,11 +905,23 @@ impl App {
                 }
             }
             AppMode::CreatingRequestBody => {
-                let max = 2; // count of types
+                let max = 5; // count of types
                 if self.state.selection_index < max - 1 {
                     self.state.selection_index += 1;
                 }
             }
+            AppMode::Finder => {
+                let max = self.state.finder.as_ref().map_or(0, |f| f.hits.len());
+                if self.state.selection_index + 1 < max {
+                    self.state.selection_index += 1;
+                }
+            }
+            AppMode::WebSocket => {
+                let max = self.state.websocket.as_ref().map_or(0, |s| s.predefined.len());
+                if self.state.selection_index + 1 < max {
+                    self.state.selection_index += 1;
+                }
+            }
             _ => {}
         }
     }
@@ -315,7 +934,7 @@ impl App {
     pub fn start_create_request(&mut self) {
         if self.state.selected_project().is_some() {
             self.state.mode = AppMode::CreatingRequest; // Start Step 1
-            self.state.input_buffer.clear();
+            self.state.input_buffer = folder_prefix(self.state.current_folder());
             // Init pending request
             self.state.pending_request = Some(crate::state::PendingRequest {
                 name: String::new(),
@@ -326,4 +945,207 @@ impl App {
             self.state.status_message = Some("No project selected".to_string());
         }
     }
+
+    pub fn start_create_folder(&mut self) {
+        if self.state.selected_project().is_some() {
+            self.state.mode = AppMode::CreatingFolder;
+            self.state.input_buffer = folder_prefix(self.state.current_folder());
+        } else {
+            self.state.status_message = Some("No project selected".to_string());
+        }
+    }
+
+    /// Asks for the new name of the selected project, or path of the selected request or folder.
+    pub fn start_rename(&mut self) {
+        if let Some(target) = self.state.selected_item() {
+            self.state.mode = AppMode::Renaming;
+            self.state.input_buffer = target.path().to_string();
+            self.state.target = Some(target);
+        }
+    }
+
+    /// Asks for the name of a copy of the selected project or request, suggesting a free one.
+    pub fn start_duplicate(&mut self) {
+        let Some(target) = self.state.selected_item() else {
+            return;
+        };
+        let taken: Vec<&str> = match target {
+            Item::Project(_) => self.state.projects.iter().map(|p| p.name.as_str()).collect(),
+            Item::Request(_) => self.state.requests.iter().chain(&self.state.folders).map(String::as_str).collect(),
+            Item::Folder(_) => {
+                self.state.status_message = Some("Folders cannot be duplicated".to_string());
+                return;
+            }
+        };
+        let mut name = format!("{}_copy", target.path());
+        for n in 2.. {
+            if !taken.contains(&name.as_str()) {
+                break;
+            }
+            name = format!("{}_copy_{}", target.path(), n);
+        }
+        self.state.mode = AppMode::Duplicating;
+        self.state.input_buffer = name;
+        self.state.target = Some(target);
+    }
+
+    pub fn start_delete(&mut self) {
+        if let Some(target) = self.state.selected_item() {
+            self.state.mode = AppMode::ConfirmingDelete;
+            self.state.target = Some(target);
+        }
+    }
+
+    /// Opens the fuzzy finder over every project and request, read once per opening.
+    pub fn start_finder(&mut self) {
+        match self.find_request_usecase.index() {
+            Ok(entries) => {
+                self.state.mode = AppMode::Finder;
+                self.state.input_buffer.clear();
+                self.state.finder = Some(Finder { entries, hits: Vec::new(), with_details: false });
+                self.update_finder();
+            }
+            Err(e) => self.state.status_message = Some(format!("Error: {}", e)),
+        }
+    }
+
+    /// Ranks the entries again after the query or the matched fields changed.
+    fn update_finder(&mut self) {
+        if let Some(finder) = self.state.finder.as_mut() {
+            finder.hits = self.find_request_usecase.search(&finder.entries, &self.state.input_buffer, finder.with_details);
+            self.state.selection_index = 0;
+        }
+    }
+
+    /// Selects the project and request of the highlighted hit.
+    fn jump_to_hit(&mut self) -> Result<()> {
+        let Some(entry) = self.state.finder.as_ref().and_then(|f| {
+            let hit = f.hits.get(self.state.selection_index)?;
+            f.entries.get(hit.index).cloned()
+        }) else {
+            return Ok(());
+        };
+        self.state.mode = AppMode::Normal;
+        self.state.input_buffer.clear();
+        self.state.finder = None;
+        self.go_to(&entry.project, entry.request_id.as_deref())?;
+        self.state.status_message = Some(entry.label());
+        Ok(())
+    }
+
+    /// Selects a project, and one of its requests with the folders around it opened.
+    fn go_to(&mut self, project: &Project, request_id: Option<&str>) -> Result<()> {
+        if self.state.selected_project() != Some(project) {
+            self.state.select_project(&project.name);
+            self.state.collapsed.clear();
+            self.state.selected_request_index = 0;
+        }
+        self.refresh_requests()?;
+        match request_id {
+            Some(id) => {
+                self.state.select_path(id);
+                self.focus_requests();
+            }
+            None => self.focus_projects(),
+        }
+        self.refresh_preview();
+        Ok(())
+    }
+
+    pub fn start_search(&mut self) {
+        self.state.mode = AppMode::Searching;
+        // The last query stays in the box to refine it
+        let previous = self.state.search.take();
+        let query = previous.as_ref().map(|s| s.query.clone()).unwrap_or_default();
+        self.state.input_buffer = query.clone();
+        self.state.search = Some(Search { query, regex: previous.is_some_and(|s| s.regex), hits: Vec::new() });
+    }
+
+    /// Searches every request file for the typed query and lists the matching lines.
+    fn run_search(&mut self) {
+        let Some(search) = self.state.search.as_mut() else {
+            return;
+        };
+        match self.search_requests_usecase.execute(&self.state.input_buffer, search.regex) {
+            Ok(hits) => {
+                self.state.status_message = Some(format!("{} matching lines for '{}'", hits.len(), self.state.input_buffer));
+                search.query = std::mem::take(&mut self.state.input_buffer);
+                search.hits = hits;
+                self.state.selection_index = 0;
+                self.state.mode = AppMode::SearchResults;
+            }
+            // An invalid regex keeps the box open to fix it
+            Err(e) => self.state.status_message = Some(format!("Error: {}", e)),
+        }
+    }
+
+    /// Opens the key bindings over the current view; `?` again closes it.
+    pub fn toggle_help(&mut self) {
+        match self.state.help.take() {
+            Some(help) => self.state.mode = help.previous,
+            None => {
+                self.state.help = Some(Help::new(self.state.mode.clone()));
+                self.state.mode = AppMode::Help;
+            }
+        }
+    }
+
+    pub fn start_help_filter(&mut self) {
+        if let Some(help) = self.state.help.as_mut() {
+            help.filtering = true;
+        }
+    }
+
+    /// Scrolls the help by `lines`, negative ones up; the end is clamped when drawn.
+    pub fn scroll_help(&mut self, lines: isize) {
+        if let Some(help) = self.state.help.as_mut() {
+            help.scroll = help.scroll.saturating_add_signed(lines);
+        }
+    }
+
+    /// Moves through the results of a search.
+    pub fn next_search_hit(&mut self, forward: bool) {
+        let count = self.state.search.as_ref().map_or(0, |s| s.hits.len());
+        if count > 0 {
+            self.state.selection_index = if forward {
+                (self.state.selection_index + 1) % count
+            } else {
+                (self.state.selection_index + count - 1) % count
+            };
+        }
+    }
+
+    fn selected_search_hit(&self) -> Option<SearchHit> {
+        self.state.search.as_ref()?.hits.get(self.state.selection_index).cloned()
+    }
+
+    /// Closes the results and selects the request of the highlighted line.
+    fn open_search_hit(&mut self) -> Result<()> {
+        let Some(hit) = self.selected_search_hit() else {
+            return Ok(());
+        };
+        self.state.mode = AppMode::Normal;
+        self.go_to(&hit.project, Some(&hit.request_id))?;
+        self.state.status_message = Some(format!("{}/{}:{}", hit.project.name, hit.request_id, hit.line));
+        Ok(())
+    }
+}
+
+/// Prefills the name of a new item so it lands in the current folder.
+fn folder_prefix(folder: &str) -> String {
+    if folder.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", folder)
+    }
+}
+
+/// Queues the changes on disk until the next frame picks them up.
+struct ChangeQueue(UnboundedSender<ProjectChange>);
+
+impl ProjectChangeSink for ChangeQueue {
+    fn changed(&self, change: ProjectChange) {
+        // The receiver is gone once the app quits
+        let _ = self.0.send(change);
+    }
 }
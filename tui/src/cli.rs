@@ -0,0 +1,444 @@
+use anyhow::Result;
+use htup_core::{
+    domain::{
+        capture::{CaptureFilter, CapturedCall},
+        diff::{DiffLine, ResponseDiff},
+        mock::{MockHit, MockOptions},
+        project::Project,
+        repository::{CaptureSink, MockHitSink, RequestRepository},
+        snapshot::SnapshotResult,
+    },
+    usecase::{
+        call_grpc::CallGrpcUseCase, execute_request::ExecuteRequestUseCase, list_projects::ListProjectsUseCase,
+        export_har::ExportHarUseCase, import_collection::{ImportCollectionUseCase, ImportReport}, import_har::ImportHarUseCase,
+        import_openapi::ImportOpenApiUseCase, load_environment::LoadEnvironmentUseCase,
+        record_traffic::RecordTrafficUseCase, serve_mock::ServeMockUseCase, snapshot_response::SnapshotResponseUseCase,
+        validate_response::ValidateResponseUseCase,
+    },
+};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+const USAGE: &str = "Usage:
+  htup                                           Start the TUI
+  htup snapshot <project> [<request>...] [--accept]
+                                                 Compare responses with their snapshots (or accept them) and the OpenAPI document
+  htup mock <project> [--port <port>] [--delay <ms>] [--status <code>]
+                                                 Serve example responses (.mock files or history) locally
+  htup proxy <project> [--upstream <url>] [--port <port>] [--record-responses] [<filter>...]
+                                                 Pass calls through to the upstream, saving each one as a .http file
+  htup proxy <project> --replay [--port <port>]  Serve the recorded responses offline
+  htup import <project> <file.har> [<filter>...] Save the entries of a HAR file as requests
+  htup import <project> <file.json> [--folders-as-projects]
+                                                 Import a Postman collection or environment (v2.1) or an Insomnia export (v4)
+  htup import <project> <openapi.yaml>           Scaffold a request per operation of an OpenAPI 3 or Swagger 2 document
+  htup export <project> <file.har> [<request>...]
+                                                 Write the response history as a HAR file, secrets masked
+
+Filters (repeatable): --host <host> (*.example.com for subdomains), --path <prefix>, --method <method>,
+  --content-type <part of the response content type>";
+
+/// Port of `htup mock` and `htup proxy` unless `--port` is given.
+const DEFAULT_PORT: u16 = 8080;
+
+/// Commands run without the TUI, e.g. in CI.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Snapshot { project: String, requests: Vec<String>, accept: bool },
+    Mock { project: String, port: u16, options: MockOptions },
+    Proxy { project: String, port: u16, upstream: Option<String>, filter: CaptureFilter, record_responses: bool },
+    Replay { project: String, port: u16 },
+    Import { project: String, file: PathBuf, filter: CaptureFilter, folders_as_projects: bool },
+    Export { project: String, file: PathBuf, requests: Vec<String> },
+}
+
+impl Command {
+    /// Parses the arguments after the program name. `None` starts the TUI.
+    pub fn parse(args: &[String]) -> Result<Option<Self>> {
+        let Some((command, rest)) = args.split_first() else {
+            return Ok(None);
+        };
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut rest = rest.iter();
+        while let Some(arg) = rest.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg.clone());
+                continue;
+            }
+            // Options with a value take the next argument, or `--name=value`
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let value = match name {
+                "--port" | "--delay" | "--status" | "--upstream" | "--host" | "--path" | "--method" | "--content-type" => match inline.or_else(|| rest.next().cloned()) {
+                    Some(value) => Some(value),
+                    None => anyhow::bail!("{} expects a value\n\n{}", name, USAGE),
+                },
+                _ => None,
+            };
+            flags.push((name.to_string(), value));
+        }
+
+        match command.as_str() {
+            "snapshot" => {
+                let Some((project, requests)) = positional.split_first() else {
+                    anyhow::bail!("snapshot expects a project\n\n{}", USAGE);
+                };
+                let mut accept = false;
+                for (flag, _) in flags {
+                    match flag.as_str() {
+                        "--accept" => accept = true,
+                        other => anyhow::bail!("Unknown option {}\n\n{}", other, USAGE),
+                    }
+                }
+                Ok(Some(Self::Snapshot { project: project.clone(), requests: requests.to_vec(), accept }))
+            }
+            "mock" => {
+                let [project] = positional.as_slice() else {
+                    anyhow::bail!("mock expects a project\n\n{}", USAGE);
+                };
+                let mut port = DEFAULT_PORT;
+                let mut options = MockOptions::default();
+                for (flag, value) in flags {
+                    let value = value.unwrap_or_default();
+                    match flag.as_str() {
+                        "--port" => port = number(&flag, &value)?,
+                        "--delay" => options.delay = Some(Duration::from_millis(number(&flag, &value)?)),
+                        "--status" => options.status = Some(number(&flag, &value)?),
+                        other => anyhow::bail!("Unknown option {}\n\n{}", other, USAGE),
+                    }
+                }
+                Ok(Some(Self::Mock { project: project.clone(), port, options }))
+            }
+            "proxy" => {
+                let [project] = positional.as_slice() else {
+                    anyhow::bail!("proxy expects a project\n\n{}", USAGE);
+                };
+                let (mut port, mut upstream, mut filter) = (DEFAULT_PORT, None, CaptureFilter::default());
+                let (mut record_responses, mut replay) = (false, false);
+                for (flag, value) in flags {
+                    let value = value.unwrap_or_default();
+                    if filter_flag(&mut filter, &flag, &value) {
+                        continue;
+                    }
+                    match flag.as_str() {
+                        "--port" => port = number(&flag, &value)?,
+                        "--upstream" => upstream = Some(value),
+                        "--record-responses" => record_responses = true,
+                        "--replay" => replay = true,
+                        other => anyhow::bail!("Unknown option {}\n\n{}", other, USAGE),
+                    }
+                }
+                if replay {
+                    return Ok(Some(Self::Replay { project: project.clone(), port }));
+                }
+                Ok(Some(Self::Proxy { project: project.clone(), port, upstream, filter, record_responses }))
+            }
+            "import" => {
+                let [project, file] = positional.as_slice() else {
+                    anyhow::bail!("import expects a project and a file\n\n{}", USAGE);
+                };
+                let (mut filter, mut folders_as_projects) = (CaptureFilter::default(), false);
+                for (flag, value) in flags {
+                    if flag == "--folders-as-projects" {
+                        folders_as_projects = true;
+                    } else if !filter_flag(&mut filter, &flag, &value.unwrap_or_default()) {
+                        anyhow::bail!("Unknown option {}\n\n{}", flag, USAGE);
+                    }
+                }
+                Ok(Some(Self::Import { project: project.clone(), file: PathBuf::from(file), filter, folders_as_projects }))
+            }
+            "export" => {
+                let [project, file, requests @ ..] = positional.as_slice() else {
+                    anyhow::bail!("export expects a project and a file\n\n{}", USAGE);
+                };
+                if let Some((flag, _)) = flags.first() {
+                    anyhow::bail!("Unknown option {}\n\n{}", flag, USAGE);
+                }
+                Ok(Some(Self::Export { project: project.clone(), file: PathBuf::from(file), requests: requests.to_vec() }))
+            }
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => anyhow::bail!("Unknown command {}\n\n{}", other, USAGE),
+        }
+    }
+}
+
+/// Applies the options shared by commands filtering calls. Returns whether `flag` was one of them.
+fn filter_flag(filter: &mut CaptureFilter, flag: &str, value: &str) -> bool {
+    let list = match flag {
+        "--host" => &mut filter.hosts,
+        "--path" => &mut filter.paths,
+        "--method" => &mut filter.methods,
+        "--content-type" => &mut filter.content_types,
+        _ => return false,
+    };
+    list.push(value.to_string());
+    true
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| anyhow::anyhow!("{} expects a number, found {}", flag, value))
+}
+
+/// Runs commands headless, printing results to stdout.
+pub struct Cli {
+    pub list_projects: ListProjectsUseCase,
+    pub request_repo: Arc<dyn RequestRepository>,
+    pub load_environment: LoadEnvironmentUseCase,
+    pub execute_request: Arc<ExecuteRequestUseCase>,
+    pub call_grpc: CallGrpcUseCase,
+    pub snapshots: SnapshotResponseUseCase,
+    pub validate_response: ValidateResponseUseCase,
+    pub serve_mock: ServeMockUseCase,
+    pub record_traffic: RecordTrafficUseCase,
+    pub import_har: ImportHarUseCase,
+    pub import_collection: ImportCollectionUseCase,
+    pub import_openapi: ImportOpenApiUseCase,
+    pub export_har: ExportHarUseCase,
+}
+
+impl Cli {
+    /// Runs a command and returns the process exit code.
+    pub async fn run(&self, command: Command) -> Result<i32> {
+        match command {
+            Command::Snapshot { project, requests, accept } => self.snapshot(&Project::new(project), requests, accept).await,
+            Command::Mock { project, port, options } => self.mock(&Project::new(project), port, options).await,
+            Command::Proxy { project, port, upstream, filter, record_responses } => {
+                self.proxy(&Project::new(project), port, upstream, filter, record_responses).await
+            }
+            // Replaying serves the recorded `.mock` files exactly as they were captured
+            Command::Replay { project, port } => self.mock(&Project::new(project), port, MockOptions { exact: true, ..Default::default() }).await,
+            Command::Import { project, file, filter, folders_as_projects } => {
+                self.import(&Project::new(project), &file, &filter, folders_as_projects)
+            }
+            Command::Export { project, file, requests } => self.export(&Project::new(project), &file, &requests),
+        }
+    }
+
+    fn import(&self, project: &Project, file: &Path, filter: &CaptureFilter, folders_as_projects: bool) -> Result<i32> {
+        let format = file.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        let report = match format.as_str() {
+            "har" => {
+                let ids = self.import_har.execute(project, file, filter)?;
+                ImportReport { requests: ids.into_iter().map(|id| (project.clone(), id)).collect(), ..Default::default() }
+            }
+            "json" | "yaml" | "yml" if self.import_openapi.accepts(file) => self.import_openapi.execute(project, file)?,
+            "json" | "yaml" | "yml" => self.import_collection.execute(project, file, folders_as_projects)?,
+            _ => anyhow::bail!("Unsupported file {:?}: expected .har, .json or .yaml", file),
+        };
+        for (project, id) in &report.requests {
+            println!("IMPORTED {}/{}", project.name, id);
+        }
+        for warning in &report.warnings {
+            println!("WARNING  {}", warning);
+        }
+        println!("\n{} requests imported ({} updated), {} warnings", report.requests.len(), report.updated, report.warnings.len());
+        Ok(0)
+    }
+
+    fn export(&self, project: &Project, file: &Path, requests: &[String]) -> Result<i32> {
+        let environment = self.load_environment.execute(project)?;
+        let count = self.export_har.execute(project, requests, &environment, file)?;
+        println!("{} responses exported to {}", count, file.display());
+        Ok(0)
+    }
+
+    async fn proxy(&self, project: &Project, port: u16, upstream: Option<String>, filter: CaptureFilter, record_responses: bool) -> Result<i32> {
+        let (events_tx, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let serve = self.record_traffic.serve(SocketAddr::from(([127, 0, 0, 1], port)), upstream.clone(), Arc::new(Forward(events_tx)));
+        tokio::pin!(serve);
+        loop {
+            tokio::select! {
+                result = &mut serve => return result.map(|_| 0),
+                Some(event) = events.recv() => match event {
+                    ServerEvent::Listening(address) => {
+                        match &upstream {
+                            Some(upstream) => println!("Proxying http://{} to {} (Ctrl-C to stop)", address, upstream),
+                            None => println!("Proxying on http://{}, set HTTP_PROXY to use it (Ctrl-C to stop)", address),
+                        }
+                        println!("Recording into {}{}\n", project.name, if record_responses { " with responses" } else { "" });
+                    }
+                    ServerEvent::Reported(call) => {
+                        let line = format!("{} {} -> {}", call.request.method, call.request.url, call.response.status);
+                        match self.record_traffic.record(project, &call, &filter, record_responses) {
+                            Ok(Some(id)) => println!("{} saved {}", line, id),
+                            Ok(None) => println!("{} (filtered)", line),
+                            Err(e) => println!("{} not saved: {:#}", line, e),
+                        }
+                    }
+                },
+                _ = tokio::signal::ctrl_c() => return Ok(0),
+            }
+        }
+    }
+
+    async fn mock(&self, project: &Project, port: u16, options: MockOptions) -> Result<i32> {
+        let router = self.serve_mock.router(project, options)?;
+        for route in router.routes() {
+            let source = match &route.response {
+                Some(response) => response.status.to_string(),
+                None => "---".to_string(),
+            };
+            println!("{:<7} {:<40} {} {}", route.method, route.path, source, route.request_id);
+        }
+        let (events_tx, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let serve = self.serve_mock.serve(SocketAddr::from(([127, 0, 0, 1], port)), router, Arc::new(Forward(events_tx)));
+        tokio::pin!(serve);
+        loop {
+            tokio::select! {
+                result = &mut serve => return result.map(|_| 0),
+                Some(event) = events.recv() => match event {
+                    ServerEvent::Listening(address) => println!("\nServing {} on http://{} (Ctrl-C to stop)", project.name, address),
+                    ServerEvent::Reported(hit) => println!(
+                        "{} {} -> {} {}",
+                        hit.method,
+                        hit.path,
+                        hit.reply.status,
+                        hit.reply.request_id.as_deref().unwrap_or("(no match)")
+                    ),
+                },
+                _ = tokio::signal::ctrl_c() => return Ok(0),
+            }
+        }
+    }
+
+    async fn snapshot(&self, project: &Project, requests: Vec<String>, accept: bool) -> Result<i32> {
+        let environment = self.load_environment.execute(project)?;
+        let requests = if requests.is_empty() { self.list_projects.list_requests(project)? } else { requests };
+
+        let (mut passed, mut failed, mut new) = (0, 0, 0);
+        for request_id in requests {
+            let request = self.request_repo.load(project, &request_id)?;
+            if request.is_websocket() {
+                println!("SKIP     {} (WebSocket)", request_id);
+                continue;
+            }
+            let response = if request.is_grpc() {
+                self.call_grpc.execute(project, &request, &environment).await
+            } else {
+                self.execute_request.execute(&request, &environment).await
+            };
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    println!("ERROR    {}: {}", request_id, environment.redact(&format!("{:#}", e)));
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            // Contract violations fail the request whatever its snapshot says
+            let violations = self.validate_response.execute(project, &request, &response, &environment)?.unwrap_or_default();
+            let report_violations = || {
+                for violation in &violations {
+                    println!("    openapi: {}", violation);
+                }
+            };
+
+            if accept {
+                self.snapshots.accept(project, &request_id, &request, &response, &environment)?;
+                if violations.is_empty() {
+                    println!("ACCEPTED {}", request_id);
+                    passed += 1;
+                } else {
+                    println!("FAIL     {} (accepted, but violates the OpenAPI document)", request_id);
+                    report_violations();
+                    failed += 1;
+                }
+                continue;
+            }
+            match self.snapshots.check(project, &request_id, &request, &response, &environment)? {
+                SnapshotResult::Match if violations.is_empty() => {
+                    println!("PASS     {}", request_id);
+                    passed += 1;
+                }
+                SnapshotResult::New(_) if violations.is_empty() => {
+                    println!("NEW      {} (no snapshot yet, run with --accept to record it)", request_id);
+                    new += 1;
+                }
+                result => {
+                    println!("FAIL     {}", request_id);
+                    report_violations();
+                    if let Some(diff) = result.diff() {
+                        print_diff(&diff);
+                    }
+                    failed += 1;
+                }
+            }
+        }
+
+        println!("\n{} passed, {} failed, {} without snapshot", passed, failed, new);
+        Ok(if failed > 0 { 1 } else { 0 })
+    }
+}
+
+/// What a server running in the background reports to the command loop.
+enum ServerEvent<T> {
+    Listening(SocketAddr),
+    Reported(T),
+}
+
+/// Hands what a server reports over to the command loop.
+struct Forward<T>(UnboundedSender<ServerEvent<T>>);
+
+impl MockHitSink for Forward<MockHit> {
+    fn listening(&self, address: SocketAddr) {
+        let _ = self.0.send(ServerEvent::Listening(address));
+    }
+
+    fn hit(&self, hit: MockHit) {
+        let _ = self.0.send(ServerEvent::Reported(hit));
+    }
+}
+
+impl CaptureSink for Forward<CapturedCall> {
+    fn listening(&self, address: SocketAddr) {
+        let _ = self.0.send(ServerEvent::Listening(address));
+    }
+
+    fn captured(&self, call: CapturedCall) {
+        let _ = self.0.send(ServerEvent::Reported(call));
+    }
+}
+
+/// Lines of unchanged context printed around each change.
+const CONTEXT: usize = 2;
+
+fn print_diff(diff: &ResponseDiff) {
+    if diff.status.0 != diff.status.1 {
+        println!("    status: {} -> {}", diff.status.0, diff.status.1);
+    }
+    for line in &diff.headers {
+        match line {
+            DiffLine::Same(_) => {}
+            DiffLine::Added(text) => println!("    + header {}", text),
+            DiffLine::Removed(text) => println!("    - header {}", text),
+        }
+    }
+    let changed: Vec<usize> = (0..diff.lines.len()).filter(|&i| !matches!(diff.lines[i], DiffLine::Same(_))).collect();
+    let near_change = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= CONTEXT);
+
+    let mut skipped = false;
+    for (i, line) in diff.lines.iter().enumerate() {
+        if !near_change(i) {
+            skipped = true;
+            continue;
+        }
+        if std::mem::take(&mut skipped) {
+            println!("    ...");
+        }
+        match line {
+            DiffLine::Same(text) => println!("      {}", text),
+            DiffLine::Added(text) => println!("    + {}", text),
+            DiffLine::Removed(text) => println!("    - {}", text),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::keymap::{Action, Keymap};
+use crate::state::{AppMode, AppState, FocusPane};
+
+/// Where a key of the help applies.
+pub struct HelpSection {
+    pub title: &'static str,
+    pub entries: Vec<HelpEntry>,
+}
+
+pub struct HelpEntry {
+    pub keys: String,
+    pub description: &'static str,
+}
+
+/// Keys typed while a wizard, prompt or popup is open; not configurable.
+const FIXED_KEYS: [(&str, &str); 7] = [
+    ("enter", "Confirm, next step of the new request wizard"),
+    ("esc", "Cancel and close"),
+    ("up, down, ctrl-p, ctrl-n", "Choose in a list"),
+    ("backspace", "Delete the last character"),
+    ("tab", "Switch what the finder or search matches"),
+    ("y", "Confirm a delete"),
+    ("n", "Cancel a delete"),
+];
+
+/// Every action by context, with the keys currently bound to it.
+pub fn sections(keymap: &Keymap) -> Vec<HelpSection> {
+    let bound = |title, actions: &[(Action, &'static str)]| HelpSection {
+        title,
+        entries: actions.iter().map(|&(action, description)| HelpEntry { keys: keys(keymap, action), description }).collect(),
+    };
+    vec![
+        bound(
+            "Normal",
+            &[
+                (Action::Next, "Select the next project or request"),
+                (Action::Previous, "Select the previous project or request"),
+                (Action::SwitchFocus, "Switch between projects and requests"),
+                (Action::FocusProjects, "Focus projects"),
+                (Action::FocusRequests, "Focus requests"),
+                (Action::Execute, "Run the request, open a project or fold a folder"),
+                (Action::Edit, "Edit the request in $EDITOR"),
+                (Action::NewProject, "New project"),
+                (Action::NewRequest, "New request in the current folder"),
+                (Action::NewFolder, "New folder in the current folder"),
+                (Action::Rename, "Rename or move the selected item"),
+                (Action::Duplicate, "Duplicate the selected item"),
+                (Action::Delete, "Move the selected item to the trash"),
+                (Action::Undo, "Restore the last deleted item"),
+                (Action::Finder, "Find a project or request by name"),
+                (Action::Search, "Search the text of every request"),
+                (Action::Introspect, "Introspect the GraphQL schema"),
+                (Action::Help, "Show this help"),
+                (Action::Quit, "Quit"),
+            ],
+        ),
+        bound(
+            "Response view",
+            &[
+                (Action::Execute, "Run the request again"),
+                (Action::Back, "Close the response, or cancel a running stream"),
+                (Action::DiffPrevious, "Diff with the previous response"),
+                (Action::PinBaseline, "Pin the response as baseline"),
+                (Action::DiffBaseline, "Diff with the baseline"),
+                (Action::SnapshotDiff, "Diff with the snapshot"),
+                (Action::AcceptSnapshot, "Accept the response as snapshot"),
+            ],
+        ),
+        bound(
+            "Diff view",
+            &[
+                (Action::Next, "Scroll down"),
+                (Action::Previous, "Scroll up"),
+                (Action::ToggleDiffLayout, "Switch between unified and side by side"),
+                (Action::Back, "Close the diff"),
+            ],
+        ),
+        bound(
+            "Search results",
+            &[
+                (Action::Next, "Next matching line"),
+                (Action::Previous, "Previous matching line"),
+                (Action::Execute, "Go to the request"),
+                (Action::Edit, "Edit the request at the matching line"),
+                (Action::Search, "Search again"),
+                (Action::Back, "Close the results"),
+            ],
+        ),
+        HelpSection {
+            title: "Wizards and prompts",
+            entries: FIXED_KEYS.iter().map(|&(keys, description)| HelpEntry { keys: keys.to_string(), description }).collect(),
+        },
+        bound(
+            "Help",
+            &[
+                (Action::Next, "Scroll down (pagedown, end for more)"),
+                (Action::Previous, "Scroll up (pageup, home for more)"),
+                (Action::Search, "Filter, <enter> to keep, <esc> to clear"),
+                (Action::Back, "Close"),
+            ],
+        ),
+    ]
+}
+
+/// Keeps the entries matching `query` in their keys or description, or whole sections by title.
+pub fn filter(sections: Vec<HelpSection>, query: &str) -> Vec<HelpSection> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return sections;
+    }
+    sections
+        .into_iter()
+        .filter_map(|mut section| {
+            if !section.title.to_lowercase().contains(&query) {
+                section.entries.retain(|e| e.keys.to_lowercase().contains(&query) || e.description.to_lowercase().contains(&query));
+            }
+            (!section.entries.is_empty()).then_some(section)
+        })
+        .collect()
+}
+
+/// The most useful keys of the current mode, for the status bar.
+pub fn hints(state: &AppState) -> Vec<(String, &'static str)> {
+    let keymap = &state.keymap;
+    let bound = |actions: &[(Action, &'static str)]| actions.iter().map(|&(action, label)| (keymap.key_for(action), label)).collect();
+    let fixed = |keys: &[(&str, &'static str)]| keys.iter().map(|&(key, label)| (key.to_string(), label)).collect();
+    match state.mode {
+        AppMode::Normal if state.focused_pane == FocusPane::Projects => {
+            bound(&[(Action::Execute, "open"), (Action::NewProject, "new"), (Action::Finder, "find"), (Action::Help, "help"), (Action::Quit, "quit")])
+        }
+        AppMode::Normal => bound(&[
+            (Action::Execute, "run"),
+            (Action::Edit, "edit"),
+            (Action::NewRequest, "new"),
+            (Action::Finder, "find"),
+            (Action::Help, "help"),
+            (Action::Quit, "quit"),
+        ]),
+        AppMode::ViewingResponse => bound(&[
+            (Action::Back, "close"),
+            (Action::Execute, "rerun"),
+            (Action::DiffPrevious, "diff"),
+            (Action::SnapshotDiff, "snapshot"),
+            (Action::Help, "help"),
+        ]),
+        AppMode::Diff => bound(&[(Action::Next, "scroll"), (Action::ToggleDiffLayout, "layout"), (Action::Back, "close"), (Action::Help, "help")]),
+        AppMode::SearchResults => {
+            bound(&[(Action::Execute, "go to"), (Action::Edit, "edit"), (Action::Search, "search again"), (Action::Back, "close"), (Action::Help, "help")])
+        }
+        AppMode::Help if state.help.as_ref().is_some_and(|h| h.filtering) => fixed(&[("enter", "keep filter"), ("esc", "clear filter")]),
+        AppMode::Help => bound(&[(Action::Next, "scroll"), (Action::Search, "filter"), (Action::Back, "close")]),
+        AppMode::ConfirmingDelete => fixed(&[("y", "delete"), ("n", "cancel")]),
+        AppMode::Finder => fixed(&[("enter", "jump"), ("tab", "names/URLs"), ("esc", "close")]),
+        AppMode::Searching => fixed(&[("enter", "search"), ("tab", "text/regex"), ("esc", "cancel")]),
+        AppMode::WebSocket => fixed(&[("enter", "send"), ("tab", "edit message"), ("esc", "disconnect")]),
+        AppMode::CreatingRequestMethod | AppMode::CreatingRequestBody => fixed(&[("up/down", "choose"), ("enter", "next"), ("esc", "cancel")]),
+        AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingFolder | AppMode::Renaming | AppMode::Duplicating => {
+            fixed(&[("enter", "confirm"), ("esc", "cancel")])
+        }
+    }
+}
+
+fn keys(keymap: &Keymap, action: Action) -> String {
+    let keys = keymap.keys_for(action);
+    if keys.is_empty() {
+        "unbound".to_string()
+    } else {
+        keys.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(sections: &[HelpSection]) -> Vec<(&str, Vec<&str>)> {
+        sections.iter().map(|s| (s.title, s.entries.iter().map(|e| e.description).collect())).collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let keymap = Keymap::default();
+        assert_eq!(filter(sections(&keymap), "  ").len(), sections(&keymap).len());
+
+        // Descriptions and keys match without case; a matching title keeps its whole section
+        assert_eq!(summary(&filter(sections(&keymap), "SNAPSHOT")), vec![("Response view", vec!["Diff with the snapshot", "Accept the response as snapshot"])]);
+        assert_eq!(summary(&filter(sections(&keymap), "ctrl-p")), vec![
+            ("Normal", vec!["Find a project or request by name"]),
+            ("Wizards and prompts", vec!["Choose in a list"]),
+        ]);
+        let diff = filter(sections(&keymap), "diff view");
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].entries.len(), 4);
+        assert!(filter(sections(&keymap), "nothing like this").is_empty());
+    }
+
+    #[test]
+    fn test_sections_show_the_bound_keys() {
+        let keymap = Keymap::parse("[bindings]\nundo = []\nnext = [\"j\", \"ctrl-n\"]\n").unwrap();
+        let sections = sections(&keymap);
+        let keys = |description: &str| sections[0].entries.iter().find(|e| e.description == description).unwrap().keys.clone();
+        assert_eq!(keys("Restore the last deleted item"), "unbound");
+        assert_eq!(keys("Select the next project or request"), "j, ctrl-n");
+    }
+}
@@ -0,0 +1,444 @@
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+use std::path::Path;
+
+/// What a key does in the lists and response views, independent of the key itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Next,
+    Previous,
+    SwitchFocus,
+    FocusProjects,
+    FocusRequests,
+    Execute,
+    Back,
+    Edit,
+    NewProject,
+    NewRequest,
+    NewFolder,
+    Rename,
+    Duplicate,
+    Delete,
+    Undo,
+    Finder,
+    Search,
+    Introspect,
+    DiffPrevious,
+    DiffBaseline,
+    PinBaseline,
+    ToggleDiffLayout,
+    SnapshotDiff,
+    AcceptSnapshot,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 26] = [
+        Action::Quit,
+        Action::Next,
+        Action::Previous,
+        Action::SwitchFocus,
+        Action::FocusProjects,
+        Action::FocusRequests,
+        Action::Execute,
+        Action::Back,
+        Action::Edit,
+        Action::NewProject,
+        Action::NewRequest,
+        Action::NewFolder,
+        Action::Rename,
+        Action::Duplicate,
+        Action::Delete,
+        Action::Undo,
+        Action::Finder,
+        Action::Search,
+        Action::Introspect,
+        Action::DiffPrevious,
+        Action::DiffBaseline,
+        Action::PinBaseline,
+        Action::ToggleDiffLayout,
+        Action::SnapshotDiff,
+        Action::AcceptSnapshot,
+        Action::Help,
+    ];
+
+    /// The name used in `keys.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Next => "next",
+            Action::Previous => "previous",
+            Action::SwitchFocus => "switch_focus",
+            Action::FocusProjects => "focus_projects",
+            Action::FocusRequests => "focus_requests",
+            Action::Execute => "execute",
+            Action::Back => "back",
+            Action::Edit => "edit",
+            Action::NewProject => "new_project",
+            Action::NewRequest => "new_request",
+            Action::NewFolder => "new_folder",
+            Action::Rename => "rename",
+            Action::Duplicate => "duplicate",
+            Action::Delete => "delete",
+            Action::Undo => "undo",
+            Action::Finder => "finder",
+            Action::Search => "search",
+            Action::Introspect => "introspect",
+            Action::DiffPrevious => "diff_previous",
+            Action::DiffBaseline => "diff_baseline",
+            Action::PinBaseline => "pin_baseline",
+            Action::ToggleDiffLayout => "toggle_diff_layout",
+            Action::SnapshotDiff => "snapshot_diff",
+            Action::AcceptSnapshot => "accept_snapshot",
+            Action::Help => "help",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Next => &["j", "down"],
+            Action::Previous => &["k", "up"],
+            Action::SwitchFocus => &["tab"],
+            Action::FocusProjects => &["h"],
+            Action::FocusRequests => &["l"],
+            Action::Execute => &["enter"],
+            Action::Back => &["esc"],
+            Action::Edit => &["e"],
+            Action::NewProject => &["N"],
+            Action::NewRequest => &["n"],
+            Action::NewFolder => &["F"],
+            Action::Rename => &["R"],
+            Action::Duplicate => &["c"],
+            Action::Delete => &["x"],
+            Action::Undo => &["u"],
+            Action::Finder => &["ctrl-p"],
+            Action::Search => &["/"],
+            Action::Introspect => &["I"],
+            Action::DiffPrevious => &["D"],
+            Action::DiffBaseline => &["B"],
+            Action::PinBaseline => &["P"],
+            Action::ToggleDiffLayout => &["v"],
+            Action::SnapshotDiff => &["S"],
+            Action::AcceptSnapshot => &["A"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// A key with its modifiers. Letters carry Shift in their case: `G`, not `shift-g`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn from_event(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code: event.code, modifiers }
+    }
+
+    /// Parses `j`, `G`, `ctrl-p`, `C-x`, `alt-enter`, `shift-tab`, `f5` or `space`.
+    pub fn parse(text: &str) -> Result<Self> {
+        // `-` itself is a key: `-`, `ctrl--`
+        let (prefix, key) = match text.strip_suffix("--") {
+            Some(prefix) => (Some(prefix), "-"),
+            None if text == "-" => (None, "-"),
+            None => match text.rsplit_once('-') {
+                Some((prefix, key)) => (Some(prefix), key),
+                None => (None, text),
+            },
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.into_iter().flat_map(|p| p.split('-')) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" | "c" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => anyhow::bail!("Unknown modifier '{}' in '{}'", modifier, text),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => anyhow::bail!("Unknown key '{}' in '{}'", key, text),
+                },
+            },
+        };
+        Ok(match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                Self { code: KeyCode::Char(c.to_ascii_uppercase()), modifiers: modifiers - KeyModifiers::SHIFT }
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => Self { code: KeyCode::BackTab, modifiers: modifiers - KeyModifiers::SHIFT },
+            code => Self { code, modifiers },
+        })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::BackTab => write!(f, "shift-tab"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => write!(f, "{}", format!("{:?}", code).to_lowercase()),
+        }
+    }
+}
+
+/// Key sequences bound to actions, read from `keys.toml` over the defaults.
+pub struct Keymap {
+    bindings: Vec<(Vec<KeyChord>, Action)>,
+    pending: Vec<KeyChord>, // Start of a sequence typed so far
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .flat_map(|&action| action.default_keys().iter().map(move |keys| (parse_sequence(keys).expect("default keys parse"), action)))
+            .collect();
+        Self { bindings, pending: Vec::new() }
+    }
+}
+
+impl Keymap {
+    /// Reads a `keys.toml`; without the file the defaults apply.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        Self::parse(&text).with_context(|| format!("Invalid key bindings in {:?}", path))
+    }
+
+    /// Parses a `[bindings]` table of action names to a key sequence or a list of them:
+    ///
+    /// ```toml
+    /// [bindings]
+    /// next = ["j", "down", "ctrl-n"]
+    /// quit = "ctrl-x ctrl-c"
+    /// ```
+    ///
+    /// Listed actions replace their default keys (`[]` unbinds one); conflicts are errors.
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+        let mut keymap = Self::default();
+        let Some(bindings) = table.get("bindings") else {
+            return Ok(keymap);
+        };
+        let bindings = bindings.as_table().context("[bindings] must be a table")?;
+        for (name, keys) in bindings {
+            let action = Action::ALL.into_iter().find(|a| a.name() == name).with_context(|| format!("Unknown action '{}'", name))?;
+            let keys: Vec<&str> = match keys {
+                toml::Value::String(keys) => vec![keys],
+                toml::Value::Array(keys) => keys.iter().map(|k| k.as_str().with_context(|| format!("Keys of '{}' must be strings", name))).collect::<Result<_>>()?,
+                _ => anyhow::bail!("Keys of '{}' must be a string or a list of strings", name),
+            };
+            keymap.bindings.retain(|(_, a)| *a != action);
+            for keys in keys {
+                keymap.bindings.push((parse_sequence(keys)?, action));
+            }
+        }
+        keymap.check()?;
+        Ok(keymap)
+    }
+
+    /// Two actions on the same keys, or keys that start a longer sequence, could never both run.
+    fn check(&self) -> Result<()> {
+        let mut conflicts = Vec::new();
+        for (i, (keys, action)) in self.bindings.iter().enumerate() {
+            for (other_keys, other) in &self.bindings[i + 1..] {
+                let (short, long) = if keys.len() <= other_keys.len() { (keys, other_keys) } else { (other_keys, keys) };
+                if long.starts_with(short) && (action != other || keys != other_keys) {
+                    conflicts.push(format!("'{}' ({}) and '{}' ({})", format_sequence(keys), action.name(), format_sequence(other_keys), other.name()));
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            anyhow::bail!("Conflicting key bindings: {}", conflicts.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Feeds a key press; returns the action once a whole sequence is typed.
+    /// A key that does not continue the pending sequence starts a new one.
+    pub fn feed(&mut self, event: KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from_event(event);
+        self.pending.push(chord);
+        loop {
+            if let Some((_, action)) = self.bindings.iter().find(|(keys, _)| *keys == self.pending) {
+                let action = *action;
+                self.pending.clear();
+                return Some(action);
+            }
+            if self.bindings.iter().any(|(keys, _)| keys.starts_with(&self.pending)) {
+                return None;
+            }
+            if self.pending.len() == 1 {
+                self.pending.clear();
+                return None;
+            }
+            self.pending = vec![chord];
+        }
+    }
+
+    /// The keys of an unfinished sequence, for the status bar.
+    pub fn pending(&self) -> Option<String> {
+        (!self.pending.is_empty()).then(|| format_sequence(&self.pending))
+    }
+
+    /// The key sequences bound to an action, written as in `keys.toml`.
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings.iter().filter(|(_, a)| *a == action).map(|(keys, _)| format_sequence(keys)).collect()
+    }
+
+    /// The first binding of an action for hints, `unbound` if it has none.
+    pub fn key_for(&self, action: Action) -> String {
+        self.keys_for(action).into_iter().next().unwrap_or_else(|| "unbound".to_string())
+    }
+}
+
+/// Chords separated by spaces: `g g`, `ctrl-x ctrl-s`.
+fn parse_sequence(text: &str) -> Result<Vec<KeyChord>> {
+    let chords: Vec<KeyChord> = text.split_whitespace().map(KeyChord::parse).collect::<Result<_>>()?;
+    if chords.is_empty() {
+        anyhow::bail!("Empty key sequence");
+    }
+    Ok(chords)
+}
+
+fn format_sequence(chords: &[KeyChord]) -> String {
+    chords.iter().map(KeyChord::to_string).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn chord(text: &str) -> KeyChord {
+        KeyChord::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!(chord("j"), KeyChord { code: KeyCode::Char('j'), modifiers: KeyModifiers::NONE });
+        assert_eq!(chord("C-x"), chord("ctrl-x"));
+        assert_eq!(chord("shift-g"), chord("G"));
+        assert_eq!(chord("shift-tab"), KeyChord { code: KeyCode::BackTab, modifiers: KeyModifiers::NONE });
+        assert_eq!(chord("alt-Enter"), KeyChord { code: KeyCode::Enter, modifiers: KeyModifiers::ALT });
+        assert_eq!(chord("ctrl--"), KeyChord { code: KeyCode::Char('-'), modifiers: KeyModifiers::CONTROL });
+        assert_eq!(chord("-"), KeyChord { code: KeyCode::Char('-'), modifiers: KeyModifiers::NONE });
+        assert_eq!(chord("space"), KeyChord { code: KeyCode::Char(' '), modifiers: KeyModifiers::NONE });
+        assert_eq!(chord("f5"), KeyChord { code: KeyCode::F(5), modifiers: KeyModifiers::NONE });
+        for text in ["ctrl-p", "alt-enter", "shift-tab", "f12", "space", "pageup", "G"] {
+            assert_eq!(chord(text).to_string(), text);
+        }
+
+        assert_eq!(KeyChord::parse("hyper-x").unwrap_err().to_string(), "Unknown modifier 'hyper' in 'hyper-x'");
+        assert_eq!(KeyChord::parse("f13").unwrap_err().to_string(), "Unknown key 'f13' in 'f13'");
+        assert!(parse_sequence("  ").is_err());
+    }
+
+    #[test]
+    fn test_events_match_parsed_chords() {
+        // Terminals report Shift with uppercase letters; the case already says it
+        assert_eq!(KeyChord::from_event(press(KeyCode::Char('G'), KeyModifiers::SHIFT)), chord("G"));
+        assert_eq!(KeyChord::from_event(press(KeyCode::BackTab, KeyModifiers::SHIFT)), chord("shift-tab"));
+        assert_eq!(KeyChord::from_event(press(KeyCode::Char('p'), KeyModifiers::CONTROL)), chord("ctrl-p"));
+    }
+
+    #[test]
+    fn test_defaults_parse_without_conflicts() {
+        let keymap = Keymap::default();
+        keymap.check().unwrap();
+        for action in Action::ALL {
+            assert_eq!(keymap.keys_for(action).len(), action.default_keys().len(), "{}", action.name());
+        }
+        assert_eq!(keymap.keys_for(Action::Next), vec!["j", "down"]);
+    }
+
+    #[test]
+    fn test_bindings_replace_defaults() {
+        let keymap = Keymap::parse("[bindings]\nnext = [\"j\", \"ctrl-n\"]\nundo = []\nquit = \"ctrl-x ctrl-c\"\n").unwrap();
+        assert_eq!(keymap.keys_for(Action::Next), vec!["j", "ctrl-n"]);
+        assert_eq!(keymap.keys_for(Action::Undo), Vec::<String>::new());
+        assert_eq!(keymap.key_for(Action::Undo), "unbound");
+        assert_eq!(keymap.keys_for(Action::Quit), vec!["ctrl-x ctrl-c"]);
+        assert_eq!(keymap.keys_for(Action::Previous), vec!["k", "up"]);
+
+        // The key an unbound action leaves free can be taken
+        let keymap = Keymap::parse("[bindings]\nundo = []\nquit = \"u\"\n").unwrap();
+        assert_eq!(keymap.keys_for(Action::Quit), vec!["u"]);
+    }
+
+    #[test]
+    fn test_invalid_bindings() {
+        let error = |text: &str| format!("{:#}", Keymap::parse(text).err().unwrap());
+        assert_eq!(error("[bindings]\nfly = \"f\"\n"), "Unknown action 'fly'");
+        assert_eq!(error("[bindings]\nquit = 1\n"), "Keys of 'quit' must be a string or a list of strings");
+        assert_eq!(error("[bindings]\nquit = [1]\n"), "Keys of 'quit' must be strings");
+        assert_eq!(error("bindings = 1\n"), "[bindings] must be a table");
+        assert_eq!(error("[bindings]\nquit = \"j\"\n"), "Conflicting key bindings: 'j' (next) and 'j' (quit)");
+        // A key cannot both run an action and start a sequence
+        assert_eq!(error("[bindings]\nquit = \"g g\"\nedit = \"g\"\n"), "Conflicting key bindings: 'g' (edit) and 'g g' (quit)");
+    }
+
+    #[test]
+    fn test_feed_sequences() {
+        let mut keymap = Keymap::parse("[bindings]\nquit = \"ctrl-x ctrl-c\"\n").unwrap();
+        let ctrl = |c| press(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(keymap.feed(ctrl('x')), None);
+        assert_eq!(keymap.pending(), Some("ctrl-x".to_string()));
+        assert_eq!(keymap.feed(ctrl('c')), Some(Action::Quit));
+        assert_eq!(keymap.pending(), None);
+
+        // A key that breaks the sequence starts over on its own
+        assert_eq!(keymap.feed(ctrl('x')), None);
+        assert_eq!(keymap.feed(press(KeyCode::Char('j'), KeyModifiers::NONE)), Some(Action::Next));
+        assert_eq!(keymap.feed(press(KeyCode::Char('z'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.pending(), None);
+    }
+}
@@ -8,46 +8,172 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{io, sync::Arc, time::Duration};
 
 mod app;
+mod cli;
+mod help;
+mod keymap;
 mod state;
+mod syntax;
+mod theme;
 mod ui;
 
 use app::App;
+use keymap::{Action, Keymap};
+use theme::Theme;
 use htup_core::{
     infra::{
         fs_project_repository::FsProjectRepository,
+        fs_trash_repository::FsTrashRepository,
+        fs_project_watcher::FsProjectWatcher,
         fs_repository::FsRequestRepository,
+        fs_environment_repository::FsEnvironmentRepository,
+        fs_token_cache::FsTokenCache,
+        system_clock::SystemClock,
+        regex_matcher::RegexPatternCompiler,
+        fs_schema_repository::FsSchemaRepository,
+        fs_proto_repository::FsProtoRepository,
+        fs_history_repository::FsHistoryRepository,
+        fs_snapshot_repository::FsSnapshotRepository,
+        fs_mock_repository::FsMockRepository,
+        mock_server::HyperMockServer,
+        proxy_server::HyperProxyServer,
+        fs_har_repository::FsHarRepository,
+        fs_collection_repository::FsCollectionRepository,
+        fs_api_spec_repository::FsApiSpecRepository,
+        jsonschema_validator::JsonSchemaValidator,
+        grpc_client::TonicGrpcClient,
+        oauth2_client::ReqwestOAuth2Client,
+        sigv4_signer::SigV4Signer,
+        hmac_signer::HmacSigner,
         reqwest_client::ReqwestHttpClient,
+        websocket_client::TungsteniteWebSocketClient,
     },
     usecase::{
         execute_request::ExecuteRequestUseCase,
         list_projects::ListProjectsUseCase,
         create_project::CreateProjectUseCase,
         create_request::CreateRequestUseCase,
+        load_environment::LoadEnvironmentUseCase,
+        acquire_token::AcquireTokenUseCase,
+        introspect_schema::IntrospectSchemaUseCase,
+        websocket_session::OpenWebSocketUseCase,
+        call_grpc::CallGrpcUseCase,
+        diff_responses::DiffResponsesUseCase,
+        snapshot_response::SnapshotResponseUseCase,
+        serve_mock::ServeMockUseCase,
+        record_traffic::RecordTrafficUseCase,
+        import_har::ImportHarUseCase,
+        export_har::ExportHarUseCase,
+        import_collection::ImportCollectionUseCase,
+        import_openapi::ImportOpenApiUseCase,
+        validate_response::ValidateResponseUseCase,
+        move_request::MoveRequestUseCase,
+        manage_project::ManageProjectUseCase,
+        delete::DeleteUseCase,
+        find_request::FindRequestUseCase,
+        search_requests::SearchRequestsUseCase,
+        watch_projects::WatchProjectsUseCase,
     },
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Setup Terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::Command::parse(&args)?;
 
     // Setup Dependencies (DI)
     let root_dir = std::env::current_dir()?;
     let project_repo = Arc::new(FsProjectRepository::new(root_dir.clone()));
     let request_repo = Arc::new(FsRequestRepository::new(root_dir.clone()));
+    let environment_repo = Arc::new(FsEnvironmentRepository::new(root_dir.clone()));
+    let schema_repo = Arc::new(FsSchemaRepository::new(root_dir.clone()));
+    let proto_repo = Arc::new(FsProtoRepository::new(root_dir.clone()));
+    let history_repo = Arc::new(FsHistoryRepository::new(root_dir.clone()));
+    let snapshot_repo = Arc::new(FsSnapshotRepository::new(root_dir.clone()));
+    let mock_repo = Arc::new(FsMockRepository::new(root_dir.clone()));
+    let har_repo = Arc::new(FsHarRepository::new());
+    let spec_repo = Arc::new(FsApiSpecRepository::new(root_dir.clone()));
+    let project_watcher = Arc::new(FsProjectWatcher::new(root_dir.clone()));
+    let clock = Arc::new(SystemClock);
+    let trash_repo = Arc::new(FsTrashRepository::new(root_dir.clone(), clock.clone()));
     let command_editor = Arc::new(htup_core::infra::command_editor::SystemCommandEditor::new(root_dir));
     let http_client = Arc::new(ReqwestHttpClient::new());
+    let oauth2_client = Arc::new(ReqwestOAuth2Client::new(clock.clone()));
+    // Tokens are secrets, so they are cached outside of the (git tracked) projects
+    let token_cache_path = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("htup")
+        .join("oauth2_tokens.json");
+    let token_cache = Arc::new(FsTokenCache::new(token_cache_path));
 
     // Setup UseCases
     let list_projects = ListProjectsUseCase::new(project_repo.clone());
-    let execute_request = ExecuteRequestUseCase::new(http_client);
-    let create_project = CreateProjectUseCase::new(project_repo);
+    let execute_request = Arc::new(
+        ExecuteRequestUseCase::new(http_client)
+            .with_oauth2(AcquireTokenUseCase::new(oauth2_client, token_cache, clock.clone()))
+            .with_signer(Arc::new(SigV4Signer::new(clock.clone())))
+            .with_signer(Arc::new(HmacSigner::new(clock.clone()))),
+    );
+    let introspect_schema = IntrospectSchemaUseCase::new(execute_request.clone(), schema_repo);
+    let open_websocket = OpenWebSocketUseCase::new(execute_request.clone(), Arc::new(TungsteniteWebSocketClient::new()), clock.clone());
+    let grpc_client = Arc::new(TonicGrpcClient::new());
+    let call_grpc = CallGrpcUseCase::new(execute_request.clone(), proto_repo.clone(), grpc_client.clone());
+    let diff_responses = DiffResponsesUseCase::new(history_repo.clone(), clock.clone());
+    let snapshot_usecase = SnapshotResponseUseCase::new(snapshot_repo.clone());
+    let create_project = CreateProjectUseCase::new(project_repo.clone());
     let create_request = CreateRequestUseCase::new(request_repo.clone());
     let edit_request = htup_core::usecase::edit_request::EditRequestUseCase::new(command_editor);
+    let load_environment = LoadEnvironmentUseCase::new(environment_repo.clone());
+    let schema_validator = Arc::new(JsonSchemaValidator::new());
+
+    // Headless commands never touch the terminal
+    if let Some(command) = command {
+        let cli = cli::Cli {
+            list_projects: ListProjectsUseCase::new(project_repo.clone()),
+            request_repo: request_repo.clone(),
+            load_environment: LoadEnvironmentUseCase::new(environment_repo.clone()),
+            execute_request: execute_request.clone(),
+            call_grpc: CallGrpcUseCase::new(execute_request.clone(), proto_repo, grpc_client),
+            snapshots: SnapshotResponseUseCase::new(snapshot_repo),
+            validate_response: ValidateResponseUseCase::new(spec_repo.clone(), schema_validator.clone()),
+            serve_mock: ServeMockUseCase::new(
+                project_repo.clone(),
+                request_repo.clone(),
+                mock_repo.clone(),
+                history_repo.clone(),
+                Arc::new(HyperMockServer::new()),
+            ),
+            record_traffic: RecordTrafficUseCase::new(
+                project_repo.clone(),
+                request_repo.clone(),
+                mock_repo,
+                environment_repo.clone(),
+                Arc::new(HyperProxyServer::new()),
+            ),
+            import_har: ImportHarUseCase::new(project_repo.clone(), request_repo.clone(), environment_repo.clone(), har_repo.clone()),
+            export_har: ExportHarUseCase::new(project_repo.clone(), request_repo.clone(), history_repo, har_repo),
+            import_collection: ImportCollectionUseCase::new(
+                project_repo.clone(),
+                request_repo.clone(),
+                environment_repo.clone(),
+                Arc::new(FsCollectionRepository::new()),
+            ),
+            import_openapi: ImportOpenApiUseCase::new(project_repo, request_repo.clone(), environment_repo, spec_repo),
+        };
+        std::process::exit(cli.run(command).await?);
+    }
+
+    // A broken keys.toml or theme.toml is reported before the terminal is taken over
+    let (keymap, theme) = match config_dir() {
+        Some(dir) => (Keymap::load(&dir.join("keys.toml"))?, Theme::load(&dir)?),
+        None => (Keymap::default(), Theme::default()),
+    };
+
+    // Setup Terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
 
     // Setup App
     let mut app = App::new(
@@ -56,8 +182,23 @@ async fn main() -> Result<()> {
         create_project, 
         create_request, 
         edit_request,
+        load_environment,
+        introspect_schema,
+        open_websocket,
+        call_grpc,
+        diff_responses,
+        snapshot_usecase,
+        ValidateResponseUseCase::new(spec_repo, schema_validator),
+        MoveRequestUseCase::new(project_repo.clone(), request_repo.clone()),
+        ManageProjectUseCase::new(project_repo.clone()),
+        DeleteUseCase::new(project_repo.clone(), request_repo.clone(), trash_repo),
+        FindRequestUseCase::new(project_repo.clone(), request_repo.clone()),
+        SearchRequestsUseCase::new(project_repo.clone(), request_repo.clone(), Arc::new(RegexPatternCompiler)),
+        WatchProjectsUseCase::new(project_watcher),
         request_repo
     );
+    app.state.keymap = keymap;
+    app.state.theme = theme.fit_terminal();
     app.init().await?;
 
     // Run Event Loop
@@ -81,66 +222,149 @@ async fn main() -> Result<()> {
 
 async fn run_app<B: ratatui::backend::Backend + std::io::Write>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
+        app.on_tick();
         terminal.draw(|f| ui::draw(f, &mut app.state))?;
 
         if event::poll(Duration::from_millis(250))? {
             if let Event::Key(key) = event::read()? {
                 match app.state.mode {
                     // Input Mode handling
-                    state::AppMode::CreatingProject | state::AppMode::CreatingRequest | state::AppMode::CreatingRequestMethod | state::AppMode::CreatingRequestBody => {
+                    state::AppMode::CreatingProject | state::AppMode::CreatingRequest | state::AppMode::CreatingRequestMethod | state::AppMode::CreatingRequestBody | state::AppMode::CreatingFolder | state::AppMode::Renaming | state::AppMode::Duplicating | state::AppMode::Finder | state::AppMode::Searching | state::AppMode::WebSocket => {
                         match key.code {
                             KeyCode::Enter => app.on_enter().await?,
                             KeyCode::Esc => app.on_esc(),
                             KeyCode::Backspace => app.on_backspace(),
+                            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.on_up(),
+                            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => app.on_down(),
                             KeyCode::Char(c) => app.on_char(c),
                             KeyCode::Up => app.on_up(),
                             KeyCode::Down => app.on_down(),
+                            KeyCode::Tab => app.on_tab(),
                             _ => {}
                         }
                     }
-                    // Normal Navigation
-                    _ => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        // Navigation
-                        KeyCode::Char('j') => app.next(),
-                        KeyCode::Char('k') => app.previous(),
-                        // Focus Switching
-                        KeyCode::Tab => app.switch_focus(),
-                        KeyCode::Char('h') => app.focus_projects(),
-                        KeyCode::Char('l') => app.focus_requests(),
-                        
-                        // Creation
-                        KeyCode::Char('N') => app.start_create_project(),
-                        KeyCode::Char('n') => app.start_create_request(),
-
-                        // Execution
+                    state::AppMode::ConfirmingDelete => match key.code {
+                        KeyCode::Char('y') | KeyCode::Enter => app.on_confirm_delete()?,
+                        KeyCode::Char('n') | KeyCode::Esc => app.on_esc(),
+                        _ => {}
+                    },
+                    state::AppMode::SearchResults => match app.state.keymap.feed(key) {
+                        Some(Action::Next) => app.next_search_hit(true),
+                        Some(Action::Previous) => app.next_search_hit(false),
+                        Some(Action::Execute) => app.on_enter().await?,
+                        Some(Action::Edit) => run_editor(terminal, app)?,
+                        Some(Action::Search) => app.start_search(),
+                        Some(Action::Help) => app.toggle_help(),
+                        Some(Action::Quit | Action::Back) => app.on_esc(),
+                        _ => {}
+                    },
+                    state::AppMode::Help if app.state.help.as_ref().is_some_and(|h| h.filtering) => match key.code {
                         KeyCode::Enter => app.on_enter().await?,
                         KeyCode::Esc => app.on_esc(),
-                        
-                        // Editing (Important: Suspend Terminal)
-                        KeyCode::Char('e') => {
-                            // Restore terminal for editor
-                            execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-                            disable_raw_mode()?;
-                            terminal.show_cursor()?;
-
-                            // Run Editor
-                            if let Err(e) = app.on_edit() {
-                                app.state.status_message = Some(format!("Edit failed: {}", e));
+                        KeyCode::Backspace => app.on_backspace(),
+                        KeyCode::Char(c) => app.on_char(c),
+                        _ => {}
+                    },
+                    state::AppMode::Help => match key.code {
+                        KeyCode::PageDown => app.scroll_help(10),
+                        KeyCode::PageUp => app.scroll_help(-10),
+                        KeyCode::End => app.scroll_help(isize::MAX),
+                        KeyCode::Home => app.scroll_help(isize::MIN),
+                        _ => match app.state.keymap.feed(key) {
+                            Some(Action::Next) => app.scroll_help(1),
+                            Some(Action::Previous) => app.scroll_help(-1),
+                            Some(Action::Search) => app.start_help_filter(),
+                            Some(Action::Help | Action::Back | Action::Quit) => app.toggle_help(),
+                            _ => {}
+                        },
+                    },
+                    // Normal Navigation
+                    _ => {
+                        if let Some(action) = app.state.keymap.feed(key) {
+                            if on_action(terminal, app, action).await? {
+                                return Ok(());
                             }
-
-                            // Re-enable terminal
-                            enable_raw_mode()?;
-                            execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
-                            terminal.hide_cursor()?;
-                            terminal.clear()?;
-                            // Force redraw immediately
-                            terminal.draw(|f| ui::draw(f, &mut app.state))?;
                         }
-                        _ => {}
                     }
                 }
             }
         }
     }
 }
+
+/// `$XDG_CONFIG_HOME/htup`, by default `~/.config/htup`.
+fn config_dir() -> Option<std::path::PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME").map(std::path::PathBuf::from).or_else(|| dirs::home_dir().map(|h| h.join(".config")))?;
+    Some(config.join("htup"))
+}
+
+/// Runs an action of the lists and response views; `true` quits.
+async fn on_action<B: ratatui::backend::Backend + std::io::Write>(terminal: &mut Terminal<B>, app: &mut App, action: Action) -> Result<bool> {
+    match action {
+        Action::Quit => return Ok(true),
+        Action::Finder => app.start_finder(),
+        Action::Search => app.start_search(),
+        Action::Help => app.toggle_help(),
+        // Navigation
+        Action::Next => app.next(),
+        Action::Previous => app.previous(),
+        // Focus Switching
+        Action::SwitchFocus => app.switch_focus(),
+        Action::FocusProjects => app.focus_projects(),
+        Action::FocusRequests => app.focus_requests(),
+
+        // Creation
+        Action::NewProject => app.start_create_project(),
+        Action::NewRequest => app.start_create_request(),
+        Action::NewFolder => app.start_create_folder(),
+
+        // Housekeeping (on the focused pane)
+        Action::Rename => app.start_rename(),
+        Action::Duplicate => app.start_duplicate(),
+        Action::Delete => app.start_delete(),
+        Action::Undo => app.on_undo()?,
+
+        // GraphQL
+        Action::Introspect => app.on_introspect().await?,
+
+        // Response diffs
+        Action::DiffPrevious => app.on_diff_previous(),
+        Action::DiffBaseline => app.on_diff_baseline(),
+        Action::PinBaseline => app.on_pin_baseline(),
+        Action::ToggleDiffLayout => app.toggle_diff_layout(),
+
+        // Snapshots
+        Action::SnapshotDiff => app.on_show_snapshot_diff(),
+        Action::AcceptSnapshot => app.on_accept_snapshot(),
+
+        // Execution
+        Action::Execute => app.on_enter().await?,
+        Action::Back => app.on_esc(),
+
+        // Editing (Important: Suspend Terminal)
+        Action::Edit => run_editor(terminal, app)?,
+    }
+    Ok(false)
+}
+
+/// Hands the terminal to the editor while it runs.
+fn run_editor<B: ratatui::backend::Backend + std::io::Write>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    // Restore terminal for editor
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    disable_raw_mode()?;
+    terminal.show_cursor()?;
+
+    // Run Editor
+    if let Err(e) = app.on_edit() {
+        app.state.status_message = Some(format!("Edit failed: {}", e));
+    }
+
+    // Re-enable terminal
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.hide_cursor()?;
+    terminal.clear()?;
+    // Force redraw immediately
+    terminal.draw(|f| ui::draw(f, &mut app.state))?;
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use htup_core::domain::{project::Project, response::Response};
+use htup_core::usecase::{stream_response::StreamingResponse, websocket_session::WebSocketSession};
+use htup_core::domain::{diff::ResponseDiff, finder::{FinderEntry, FinderHit}, search::SearchHit, environment::Environment, snapshot::SnapshotResult, graphql::GraphQLSchema, project::Project, request::Request, response::Response, trash::TrashEntry};
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppMode {
@@ -8,7 +12,16 @@ pub enum AppMode {
     CreatingRequest, // Step 1: Name
     CreatingRequestMethod, // Step 2: Method
     CreatingRequestBody, // Step 3: Body Type
-    Help,
+    WebSocket, // Live session of a WS request
+    Diff, // Comparison of two responses of the selected request
+    CreatingFolder,
+    Renaming, // New name or path of `AppState::target`
+    Duplicating, // Name of the copy of `AppState::target`
+    ConfirmingDelete, // y/n before `AppState::target` goes to the trash
+    Finder, // Ctrl-P fuzzy search over every project and request
+    Searching, // Query of a full-text search, typed in the input buffer
+    SearchResults, // Matching lines of the last full-text search
+    Help, // Key bindings overlay over `Help::previous`
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +30,87 @@ pub enum FocusPane {
     Requests,
 }
 
+/// A line of the Requests pane: at each level, folders first, then requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestRow<'a> {
+    Folder(&'a str),
+    Request(&'a str),
+}
+
+impl RequestRow<'_> {
+    pub fn path(&self) -> &str {
+        match self {
+            RequestRow::Folder(path) | RequestRow::Request(path) => path,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.path().matches('/').count()
+    }
+
+    pub fn name(&self) -> &str {
+        self.path().rsplit('/').next().unwrap_or_default()
+    }
+}
+
+/// A project, folder or request that a rename, duplicate or delete applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Project(String),
+    Folder(String),
+    Request(String),
+}
+
+impl Item {
+    /// The name of a project, the path of a folder or request.
+    pub fn path(&self) -> &str {
+        match self {
+            Item::Project(path) | Item::Folder(path) | Item::Request(path) => path,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Item::Project(_) => "project",
+            Item::Folder(_) => "folder",
+            Item::Request(_) => "request",
+        }
+    }
+}
+
+/// The folder containing a request or folder path, `""` at the top of the project.
+pub fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// The open fuzzy finder; the query is typed in `AppState::input_buffer`.
+pub struct Finder {
+    pub entries: Vec<FinderEntry>,
+    pub hits: Vec<FinderHit>,
+    pub with_details: bool, // Also match the method and URL
+}
+
+/// A full-text search over the request files.
+pub struct Search {
+    pub query: String, // Last query run
+    pub regex: bool,
+    pub hits: Vec<SearchHit>,
+}
+
+/// The overlay opened with `?`; closing it returns to `previous`.
+pub struct Help {
+    pub previous: AppMode,
+    pub query: String, // Filter on keys, descriptions and section titles
+    pub filtering: bool, // Typing the query
+    pub scroll: usize, // First visible line, clamped when drawn
+}
+
+impl Help {
+    pub fn new(previous: AppMode) -> Self {
+        Self { previous, query: String::new(), filtering: false, scroll: 0 }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PendingRequest {
     pub name: String,
@@ -30,10 +124,29 @@ pub struct AppState {
     pub projects: Vec<Project>,
     pub selected_project_index: usize,
     pub requests: Vec<String>,
-    pub selected_request_index: usize,
+    pub folders: Vec<String>,
+    pub collapsed: HashSet<String>, // Folders of the selected project shown closed
+    pub selected_request_index: usize, // Row of the Requests pane
+    pub target: Option<Item>, // Item being renamed, duplicated or deleted
+    pub trash: Vec<TrashEntry>, // Deleted this session, the last one is restored first
+    pub finder: Option<Finder>,
+    pub search: Option<Search>, // Kept after closing so `/` refines the last query
+    pub help: Option<Help>,
+    pub current_request: Option<Request>, // Preview of the selected request
     pub current_response: Option<Response>,
+    pub environment: Environment, // Variables and secrets of the selected project
+    pub graphql_schema: Option<GraphQLSchema>, // Cached introspection of the selected project
+    pub websocket: Option<WebSocketSession>, // Open session in WebSocket mode
+    pub streaming: Option<StreamingResponse>, // Response of a `@stream` request, shown while it arrives
+    pub diff: Option<(String, ResponseDiff)>, // Title and result of the last comparison
+    pub diff_side_by_side: bool,
+    pub diff_scroll: u16,
+    pub snapshot: Option<SnapshotResult>, // Current response against the approved snapshot
+    pub contract: Option<Vec<String>>, // OpenAPI violations of the current response, if its operation is known
     pub status_message: Option<String>,
     pub input_buffer: String,
+    pub keymap: Keymap, // Key bindings of the lists and response views
+    pub theme: Theme,
     
     // For Wizards
     pub pending_request: Option<PendingRequest>,
@@ -48,10 +161,29 @@ impl AppState {
             projects: Vec::new(),
             selected_project_index: 0,
             requests: Vec::new(),
+            folders: Vec::new(),
+            collapsed: HashSet::new(),
             selected_request_index: 0,
+            target: None,
+            trash: Vec::new(),
+            finder: None,
+            search: None,
+            help: None,
+            current_request: None,
             current_response: None,
+            environment: Environment::default(),
+            graphql_schema: None,
+            websocket: None,
+            streaming: None,
+            diff: None,
+            diff_side_by_side: false,
+            diff_scroll: 0,
+            snapshot: None,
+            contract: None,
             status_message: None,
             input_buffer: String::new(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
             pending_request: None,
             selection_index: 0,
         }
@@ -61,7 +193,69 @@ impl AppState {
         self.projects.get(self.selected_project_index)
     }
 
+    pub fn select_project(&mut self, name: &str) {
+        if let Some(index) = self.projects.iter().position(|p| p.name == name) {
+            self.selected_project_index = index;
+        }
+    }
+
+    /// The selected project, or the selected row when the Requests pane has the focus.
+    pub fn selected_item(&self) -> Option<Item> {
+        match self.focused_pane {
+            FocusPane::Projects => self.selected_project().map(|p| Item::Project(p.name.clone())),
+            FocusPane::Requests => self.selected_row().map(|row| match row {
+                RequestRow::Folder(path) => Item::Folder(path.to_string()),
+                RequestRow::Request(id) => Item::Request(id.to_string()),
+            }),
+        }
+    }
+
+    /// The visible rows of the Requests pane; the content of collapsed folders is left out.
+    pub fn request_rows(&self) -> Vec<RequestRow<'_>> {
+        let mut rows = Vec::new();
+        self.push_rows("", &mut rows);
+        rows
+    }
+
+    fn push_rows<'a>(&'a self, folder: &str, rows: &mut Vec<RequestRow<'a>>) {
+        for child in self.folders.iter().filter(|f| parent(f) == folder) {
+            rows.push(RequestRow::Folder(child));
+            if !self.collapsed.contains(child) {
+                self.push_rows(child, rows);
+            }
+        }
+        rows.extend(self.requests.iter().filter(|r| parent(r) == folder).map(|r| RequestRow::Request(r)));
+    }
+
+    pub fn selected_row(&self) -> Option<RequestRow<'_>> {
+        self.request_rows().get(self.selected_request_index).copied()
+    }
+
     pub fn selected_request_id(&self) -> Option<&str> {
-        self.requests.get(self.selected_request_index).map(|s| s.as_str())
+        match self.selected_row()? {
+            RequestRow::Request(id) => Some(id),
+            RequestRow::Folder(_) => None,
+        }
+    }
+
+    /// The selected folder, or the folder of the selected request: where new items go.
+    pub fn current_folder(&self) -> &str {
+        match self.selected_row() {
+            Some(RequestRow::Folder(path)) => path,
+            Some(RequestRow::Request(id)) => parent(id),
+            None => "",
+        }
+    }
+
+    /// Selects the row of a request or folder, opening the folders around it.
+    pub fn select_path(&mut self, path: &str) {
+        let mut folder = parent(path);
+        while !folder.is_empty() {
+            self.collapsed.remove(folder);
+            folder = parent(folder);
+        }
+        if let Some(index) = self.request_rows().iter().position(|row| row.path() == path) {
+            self.selected_request_index = index;
+        }
     }
 }
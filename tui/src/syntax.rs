@@ -0,0 +1,87 @@
+use crate::theme::Theme;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+/// Bodies starting like JSON are highlighted, anything else stays plain.
+pub fn is_json(body: &str) -> bool {
+    matches!(body.trim_start().chars().next(), Some('{' | '['))
+}
+
+/// The lines of a body, highlighted when it is JSON.
+pub fn body(body: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let json = is_json(body);
+    body.lines().map(|l| if json { json_line(l, theme) } else { Line::from(l.to_string()) }).collect()
+}
+
+/// Highlights one line of pretty-printed JSON; strings never span lines there.
+pub fn json_line(line: &str, theme: &Theme) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut end = line.len();
+                let mut escaped = false;
+                for (i, c) in chars.by_ref() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = i + 1;
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                // A string followed by `:` is a key
+                let is_key = line[end..].trim_start().starts_with(':');
+                let color = if is_key { theme.syntax_key } else { theme.syntax_string };
+                push(&mut spans, &mut plain, &line[start..end], color);
+            }
+            '-' | '0'..='9' => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                push(&mut spans, &mut plain, &line[start..end], theme.syntax_number);
+            }
+            'a'..='z' => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_ascii_alphabetic() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                match &line[start..end] {
+                    word @ ("true" | "false" | "null") => push(&mut spans, &mut plain, word, theme.syntax_literal),
+                    word => plain.push_str(word),
+                }
+            }
+            '{' | '}' | '[' | ']' | ',' | ':' => push(&mut spans, &mut plain, &line[start..start + 1], theme.syntax_punctuation),
+            c => plain.push(c),
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    Line::from(spans)
+}
+
+/// Ends the plain text before `text`, drawn in `color`.
+fn push(spans: &mut Vec<Span<'static>>, plain: &mut String, text: &str, color: Color) {
+    if !plain.is_empty() {
+        spans.push(Span::raw(std::mem::take(plain)));
+    }
+    spans.push(Span::styled(text.to_string(), Style::default().fg(color)));
+}
+
+/// `Name: value` of a request preview, with the name highlighted.
+pub fn header(name: &str, value: &str, theme: &Theme) -> Line<'static> {
+    Line::from(vec![Span::styled(name.to_string(), Style::default().fg(theme.syntax_header)), Span::raw(format!(": {}", value))])
+}
@@ -0,0 +1,433 @@
+use anyhow::{Context, Result};
+use ratatui::style::{Color, Modifier, Style};
+use std::path::Path;
+
+/// Colours of every part of the UI, including syntax highlighting of bodies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    pub background: Color,
+    pub border: Color,
+    pub border_focused: Color, // Pane with the focus
+    pub selected: Color, // Selected project or request
+    pub selected_bg: Color, // Selected line of the finder and search results
+    pub popup_border: Color,
+    pub input: Color, // Text being typed
+    pub matched: Color, // Characters a search matched
+    pub dim: Color,
+    pub accent: Color, // Section titles, event names, sent messages
+    pub success: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub added: Color,
+    pub removed: Color,
+    pub changed: Color,
+    pub status_bar: Color,
+    pub status_bar_bg: Color,
+    pub syntax_method: Color,
+    pub syntax_url: Color,
+    pub syntax_header: Color,
+    pub syntax_key: Color,
+    pub syntax_string: Color,
+    pub syntax_number: Color,
+    pub syntax_literal: Color, // `true`, `false` and `null`
+    pub syntax_punctuation: Color,
+}
+
+/// Names of the built-in themes.
+pub const BUILTIN: [&str; 3] = ["dark", "light", "high-contrast"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// For dark terminals, the colours htup always had.
+    pub fn dark() -> Self {
+        Self {
+            text: Color::Reset,
+            background: Color::Reset,
+            border: Color::White,
+            border_focused: Color::Green,
+            selected: Color::Yellow,
+            selected_bg: Color::DarkGray,
+            popup_border: Color::Cyan,
+            input: Color::Yellow,
+            matched: Color::Yellow,
+            dim: Color::DarkGray,
+            accent: Color::Cyan,
+            success: Color::Green,
+            error: Color::Red,
+            warning: Color::Yellow,
+            added: Color::Green,
+            removed: Color::Red,
+            changed: Color::Yellow,
+            status_bar: Color::White,
+            status_bar_bg: Color::Blue,
+            syntax_method: Color::Magenta,
+            syntax_url: Color::Reset,
+            syntax_header: Color::Cyan,
+            syntax_key: Color::Cyan,
+            syntax_string: Color::Green,
+            syntax_number: Color::Yellow,
+            syntax_literal: Color::Magenta,
+            syntax_punctuation: Color::Reset,
+        }
+    }
+
+    /// For light terminals: no yellow or cyan on white.
+    pub fn light() -> Self {
+        Self {
+            text: Color::Reset,
+            background: Color::Reset,
+            border: Color::DarkGray,
+            border_focused: Color::Blue,
+            selected: Color::Blue,
+            selected_bg: Color::Rgb(215, 215, 255),
+            popup_border: Color::Blue,
+            input: Color::Magenta,
+            matched: Color::Red,
+            dim: Color::DarkGray,
+            accent: Color::Blue,
+            success: Color::Rgb(0, 135, 0),
+            error: Color::Red,
+            warning: Color::Rgb(175, 95, 0),
+            added: Color::Rgb(0, 135, 0),
+            removed: Color::Red,
+            changed: Color::Rgb(175, 95, 0),
+            status_bar: Color::White,
+            status_bar_bg: Color::Blue,
+            syntax_method: Color::Magenta,
+            syntax_url: Color::Reset,
+            syntax_header: Color::Blue,
+            syntax_key: Color::Blue,
+            syntax_string: Color::Rgb(0, 135, 0),
+            syntax_number: Color::Rgb(175, 95, 0),
+            syntax_literal: Color::Magenta,
+            syntax_punctuation: Color::DarkGray,
+        }
+    }
+
+    /// Bright colours on black, whatever the terminal's own colours.
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Color::White,
+            background: Color::Black,
+            border: Color::White,
+            border_focused: Color::LightYellow,
+            selected: Color::LightYellow,
+            selected_bg: Color::Blue,
+            popup_border: Color::White,
+            input: Color::LightYellow,
+            matched: Color::LightMagenta,
+            dim: Color::Gray,
+            accent: Color::LightCyan,
+            success: Color::LightGreen,
+            error: Color::LightRed,
+            warning: Color::LightYellow,
+            added: Color::LightGreen,
+            removed: Color::LightRed,
+            changed: Color::LightYellow,
+            status_bar: Color::Black,
+            status_bar_bg: Color::White,
+            syntax_method: Color::LightMagenta,
+            syntax_url: Color::White,
+            syntax_header: Color::LightCyan,
+            syntax_key: Color::LightCyan,
+            syntax_string: Color::LightGreen,
+            syntax_number: Color::LightYellow,
+            syntax_literal: Color::LightMagenta,
+            syntax_punctuation: Color::White,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Reads `theme.toml` of the config directory, the dark theme without it:
+    ///
+    /// ```toml
+    /// theme = "light"          # built-in, or `themes/<name>.toml`
+    /// [colors]                 # overrides on top of it
+    /// border_focused = "#005f87"
+    /// ```
+    ///
+    /// User themes in `themes/` name their base in `extends` and may build on each other.
+    pub fn load(config_dir: &Path) -> Result<Self> {
+        let path = config_dir.join("theme.toml");
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        // theme.toml, then the user themes it `extends`, down to a built-in one
+        let mut files = vec![(path.clone(), read_table(&path)?)];
+        let mut theme = loop {
+            let (path, table) = files.last().context("No theme file")?;
+            let key = if files.len() == 1 { "theme" } else { "extends" };
+            let base = match table.get(key) {
+                Some(name) => name.as_str().with_context(|| format!("Invalid theme in {:?}: '{}' must be a theme name", path, key))?,
+                None => "dark",
+            };
+            if let Some(theme) = Self::builtin(base) {
+                break theme;
+            }
+            let next = config_dir.join("themes").join(format!("{}.toml", base));
+            if !next.exists() {
+                anyhow::bail!("Invalid theme in {:?}: unknown theme '{}', neither built-in ({}) nor {:?}", path, base, BUILTIN.join(", "), next);
+            }
+            if files.iter().any(|(p, _)| *p == next) {
+                anyhow::bail!("Invalid theme in {:?}: themes extend each other in a loop at '{}'", path, base);
+            }
+            files.push((next.clone(), read_table(&next)?));
+        };
+        for (path, table) in files.iter().rev() {
+            theme.apply(table).with_context(|| format!("Invalid theme in {:?}", path))?;
+        }
+        Ok(theme)
+    }
+
+    /// Overrides colours with the `[colors]` of a theme file.
+    fn apply(&mut self, table: &toml::Table) -> Result<()> {
+        let Some(colors) = table.get("colors") else {
+            return Ok(());
+        };
+        for (name, value) in colors.as_table().context("[colors] must be a table")? {
+            let value = value.as_str().with_context(|| format!("Colour of '{}' must be a string", name))?;
+            let slot = self.slot(name).with_context(|| format!("Unknown colour '{}'", name))?;
+            *slot = value.parse().map_err(|_| anyhow::anyhow!("Invalid colour '{}' for '{}', use a name, #rrggbb or 0-255", value, name))?;
+        }
+        Ok(())
+    }
+
+    fn slot(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "text" => &mut self.text,
+            "background" => &mut self.background,
+            "border" => &mut self.border,
+            "border_focused" => &mut self.border_focused,
+            "selected" => &mut self.selected,
+            "selected_bg" => &mut self.selected_bg,
+            "popup_border" => &mut self.popup_border,
+            "input" => &mut self.input,
+            "matched" => &mut self.matched,
+            "dim" => &mut self.dim,
+            "accent" => &mut self.accent,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "added" => &mut self.added,
+            "removed" => &mut self.removed,
+            "changed" => &mut self.changed,
+            "status_bar" => &mut self.status_bar,
+            "status_bar_bg" => &mut self.status_bar_bg,
+            "syntax_method" => &mut self.syntax_method,
+            "syntax_url" => &mut self.syntax_url,
+            "syntax_header" => &mut self.syntax_header,
+            "syntax_key" => &mut self.syntax_key,
+            "syntax_string" => &mut self.syntax_string,
+            "syntax_number" => &mut self.syntax_number,
+            "syntax_literal" => &mut self.syntax_literal,
+            "syntax_punctuation" => &mut self.syntax_punctuation,
+            _ => return None,
+        })
+    }
+
+    /// Replaces RGB and 256-palette colours by the closest of the 16 ANSI ones,
+    /// unless `COLORTERM` says the terminal has truecolor.
+    pub fn fit_terminal(self) -> Self {
+        let truecolor = std::env::var("COLORTERM").is_ok_and(|v| v == "truecolor" || v == "24bit");
+        if truecolor {
+            self
+        } else {
+            self.ansi()
+        }
+    }
+
+    fn ansi(mut self) -> Self {
+        for name in SLOTS {
+            if let Some(slot) = self.slot(name) {
+                *slot = to_ansi(*slot);
+            }
+        }
+        self
+    }
+
+    /// Text on the background, for panes and popups.
+    pub fn base(&self) -> Style {
+        Style::default().fg(self.text).bg(self.background)
+    }
+
+    pub fn bold(&self, color: Color) -> Style {
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    pub fn border(&self, focused: bool) -> Style {
+        Style::default().fg(if focused { self.border_focused } else { self.border })
+    }
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    text.parse().with_context(|| format!("Invalid theme in {:?}", path))
+}
+
+const SLOTS: [&str; 27] = [
+    "text",
+    "background",
+    "border",
+    "border_focused",
+    "selected",
+    "selected_bg",
+    "popup_border",
+    "input",
+    "matched",
+    "dim",
+    "accent",
+    "success",
+    "error",
+    "warning",
+    "added",
+    "removed",
+    "changed",
+    "status_bar",
+    "status_bar_bg",
+    "syntax_method",
+    "syntax_url",
+    "syntax_header",
+    "syntax_key",
+    "syntax_string",
+    "syntax_number",
+    "syntax_literal",
+    "syntax_punctuation",
+];
+
+/// The 16 ANSI colours as xterm draws them.
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn to_ansi(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(n) if n < 16 => return ANSI[n as usize].0,
+        Color::Indexed(n) if n < 232 => {
+            // 6x6x6 cube
+            let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+            let n = n - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        Color::Indexed(n) => {
+            let gray = 8 + (n - 232) * 10;
+            (gray, gray, gray)
+        }
+        color => return color,
+    };
+    let distance = |&(_, (r2, g2, b2)): &(Color, (u8, u8, u8))| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+    ANSI.iter().min_by_key(|c| distance(c)).map_or(color, |c| c.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A config directory holding `theme.toml` and the given user themes.
+    fn config(name: &str, theme: &str, themes: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("htup-theme-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("themes")).unwrap();
+        fs::write(dir.join("theme.toml"), theme).unwrap();
+        for (name, text) in themes {
+            fs::write(dir.join("themes").join(format!("{}.toml", name)), text).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_load_user_themes() {
+        let dir = config("load", "theme = \"ocean\"\n[colors]\nborder = \"#005f87\"\n", &[
+            ("ocean", "extends = \"deep\"\n[colors]\nborder = \"red\"\naccent = \"33\"\n"),
+            ("deep", "extends = \"light\"\n[colors]\naccent = \"blue\"\ndim = \"gray\"\n"),
+        ]);
+        let theme = Theme::load(&dir).unwrap();
+        assert_eq!(theme.border, Color::Rgb(0, 95, 135));
+        assert_eq!(theme.accent, Color::Indexed(33));
+        assert_eq!(theme.dim, Color::Gray);
+        assert_eq!(theme.selected_bg, Theme::light().selected_bg);
+        assert_eq!(Theme::load(&dir.join("missing")).unwrap(), Theme::dark());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_errors() {
+        // `a` and `b` extend each other
+        let looping = [("a", "extends = \"b\""), ("b", "extends = \"a\"")];
+        let cases = [
+            ("theme = \"solarized\"", "unknown theme 'solarized', neither built-in (dark, light, high-contrast) nor"),
+            ("theme = 1", "'theme' must be a theme name"),
+            ("theme = \"a\"", "themes extend each other in a loop at 'a'"),
+            ("[colors]\nborder = \"#12345\"", "Invalid colour '#12345' for 'border', use a name, #rrggbb or 0-255"),
+            ("[colors]\nshadow = \"red\"", "Unknown colour 'shadow'"),
+            ("[colors]\nborder = 1", "Colour of 'border' must be a string"),
+            ("colors = \"red\"", "[colors] must be a table"),
+        ];
+        for (i, (theme, message)) in cases.into_iter().enumerate() {
+            let dir = config(&format!("error-{}", i), theme, &looping);
+            let error = format!("{:#}", Theme::load(&dir).unwrap_err());
+            assert!(error.starts_with("Invalid theme in "), "{}", error);
+            assert!(error.contains(message), "{}", error);
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        assert_eq!(to_ansi(Color::Rgb(0, 135, 0)), Color::Green);
+        assert_eq!(to_ansi(Color::Rgb(175, 95, 0)), Color::Red);
+        assert_eq!(to_ansi(Color::Rgb(215, 215, 255)), Color::Gray);
+        assert_eq!(to_ansi(Color::Rgb(250, 250, 250)), Color::White);
+        // The first 16 of the palette are the ANSI colours, then a 6x6x6 cube and a gray ramp
+        assert_eq!(to_ansi(Color::Indexed(9)), Color::LightRed);
+        assert_eq!(to_ansi(Color::Indexed(21)), Color::Blue);
+        assert_eq!(to_ansi(Color::Indexed(226)), Color::LightYellow);
+        assert_eq!(to_ansi(Color::Indexed(232)), Color::Black);
+        assert_eq!(to_ansi(Color::Indexed(244)), Color::DarkGray);
+        for color in [Color::Reset, Color::Cyan, Color::LightBlue] {
+            assert_eq!(to_ansi(color), color);
+        }
+    }
+
+    #[test]
+    fn test_ansi_theme_keeps_named_colours() {
+        let theme = Theme::light().ansi();
+        assert_eq!(theme.success, Color::Green);
+        assert_eq!(theme.selected_bg, Color::Gray);
+        assert_eq!(theme.text, Color::Reset);
+        assert_eq!(Theme::dark().ansi(), Theme::dark());
+    }
+}
@@ -1,7 +1,20 @@
-use crate::state::{AppMode, AppState, FocusPane};
+use crate::state::{AppMode, AppState, FocusPane, RequestRow};
+use crate::help;
+use crate::keymap::Action;
+use crate::syntax;
+use crate::theme::Theme;
+use htup_core::domain::{
+    body::Body,
+    diff::{DiffLine, JsonChange, ResponseDiff},
+    snapshot::SnapshotResult,
+    websocket::Direction as MessageDirection,
+};
+use htup_core::usecase::stream_response::StreamState;
+use std::time::{SystemTime, UNIX_EPOCH};
+use htup_core::infra::parser::{format_auth, format_body};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
@@ -13,6 +26,7 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
         .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
         .split(f.size());
 
+    f.render_widget(Block::default().style(state.theme.base()), f.size());
     let main_area = chunks[0];
     let status_area = chunks[1];
 
@@ -32,12 +46,30 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
     draw_status_bar(f, state, status_area);
 
     // Draw Popups overlay
-    if match state.mode {
-        AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingRequestMethod | AppMode::CreatingRequestBody => true,
-        _ => false,
-    } {
+    if matches!(
+        state.mode,
+        AppMode::CreatingProject
+            | AppMode::CreatingRequest
+            | AppMode::CreatingRequestMethod
+            | AppMode::CreatingRequestBody
+            | AppMode::CreatingFolder
+            | AppMode::Renaming
+            | AppMode::Duplicating
+    ) {
         draw_input_popup(f, state, f.size());
     }
+    if state.mode == AppMode::ConfirmingDelete {
+        draw_confirm_delete(f, state, f.size());
+    }
+    if state.mode == AppMode::Finder {
+        draw_finder(f, state, f.size());
+    }
+    if matches!(state.mode, AppMode::Searching | AppMode::SearchResults) {
+        draw_search(f, state, f.size());
+    }
+    if state.mode == AppMode::Help {
+        draw_help(f, state, f.size());
+    }
 }
 
 fn draw_projects(f: &mut Frame, state: &AppState, area: Rect) {
@@ -47,15 +79,10 @@ fn draw_projects(f: &mut Frame, state: &AppState, area: Rect) {
         .map(|p| ListItem::new(p.name.as_str()))
         .collect();
 
-    let border_color = if let FocusPane::Projects = state.focused_pane {
-        Color::Green
-    } else {
-        Color::White
-    };
-
+    let theme = &state.theme;
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Projects").border_style(Style::default().fg(border_color)))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title("Projects").border_style(theme.border(state.focused_pane == FocusPane::Projects)))
+        .highlight_style(theme.bold(theme.selected))
         .highlight_symbol("> ");
 
     let mut list_state = ListState::default();
@@ -66,20 +93,22 @@ fn draw_projects(f: &mut Frame, state: &AppState, area: Rect) {
 
 fn draw_requests(f: &mut Frame, state: &AppState, area: Rect) {
     let items: Vec<ListItem> = state
-        .requests
+        .request_rows()
         .iter()
-        .map(|r| ListItem::new(r.as_str()))
+        .map(|row| {
+            let indent = "  ".repeat(row.depth());
+            match row {
+                RequestRow::Folder(path) if state.collapsed.contains(*path) => ListItem::new(format!("{}▸ {}/", indent, row.name())),
+                RequestRow::Folder(_) => ListItem::new(format!("{}▾ {}/", indent, row.name())),
+                RequestRow::Request(_) => ListItem::new(format!("{}{}", indent, row.name())),
+            }
+        })
         .collect();
 
-    let border_color = if let FocusPane::Requests = state.focused_pane {
-        Color::Green
-    } else {
-        Color::White
-    };
-
+    let theme = &state.theme;
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Requests").border_style(Style::default().fg(border_color)))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan))
+        .block(Block::default().borders(Borders::ALL).title("Requests").border_style(theme.border(state.focused_pane == FocusPane::Requests)))
+        .highlight_style(theme.bold(theme.selected))
         .highlight_symbol("> ");
 
     let mut list_state = ListState::default();
@@ -89,18 +118,66 @@ fn draw_requests(f: &mut Frame, state: &AppState, area: Rect) {
 }
 
 fn draw_content(f: &mut Frame, state: &AppState, area: Rect) {
-    let block = Block::default().borders(Borders::ALL).title("Content");
+    let theme = &state.theme;
+    let block = Block::default().borders(Borders::ALL).title("Content").border_style(theme.border(false));
     
     match state.mode {
+        AppMode::WebSocket => draw_websocket(f, state, area),
+        AppMode::Diff => draw_diff(f, state, area),
+        AppMode::ViewingResponse if state.streaming.is_some() => draw_streaming(f, state, area),
         AppMode::ViewingResponse => {
             if let Some(resp) = &state.current_response {
-                let status_line = Line::from(vec![
-                    Span::raw(format!("Status: {} {} | Time: {:?}", resp.status, resp.status_text, resp.latency)),
+                let status_color = if resp.status >= 400 { theme.error } else { theme.success };
+                let mut status_line = Line::from(vec![
+                    Span::raw("Status: "),
+                    Span::styled(format!("{} {}", resp.status, resp.status_text), theme.bold(status_color)),
+                    Span::raw(format!(" | Time: {:?}", resp.latency)),
                 ]);
+                match &state.snapshot {
+                    Some(SnapshotResult::Match) => status_line.spans.push(Span::styled(" | snapshot ok", Style::default().fg(theme.success))),
+                    Some(SnapshotResult::Mismatch { .. }) => {
+                        status_line.spans.push(Span::styled(" | snapshot MISMATCH", theme.bold(theme.error)))
+                    }
+                    _ => {}
+                }
+                match state.contract.as_deref() {
+                    Some([]) => status_line.spans.push(Span::styled(" | contract ok", Style::default().fg(theme.success))),
+                    Some(_) => status_line.spans.push(Span::styled(" | CONTRACT VIOLATED", theme.bold(theme.error))),
+                    None => {}
+                }
                 
-                let body_lines: Vec<Line> = resp.body.lines().map(Line::from).collect();
+                let body = state.environment.redact(&resp.body);
+                let body_lines = syntax::body(&body, theme);
                 let mut content = vec![status_line, Line::from("")];
+                // GraphQL reports errors in the body, usually with a 200 status
+                if !resp.graphql_errors.is_empty() {
+                    let error_style = Style::default().fg(theme.error);
+                    content.push(Line::from(Span::styled("GraphQL errors:", error_style.add_modifier(Modifier::BOLD))));
+                    for error in &resp.graphql_errors {
+                        content.push(Line::from(Span::styled(format!("  {}", state.environment.redact(error)), error_style)));
+                    }
+                    content.push(Line::from(""));
+                }
+                if let Some(violations) = state.contract.as_ref().filter(|v| !v.is_empty()) {
+                    let error_style = Style::default().fg(theme.error);
+                    content.push(Line::from(Span::styled("OpenAPI violations:", error_style.add_modifier(Modifier::BOLD))));
+                    for violation in violations {
+                        content.push(Line::from(Span::styled(format!("  {}", violation), error_style)));
+                    }
+                    content.push(Line::from(""));
+                }
                 content.extend(body_lines);
+                if !resp.trailers.is_empty() {
+                    content.push(Line::from(""));
+                    content.push(Line::from(Span::styled("Trailers:", theme.bold(theme.accent))));
+                    let mut trailers: Vec<_> = resp.trailers.iter().collect();
+                    trailers.sort();
+                    for (k, v) in trailers {
+                        let mut line = syntax::header(k, &state.environment.redact(v), theme);
+                        line.spans.insert(0, Span::raw("  "));
+                        content.push(line);
+                    }
+                }
 
                 let p = Paragraph::new(content).block(block).wrap(Wrap { trim: false });
                 f.render_widget(p, area);
@@ -109,44 +186,498 @@ fn draw_content(f: &mut Frame, state: &AppState, area: Rect) {
             }
         }
         _ => {
-            f.render_widget(Paragraph::new("Press <Enter> to run request\nPress <n> to create new Request\nPress <N> (shift+n) to create new Project").block(block), area);
+            let mut content = Vec::new();
+            if let Some(req) = &state.current_request {
+                // Resolve variables for the preview, but never show secret values
+                let preview = state.environment.redact_request(&state.environment.apply(req));
+                if let Some(auth) = &preview.auth {
+                    content.push(Line::from(format_auth(auth)));
+                }
+                content.push(Line::from(vec![
+                    Span::styled(preview.method.clone(), theme.bold(theme.syntax_method)),
+                    Span::raw(" "),
+                    Span::styled(preview.url.clone(), Style::default().fg(theme.syntax_url)),
+                ]));
+                let mut headers: Vec<_> = preview.headers.iter().collect();
+                headers.sort();
+                for (k, v) in headers {
+                    content.push(syntax::header(k, v, theme));
+                }
+                if let Some(body) = &preview.body {
+                    content.push(Line::from(""));
+                    content.extend(syntax::body(&format_body(body), theme));
+                }
+                if let (Some(Body::GraphQL { query, .. }), Some(schema)) = (&preview.body, &state.graphql_schema) {
+                    let unknown = schema.unknown_fields(query);
+                    if !unknown.is_empty() {
+                        content.push(Line::from(""));
+                        content.push(Line::from(Span::styled(
+                            format!("Unknown fields: {}", unknown.join(", ")),
+                            Style::default().fg(theme.error),
+                        )));
+                    }
+                }
+                content.push(Line::from(""));
+            }
+            let keys = &state.keymap;
+            content.push(Line::from(format!("Press <{}> to run request", keys.key_for(Action::Execute))));
+            if let Some(Body::GraphQL { .. }) = state.current_request.as_ref().and_then(|r| r.body.as_ref()) {
+                content.push(Line::from(format!("Press <{}> to introspect the GraphQL schema", keys.key_for(Action::Introspect))));
+            }
+            content.push(Line::from(format!("Press <{}> to create new Request", keys.key_for(Action::NewRequest))));
+            content.push(Line::from(format!("Press <{}> to create new Project", keys.key_for(Action::NewProject))));
+            content.push(Line::from(format!("Press <{}> for all keys", keys.key_for(Action::Help))));
+            f.render_widget(Paragraph::new(content).block(block).wrap(Wrap { trim: false }), area);
         }
     }
 }
 
-fn draw_status_bar(f: &mut Frame, state: &AppState, area: Rect) {
-    let msg = state.status_message.as_deref().unwrap_or("Ready");
-    let p = Paragraph::new(msg).style(Style::default().bg(Color::Blue).fg(Color::White));
+fn draw_websocket(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(session) = &state.websocket else {
+        return;
+    };
+    let theme = &state.theme;
+    let predefined_height = if session.predefined.is_empty() { 0 } else { session.predefined.len().min(5) as u16 + 2 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(predefined_height), Constraint::Length(3)].as_ref())
+        .split(area);
+
+    let title = match &session.closed {
+        Some(reason) => format!("WebSocket (closed: {})", state.environment.redact(reason)),
+        None => "WebSocket (connected)".to_string(),
+    };
+    let lines: Vec<Line> = session
+        .log
+        .iter()
+        .flat_map(|message| {
+            let (arrow, color) = match message.direction {
+                MessageDirection::Sent => ("->", theme.accent),
+                MessageDirection::Received => ("<-", theme.success),
+            };
+            let text = state.environment.redact(&message.text);
+            let mut lines = text.lines();
+            let first = Line::from(vec![
+                Span::styled(format!("[{}] ", format_time(message.timestamp)), Style::default().fg(theme.dim)),
+                Span::styled(format!("{} ", arrow), theme.bold(color)),
+                Span::raw(lines.next().unwrap_or("").to_string()),
+            ]);
+            std::iter::once(first)
+                .chain(lines.map(|l| Line::from(format!("                  {}", l))))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    // Keep the latest messages in view
+    let visible = chunks[0].height.saturating_sub(2) as usize;
+    let scroll = lines.len().saturating_sub(visible) as u16;
+    let log = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title).border_style(theme.border(false)))
+        .scroll((scroll, 0));
+    f.render_widget(log, chunks[0]);
+
+    if !session.predefined.is_empty() {
+        let items: Vec<ListItem> = session
+            .predefined
+            .iter()
+            .map(|m| ListItem::new(state.environment.redact(&m.replace('\n', " "))))
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Predefined (<Up>/<Down>, <Tab> to edit)").border_style(theme.border(false)))
+            .highlight_style(theme.bold(theme.selected))
+            .highlight_symbol("> ");
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selection_index));
+        f.render_stateful_widget(list, chunks[1], &mut list_state);
+    }
+
+    let input = Paragraph::new(state.input_buffer.as_str())
+        .style(Style::default().fg(theme.input))
+        .block(Block::default().borders(Borders::ALL).title("Message (<Enter> to send, <Esc> to disconnect)").border_style(theme.border(true)));
+    f.render_widget(input, chunks[2]);
+}
+
+fn draw_streaming(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(streaming) = &state.streaming else {
+        return;
+    };
+    let theme = &state.theme;
+    let progress = match &streaming.state {
+        StreamState::Streaming => format!("streaming... <{}> to cancel", state.keymap.key_for(Action::Back)),
+        StreamState::Finished(elapsed) => format!("done in {:?}", elapsed),
+        StreamState::Cancelled => "cancelled".to_string(),
+        StreamState::Failed(e) => format!("failed: {}", e),
+    };
+    let mut content = vec![
+        Line::from(format!("Status: {} {} | {}", streaming.status(), streaming.status_text(), progress)),
+        Line::from(""),
+    ];
+
+    if streaming.is_event_stream() {
+        let dim = Style::default().fg(theme.dim);
+        for event in &streaming.events {
+            let mut head = vec![Span::styled(format!("[+{:.3}s] ", event.elapsed.as_secs_f64()), dim)];
+            head.push(Span::styled(
+                event.event.clone().unwrap_or_else(|| "message".to_string()),
+                theme.bold(theme.accent),
+            ));
+            if let Some(id) = &event.id {
+                head.push(Span::styled(format!(" id={}", id), dim));
+            }
+            if let Some(retry) = event.retry {
+                head.push(Span::styled(format!(" retry={}ms", retry), dim));
+            }
+            content.push(Line::from(head));
+            let data = state.environment.redact(&event.data);
+            for mut line in syntax::body(&data, theme) {
+                line.spans.insert(0, Span::raw("  "));
+                content.push(line);
+            }
+        }
+    } else {
+        if let Some((elapsed, _)) = streaming.chunks.last() {
+            content.push(Line::from(Span::styled(
+                format!("{} chunks, last at +{:.3}s", streaming.chunks.len(), elapsed.as_secs_f64()),
+                Style::default().fg(theme.dim),
+            )));
+            content.push(Line::from(""));
+        }
+        let body = state.environment.redact(&streaming.body);
+        // Highlighting only once it is whole, half a JSON document is not
+        if streaming.is_streaming() {
+            content.extend(body.lines().map(|l| Line::from(l.to_string())));
+        } else {
+            content.extend(syntax::body(&body, theme));
+        }
+    }
+
+    // Follow the tail while data keeps arriving
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = content.len().saturating_sub(visible) as u16;
+    let p = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title("Content").border_style(theme.border(false)))
+        .scroll((scroll, 0));
     f.render_widget(p, area);
 }
 
+fn draw_diff(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some((title, diff)) = &state.diff else {
+        return;
+    };
+    let theme = &state.theme;
+    let added = Style::default().fg(theme.added);
+    let removed = Style::default().fg(theme.removed);
+
+    if state.diff_side_by_side {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        let mut rows = Vec::new();
+        if headers_changed(diff) {
+            rows.extend(side_by_side(&diff.headers));
+            rows.push((None, None));
+        }
+        rows.extend(side_by_side(&diff.lines));
+        let (left, right): (Vec<Line>, Vec<Line>) = rows
+            .into_iter()
+            .map(|(old, new)| {
+                let line = |text: Option<&DiffLine>, style| match text {
+                    Some(DiffLine::Same(t)) => Line::from(t.clone()),
+                    Some(DiffLine::Added(t) | DiffLine::Removed(t)) => Line::from(Span::styled(t.clone(), style)),
+                    None => Line::from(""),
+                };
+                (line(old, removed), line(new, added))
+            })
+            .unzip();
+        let old_title = format!("{} | old: {}", title, diff.status.0);
+        for (lines, column, title) in [(left, columns[0], old_title), (right, columns[1], format!("new: {}", diff.status.1))] {
+            let block = Block::default().borders(Borders::ALL).title(title).border_style(theme.border(false));
+            let p = Paragraph::new(lines).block(block).scroll((state.diff_scroll, 0));
+            f.render_widget(p, column);
+        }
+        return;
+    }
+
+    let status_style = if diff.status.0 == diff.status.1 { Style::default() } else { Style::default().fg(theme.warning) };
+    let mut content = vec![Line::from(Span::styled(format!("Status: {} -> {}", diff.status.0, diff.status.1), status_style))];
+    if !diff.changes.is_empty() {
+        content.push(Line::from(""));
+        for change in &diff.changes {
+            content.push(match change {
+                JsonChange::Added { path, value } => Line::from(Span::styled(format!("+ {}: {}", path, value), added)),
+                JsonChange::Removed { path, value } => Line::from(Span::styled(format!("- {}: {}", path, value), removed)),
+                JsonChange::Changed { path, old, new } => {
+                    Line::from(Span::styled(format!("~ {}: {} -> {}", path, old, new), Style::default().fg(theme.changed)))
+                }
+            });
+        }
+    }
+    let unified = |line: &DiffLine| match line {
+        DiffLine::Same(text) => Line::from(format!("  {}", text)),
+        DiffLine::Added(text) => Line::from(Span::styled(format!("+ {}", text), added)),
+        DiffLine::Removed(text) => Line::from(Span::styled(format!("- {}", text), removed)),
+    };
+    if headers_changed(diff) {
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled("Headers", theme.bold(theme.accent))));
+        content.extend(diff.headers.iter().map(unified));
+    }
+    content.push(Line::from(""));
+    content.extend(diff.lines.iter().map(unified));
+    let p = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title(format!("Diff: {}", title)).border_style(theme.border(false)))
+        .scroll((state.diff_scroll, 0));
+    f.render_widget(p, area);
+}
+
+fn headers_changed(diff: &ResponseDiff) -> bool {
+    diff.headers.iter().any(|l| !matches!(l, DiffLine::Same(_)))
+}
+
+/// Pairs up the lines of a diff into rows, putting removed and added runs next to each other.
+fn side_by_side(lines: &[DiffLine]) -> Vec<(Option<&DiffLine>, Option<&DiffLine>)> {
+    let mut rows = Vec::new();
+    let mut lines = lines.iter().peekable();
+    while let Some(line) = lines.next() {
+        if let DiffLine::Same(_) = line {
+            rows.push((Some(line), Some(line)));
+            continue;
+        }
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        let mut next = Some(line);
+        while let Some(line) = next {
+            match line {
+                DiffLine::Added(_) => added.push(line),
+                _ => removed.push(line),
+            }
+            next = lines.next_if(|l| !matches!(l, DiffLine::Same(_)));
+        }
+        for i in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(i).copied(), added.get(i).copied()));
+        }
+    }
+    rows
+}
+
+/// Formats a time as `HH:MM:SS.mmm` (UTC).
+fn format_time(time: SystemTime) -> String {
+    let millis = time.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+    let secs = millis / 1000 % 86_400;
+    format!("{:02}:{:02}:{:02}.{:03}", secs / 3_600, secs % 3_600 / 60, secs % 60, millis % 1000)
+}
+
+fn draw_status_bar(f: &mut Frame, state: &AppState, area: Rect) {
+    let msg = match state.keymap.pending() {
+        // Middle of a multi-key binding
+        Some(keys) => format!("{} …", keys),
+        None => state.environment.redact(state.status_message.as_deref().unwrap_or("Ready")),
+    };
+    let style = Style::default().bg(state.theme.status_bar_bg).fg(state.theme.status_bar);
+    let mut hints = Vec::new();
+    for (keys, label) in help::hints(state) {
+        hints.push(Span::styled(format!(" <{}>", keys), style.add_modifier(Modifier::BOLD)));
+        hints.push(Span::styled(format!(" {} ", label), style));
+    }
+    let hints = Line::from(hints);
+    // The message wins when both do not fit
+    let width = (hints.width() as u16).min(area.width.saturating_sub(msg.chars().count() as u16 + 1));
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(width)])
+        .split(area);
+    f.render_widget(Paragraph::new(msg).style(style), chunks[0]);
+    f.render_widget(Paragraph::new(hints).style(style).alignment(ratatui::layout::Alignment::Right), chunks[1]);
+}
+
+fn draw_help(f: &mut Frame, state: &mut AppState, area: Rect) {
+    let Some(help) = state.help.as_mut() else {
+        return;
+    };
+    let theme = &state.theme;
+    let sections = help::filter(help::sections(&state.keymap), &help.query);
+    let key_width = sections.iter().flat_map(|s| &s.entries).map(|e| e.keys.chars().count()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for section in &sections {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(section.title, theme.bold(theme.accent))));
+        for entry in &section.entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:width$}  ", entry.keys, width = key_width), Style::default().fg(theme.input)),
+                Span::raw(entry.description),
+            ]));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("No matching keys", Style::default().fg(theme.dim))));
+    }
+
+    let title = match (help.filtering, help.query.is_empty()) {
+        (true, _) => format!("Help, filter: {}_", help.query),
+        (false, false) => format!("Help, filter: {}", help.query),
+        (false, true) => "Help".to_string(),
+    };
+    let block = Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.popup_border));
+    let area = centered_rect(70, 80, area);
+    // Stop at the last page instead of scrolling past it
+    let height = block.inner(area).height as usize;
+    help.scroll = help.scroll.min(lines.len().saturating_sub(height));
+    let scroll = help.scroll as u16;
+    clear(f, theme, area);
+    f.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+fn draw_finder(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(finder) = &state.finder else {
+        return;
+    };
+    let theme = &state.theme;
+    let scope = if finder.with_details { "names, methods and URLs" } else { "names" };
+    let title = format!("Find in {} ({}/{}, <Tab> to switch)", scope, finder.hits.len(), finder.entries.len());
+    let block = Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.popup_border));
+    let area = centered_rect(70, 60, area);
+    clear(f, theme, area);
+    f.render_widget(block.clone(), area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(block.inner(area));
+    let input = Paragraph::new(format!("> {}", state.input_buffer)).style(Style::default().fg(theme.input));
+    f.render_widget(input, chunks[0]);
+
+    let matched = theme.bold(theme.matched);
+    let items: Vec<ListItem> = finder
+        .hits
+        .iter()
+        .filter_map(|hit| {
+            let entry = finder.entries.get(hit.index)?;
+            let mut spans = highlight(&entry.label(), &hit.label_positions, Style::default(), matched);
+            if let Some(detail) = &entry.detail {
+                spans.push(Span::raw("  "));
+                spans.extend(highlight(detail, &hit.detail_positions, Style::default().fg(theme.dim), matched));
+            }
+            Some(ListItem::new(Line::from(spans)))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().bg(theme.selected_bg)).highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selection_index));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn draw_search(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(search) = &state.search else {
+        return;
+    };
+    let theme = &state.theme;
+    let area = centered_rect(80, 70, area);
+    clear(f, theme, area);
+
+    if state.mode == AppMode::Searching {
+        let kind = if search.regex { "regex" } else { "text" };
+        let title = format!("Search request files ({}, <Tab> to switch)", kind);
+        let block = Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.popup_border));
+        let input = Paragraph::new(format!("> {}", state.input_buffer)).style(Style::default().fg(theme.input)).block(block);
+        f.render_widget(input, area);
+        return;
+    }
+
+    let keys = &state.keymap;
+    let title = format!(
+        "{} lines matching '{}' (<{}> select, <{}> edit at line, <{}> search again)",
+        search.hits.len(),
+        search.query,
+        keys.key_for(Action::Execute),
+        keys.key_for(Action::Edit),
+        keys.key_for(Action::Search)
+    );
+    let block = Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.popup_border));
+    let matched = theme.bold(theme.matched);
+    let items: Vec<ListItem> = search
+        .hits
+        .iter()
+        .map(|hit| {
+            let location = format!("{}/{}:{}  ", hit.project.name, hit.request_id, hit.line);
+            let mut spans = vec![Span::styled(location, Style::default().fg(theme.accent))];
+            let mut end = 0;
+            for range in &hit.ranges {
+                spans.push(Span::raw(hit.text[end..range.start].to_string()));
+                spans.push(Span::styled(hit.text[range.clone()].to_string(), matched));
+                end = range.end;
+            }
+            spans.push(Span::raw(hit.text[end..].to_string()));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_style(Style::default().bg(theme.selected_bg)).highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.selection_index));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// One span per character, styled `matched` at the given char indices.
+fn highlight(text: &str, positions: &[usize], style: Style, matched: Style) -> Vec<Span<'static>> {
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| Span::styled(c.to_string(), if positions.contains(&i) { matched } else { style }))
+        .collect()
+}
+
+fn draw_confirm_delete(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(target) = &state.target else {
+        return;
+    };
+    let theme = &state.theme;
+    let block = Block::default().borders(Borders::ALL).title("Delete").border_style(Style::default().fg(theme.error));
+    let area = centered_rect(50, 20, area);
+    let text = vec![
+        Line::from(format!("Delete {} '{}'?", target.kind(), target.path())),
+        Line::from(format!("It is moved to the trash and can be restored with '{}'.", state.keymap.key_for(Action::Undo))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y]", theme.bold(theme.error)),
+            Span::raw(" Delete  "),
+            Span::styled("[n]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" Cancel"),
+        ]),
+    ];
+    clear(f, theme, area);
+    f.render_widget(Paragraph::new(text).block(block).wrap(Wrap { trim: false }), area);
+}
+
 fn draw_input_popup(f: &mut Frame, state: &AppState, area: Rect) {
     let title = match state.mode {
         AppMode::CreatingProject => "Create New Project",
         AppMode::CreatingRequest => "Request Name",
         AppMode::CreatingRequestMethod => "Select Method",
         AppMode::CreatingRequestBody => "Select Body Type",
+        AppMode::CreatingFolder => "New Folder",
+        AppMode::Renaming => "Rename / Move (path)",
+        AppMode::Duplicating => "Duplicate As",
         _ => "",
     };
 
-    let block = Block::default().borders(Borders::ALL).title(title);
+    let theme = &state.theme;
+    let block = Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.popup_border));
     let area = centered_rect(60, 40, area); // Increased height for lists
-    f.render_widget(ratatui::widgets::Clear, area); // Clear background
+    clear(f, theme, area);
     f.render_widget(block.clone(), area);
 
     let inner_area = block.inner(area);
 
     match state.mode {
-        AppMode::CreatingProject | AppMode::CreatingRequest => {
+        AppMode::CreatingProject | AppMode::CreatingRequest | AppMode::CreatingFolder | AppMode::Renaming | AppMode::Duplicating => {
              let input = Paragraph::new(state.input_buffer.as_str())
-                .style(Style::default().fg(Color::Yellow));
+                .style(Style::default().fg(theme.input));
             f.render_widget(input, inner_area);
         }
         AppMode::CreatingRequestMethod => {
-            let methods = vec!["GET", "POST", "PUT", "DELETE", "PATCH"];
+            let methods = ["GET", "POST", "PUT", "DELETE", "PATCH"];
             let items: Vec<ListItem> = methods.iter().map(|m| ListItem::new(*m)).collect();
             let list = List::new(items)
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))
+                .highlight_style(theme.bold(theme.selected))
                 .highlight_symbol("> ");
             
             let mut list_state = ListState::default();
@@ -154,10 +685,10 @@ fn draw_input_popup(f: &mut Frame, state: &AppState, area: Rect) {
             f.render_stateful_widget(list, inner_area, &mut list_state);
         }
         AppMode::CreatingRequestBody => {
-            let types = vec!["Empty", "JSON"];
+            let types = ["Empty", "JSON", "Form", "Multipart", "GraphQL"];
             let items: Vec<ListItem> = types.iter().map(|t| ListItem::new(*t)).collect();
             let list = List::new(items)
-                .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))
+                .highlight_style(theme.bold(theme.selected))
                 .highlight_symbol("> ");
             
             let mut list_state = ListState::default();
@@ -168,6 +699,12 @@ fn draw_input_popup(f: &mut Frame, state: &AppState, area: Rect) {
     }
 }
 
+/// Empties the area of a popup down to the theme's background.
+fn clear(f: &mut Frame, theme: &Theme, area: Rect) {
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(Block::default().style(theme.base()), area);
+}
+
 /// Helper to center a rect
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()